@@ -0,0 +1,103 @@
+//! The gdb-style `disassemble` command.
+
+use anyhow::{Result, anyhow};
+use std::fmt::Write;
+
+use crate::disassemble::Instruction;
+use crate::expression::Expression;
+use crate::process::Process;
+use crate::process::stoppoint::VirtualAddress;
+
+/// Number of instructions shown when the address isn't in a known function.
+const DEFAULT_COUNT: usize = 16;
+
+#[derive(Clone, Debug)]
+pub enum DisassembleCommand {
+    /// `disassemble [address]`: the function containing the address (the PC by
+    /// default), or a few instructions from it if there's no such function.
+    Function(Option<Expression>),
+    /// `disassemble <start>,<end>`: the instructions starting in `[start, end)`.
+    Range { start: Expression, end: Expression },
+    /// `disassemble <start>,+<len>`
+    Length { start: Expression, len: Expression },
+}
+
+impl DisassembleCommand {
+    /// Parse the arguments of `disassemble`. Expressions may contain spaces,
+    /// e.g. `disassemble $pc - 8, +16`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        if args.is_empty() {
+            return Ok(DisassembleCommand::Function(None));
+        }
+        let args = args.join(" ");
+        let Some((start, end)) = args.split_once(',') else {
+            let address = Expression::parse(&args)?;
+            return Ok(DisassembleCommand::Function(Some(address)));
+        };
+
+        let start = Expression::parse(start.trim())?;
+        match end.trim().strip_prefix('+') {
+            Some(len) => Ok(DisassembleCommand::Length {
+                start,
+                len: Expression::parse(len.trim())?,
+            }),
+            None => Ok(DisassembleCommand::Range {
+                start,
+                end: Expression::parse(end.trim())?,
+            }),
+        }
+    }
+
+    /// Run the command against the inferior, returning the text to show the user.
+    pub fn execute(&self, process: &Process) -> Result<String> {
+        let instructions = match self {
+            DisassembleCommand::Function(address) => {
+                let address = match address {
+                    Some(address) => process.evaluate_address(address)?,
+                    None => process
+                        .pc()
+                        .ok_or_else(|| anyhow!("The process is not stopped"))?,
+                };
+                match process.function_bounds(address) {
+                    Some((start, end)) if start < end => process.disassemble_range(start, end)?,
+                    _ => process.disassemble(address, DEFAULT_COUNT)?,
+                }
+            }
+            DisassembleCommand::Range { start, end } => {
+                let start = process.evaluate_address(start)?;
+                let end = process.evaluate_address(end)?;
+                process.disassemble_range(start, end)?
+            }
+            DisassembleCommand::Length { start, len } => {
+                let start = process.evaluate_address(start)?;
+                let len = len.evaluate(process)?;
+                process.disassemble_range(start, start.offset(len as i64))?
+            }
+        };
+        Ok(format_instructions(process, &instructions))
+    }
+}
+
+/// One instruction per line, with the current instruction marked by `=>`:
+///
+/// ```text
+/// => 0x555555559a44 <main+4>:  mov rbp, rsp
+/// ```
+fn format_instructions(process: &Process, instructions: &[Instruction]) -> String {
+    let pc = process.pc();
+    let mut out = String::new();
+    for inst in instructions {
+        let marker = if Some(inst.address) == pc { "=>" } else { "  " };
+        let symbol = symbol_suffix(process, inst.address);
+        let text = inst.annotated_text(|target| process.symbolize(target));
+        let _ = writeln!(out, "{marker} {}{symbol}:  {text}", inst.address);
+    }
+    out.trim_end().to_string()
+}
+
+fn symbol_suffix(process: &Process, address: VirtualAddress) -> String {
+    process
+        .symbolize(address)
+        .map(|symbol| format!(" <{symbol}>"))
+        .unwrap_or_default()
+}
@@ -0,0 +1,103 @@
+//! The `handle` command, for how the inferior's signals are dealt with.
+
+use anyhow::{Result, anyhow};
+use nix::sys::signal::Signal;
+
+use crate::debugger::info::format_signal_rows;
+use crate::process::Process;
+use crate::process::signals::parse_signal;
+
+const USAGE: &str = "usage: handle <signal>... [stop|nostop] [print|noprint] [pass|nopass]";
+
+/// A change to how a signal is handled, as with gdb: stopping implies
+/// printing, and not printing implies not stopping.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SignalAction {
+    Stop,
+    NoStop,
+    Print,
+    NoPrint,
+    Pass,
+    NoPass,
+}
+
+impl SignalAction {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "stop" => Some(SignalAction::Stop),
+            "nostop" => Some(SignalAction::NoStop),
+            "print" => Some(SignalAction::Print),
+            "noprint" => Some(SignalAction::NoPrint),
+            "pass" | "noignore" => Some(SignalAction::Pass),
+            "nopass" | "ignore" => Some(SignalAction::NoPass),
+            _ => None,
+        }
+    }
+}
+
+/// `handle SIGUSR1 nostop noprint`: change how signals are handled, then show
+/// how they are. With no actions, it just shows them.
+#[derive(Clone, Debug)]
+pub struct HandleCommand {
+    signals: Vec<Signal>,
+    actions: Vec<SignalAction>,
+}
+
+impl HandleCommand {
+    /// Parse the arguments of `handle ...`: the signals, then the actions.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let split = args
+            .iter()
+            .position(|arg| SignalAction::parse(&arg.to_lowercase()).is_some())
+            .unwrap_or(args.len());
+        let (signals, actions) = args.split_at(split);
+        if signals.is_empty() {
+            return Err(anyhow!(USAGE));
+        }
+        let signals = signals
+            .iter()
+            .map(|s| parse_signal(s))
+            .collect::<Result<Vec<_>>>()?;
+        let actions = actions
+            .iter()
+            .map(|a| {
+                SignalAction::parse(&a.to_lowercase())
+                    .ok_or_else(|| anyhow!("Unknown action {a:?}; {USAGE}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { signals, actions })
+    }
+
+    /// Apply the actions, returning the new dispositions to show the user.
+    pub fn execute(&self, process: &mut Process) -> Result<String> {
+        for signal in &self.signals {
+            let mut disposition = process.signals().get(*signal);
+            for action in &self.actions {
+                match action {
+                    SignalAction::Stop => {
+                        disposition.stop = true;
+                        disposition.print = true;
+                    }
+                    SignalAction::NoStop => disposition.stop = false,
+                    SignalAction::Print => disposition.print = true,
+                    SignalAction::NoPrint => {
+                        disposition.print = false;
+                        disposition.stop = false;
+                    }
+                    SignalAction::Pass => disposition.pass = true,
+                    SignalAction::NoPass => disposition.pass = false,
+                }
+            }
+            if !self.actions.is_empty() {
+                process.set_signal_disposition(*signal, disposition)?;
+            }
+        }
+        let table = process.signals();
+        let rows: Vec<_> = self
+            .signals
+            .iter()
+            .map(|signal| (*signal, table.get(*signal)))
+            .collect();
+        Ok(format_signal_rows(&rows))
+    }
+}
@@ -0,0 +1,273 @@
+//! The `info` commands, describing what the debugger knows about.
+
+use anyhow::{Result, anyhow};
+use nix::sys::signal::Signal;
+use std::fmt::Write;
+
+use crate::process::Process;
+use crate::process::signals::{SignalDisposition, parse_signal};
+use crate::process::stoppoint::VirtualAddress;
+use crate::process::stoppoint::breakpoint::BreakpointId;
+use crate::process::stoppoint::hardware::HardwareMode;
+
+#[derive(Clone, Debug)]
+pub enum InfoCommand {
+    /// `info breakpoints`: every breakpoint, watchpoint and catchpoint.
+    Breakpoints,
+    /// `info signals [<signal>]`: how every signal (or just the one) is
+    /// handled.
+    Signals(Option<Signal>),
+}
+
+impl InfoCommand {
+    /// Parse the arguments of `info ...`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        match args {
+            [what] if matches!(what.as_str(), "breakpoints" | "break" | "b") => {
+                Ok(InfoCommand::Breakpoints)
+            }
+            [what, signal @ ..] if matches!(what.as_str(), "signals" | "signal") => match signal {
+                [] => Ok(InfoCommand::Signals(None)),
+                [signal] => Ok(InfoCommand::Signals(Some(parse_signal(signal)?))),
+                _ => Err(anyhow!("usage: info signals [<signal>]")),
+            },
+            _ => Err(anyhow!("usage: info breakpoints | info signals [<signal>]")),
+        }
+    }
+
+    /// Run the command, returning the text to show the user.
+    pub fn execute(&self, process: &Process) -> Result<String> {
+        match self {
+            InfoCommand::Breakpoints => {
+                let rows = breakpoint_rows(process);
+                if rows.is_empty() {
+                    return Ok("No breakpoints or watchpoints".to_string());
+                }
+                Ok(format_breakpoint_rows(&rows))
+            }
+            InfoCommand::Signals(signal) => {
+                let table = process.signals();
+                let rows: Vec<_> = match signal {
+                    Some(signal) => vec![(*signal, table.get(*signal))],
+                    None => table.iter().collect(),
+                };
+                Ok(format_signal_rows(&rows))
+            }
+        }
+    }
+}
+
+/// A line of the breakpoint list: a breakpoint, watchpoint or catchpoint, or
+/// one of the locations of a breakpoint that resolved to several.
+#[derive(Clone, Debug)]
+pub struct BreakpointRow {
+    /// The number shown to the user, e.g. `2`, or `2.1` for a location.
+    pub number: String,
+    /// The breakpoint, watchpoint or catchpoint the row belongs to.
+    pub id: BreakpointId,
+    /// e.g. `breakpoint`, or `hw watchpoint`. Empty for a location.
+    pub kind: &'static str,
+    /// Whether it uses one of the debug registers.
+    pub hardware: bool,
+    pub enabled: bool,
+    /// The address, or `<PENDING>` / `<MULTIPLE>`. Empty for a catchpoint.
+    pub address: String,
+    pub hits: u64,
+    /// Number of upcoming hits that won't stop the inferior.
+    pub ignore_count: u64,
+    /// The condition for stopping, if there is one.
+    pub condition: Option<String>,
+    /// Commands run on each hit.
+    pub commands: Vec<String>,
+    /// Where the breakpoint is, e.g. `main+4 at src/main.rs:12`.
+    pub what: String,
+}
+
+impl BreakpointRow {
+    /// Whether this is one of the locations of a breakpoint, rather than the
+    /// breakpoint itself.
+    pub fn is_location(&self) -> bool {
+        self.kind.is_empty()
+    }
+
+    /// Whether this is a syscall catchpoint, which has no address.
+    pub fn is_catchpoint(&self) -> bool {
+        self.kind == "catchpoint"
+    }
+}
+
+/// The breakpoint list, in the order of the breakpoint, watchpoint and
+/// catchpoint numbers.
+pub fn breakpoint_rows(process: &Process) -> Vec<BreakpointRow> {
+    let mut rows = Vec::new();
+    for breakpoint in process.breakpoints() {
+        let sites: Vec<_> = breakpoint
+            .sites()
+            .iter()
+            .filter_map(|id| process.breakpoint_site(id))
+            .collect();
+        let mut row = BreakpointRow {
+            number: breakpoint.id().to_string(),
+            id: breakpoint.id(),
+            kind: match breakpoint.is_hardware() {
+                true => "hw breakpoint",
+                false => "breakpoint",
+            },
+            hardware: breakpoint.is_hardware(),
+            enabled: breakpoint.is_enabled(),
+            address: String::new(),
+            hits: sites.iter().map(|site| site.hit_count()).sum(),
+            ignore_count: breakpoint.policy().ignore_count(),
+            condition: breakpoint.policy().condition().map(|c| c.to_string()),
+            commands: breakpoint.policy().commands().to_vec(),
+            what: breakpoint.location().to_string(),
+        };
+        match sites.as_slice() {
+            [] => {
+                row.address = "<PENDING>".to_string();
+                rows.push(row);
+            }
+            [site] => {
+                row.address = site.address().to_string();
+                row.what = describe_address(process, site.address());
+                rows.push(row);
+            }
+            _ => {
+                row.address = "<MULTIPLE>".to_string();
+                let header = row.clone();
+                rows.push(header);
+                for (i, site) in sites.iter().enumerate() {
+                    rows.push(BreakpointRow {
+                        number: format!("{}.{}", breakpoint.id(), i + 1),
+                        kind: "",
+                        enabled: site.is_enabled(),
+                        address: site.address().to_string(),
+                        hits: site.hit_count(),
+                        ignore_count: 0,
+                        condition: None,
+                        commands: Vec::new(),
+                        what: describe_address(process, site.address()),
+                        ..row.clone()
+                    });
+                }
+            }
+        }
+    }
+
+    for watchpoint in process.watchpoints() {
+        rows.push(BreakpointRow {
+            number: watchpoint.id().to_string(),
+            id: watchpoint.id(),
+            kind: match watchpoint.mode() {
+                HardwareMode::ReadWrite => "acc watchpoint",
+                _ => "hw watchpoint",
+            },
+            hardware: true,
+            enabled: watchpoint.is_enabled(),
+            address: watchpoint.address().to_string(),
+            hits: watchpoint.policy().hit_count(),
+            ignore_count: watchpoint.policy().ignore_count(),
+            condition: watchpoint.policy().condition().map(|c| c.to_string()),
+            commands: watchpoint.policy().commands().to_vec(),
+            what: format!("{} bytes ({})", watchpoint.size(), watchpoint.mode()),
+        });
+    }
+
+    for catchpoint in process.catchpoints() {
+        rows.push(BreakpointRow {
+            number: catchpoint.id().to_string(),
+            id: catchpoint.id(),
+            kind: "catchpoint",
+            hardware: false,
+            enabled: catchpoint.is_enabled(),
+            address: String::new(),
+            hits: catchpoint.policy().hit_count(),
+            ignore_count: catchpoint.policy().ignore_count(),
+            condition: catchpoint.policy().condition().map(|c| c.to_string()),
+            commands: catchpoint.policy().commands().to_vec(),
+            what: format!("syscall {}", catchpoint.describe_syscalls()),
+        });
+    }
+
+    // breakpoints, watchpoints and catchpoints share the numbering; the sort
+    // is stable, so a breakpoint's locations stay after it
+    rows.sort_by_key(|row| row.id);
+    rows
+}
+
+/// e.g. `main+4 at src/main.rs:12`, or as much of that as is known.
+fn describe_address(process: &Process, address: VirtualAddress) -> String {
+    let symbol = process.symbolize(address);
+    let location = process.source_location(address);
+    match (symbol, location) {
+        (Some(symbol), Some(location)) => format!("{symbol} at {location}"),
+        (Some(symbol), None) => symbol,
+        (None, Some(location)) => location.to_string(),
+        (None, None) => String::new(),
+    }
+}
+
+/// Lay the rows out in columns, under a header.
+fn format_breakpoint_rows(rows: &[BreakpointRow]) -> String {
+    let number_width = rows
+        .iter()
+        .map(|r| r.number.len())
+        .max()
+        .unwrap_or(0)
+        .max(3);
+    let kind_width = rows.iter().map(|r| r.kind.len()).max().unwrap_or(0).max(4);
+    let address_width = rows
+        .iter()
+        .map(|r| r.address.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+
+    let mut out = format!(
+        "{:<number_width$} {:<kind_width$} Enb {:<address_width$} {:>5} What\n",
+        "Num", "Type", "Address", "Hits"
+    );
+    for row in rows {
+        let enabled = if row.enabled { 'y' } else { 'n' };
+        let _ = writeln!(
+            out,
+            "{:<number_width$} {:<kind_width$} {enabled:<3} {:<address_width$} {:>5} {}",
+            row.number, row.kind, row.address, row.hits, row.what
+        );
+        if let Some(condition) = &row.condition {
+            let _ = writeln!(out, "{:number_width$} stop only if {condition}", "");
+        }
+        if row.ignore_count > 0 {
+            let _ = writeln!(
+                out,
+                "{:number_width$} Will ignore next {} crossings",
+                "", row.ignore_count
+            );
+        }
+        for command in &row.commands {
+            let _ = writeln!(out, "{:number_width$}   {command}", "");
+        }
+    }
+    out.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Lay out how each signal is handled, under a header, as `handle` and `info
+/// signals` show it.
+pub fn format_signal_rows(rows: &[(Signal, SignalDisposition)]) -> String {
+    let yes_no = |b: bool| if b { "Yes" } else { "No" };
+    let mut out = "Signal    Stop Print Pass".to_string();
+    for (signal, disposition) in rows {
+        let _ = write!(
+            out,
+            "\n{:<9} {:<4} {:<5} {}",
+            signal.as_str(),
+            yes_no(disposition.stop),
+            yes_no(disposition.print),
+            yes_no(disposition.pass)
+        );
+    }
+    out
+}
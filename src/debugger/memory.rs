@@ -0,0 +1,298 @@
+//! The `memory read`/`memory write` and gdb-style `x/<n><fmt><unit>` commands.
+
+use anyhow::{Result, anyhow};
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::expression::Expression;
+use crate::process::Process;
+use crate::process::memory::{MAX_READ_LEN, PAGE_SIZE};
+use crate::process::stoppoint::VirtualAddress;
+
+/// Default number of bytes shown by `memory read`.
+const DEFAULT_READ_LEN: usize = 32;
+/// Upper bound on the length of a single string for `x/s`.
+const MAX_STRING_LEN: usize = 4096;
+
+#[derive(Clone, Debug)]
+pub enum MemoryCommand {
+    /// `memory read <address> [len]`: hex dump of `len` bytes.
+    Read { address: Expression, len: usize },
+    /// `memory write <address> <byte>...`
+    Write { address: Expression, data: Vec<u8> },
+    /// `x/<n><fmt><unit> <address>`: print `n` units in the given format.
+    Examine {
+        address: Expression,
+        format: ExamineFormat,
+    },
+}
+
+/// How to display each unit of memory for the `x` command.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisplayFormat {
+    Hex,
+    Decimal,
+    Unsigned,
+    Octal,
+    Char,
+    /// NUL-terminated strings; the unit size is ignored.
+    String,
+}
+
+/// The size of each unit of memory for the `x` command (gdb's names).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnitSize {
+    Byte,
+    HalfWord,
+    Word,
+    Giant,
+}
+
+impl UnitSize {
+    fn bytes(&self) -> usize {
+        match self {
+            UnitSize::Byte => 1,
+            UnitSize::HalfWord => 2,
+            UnitSize::Word => 4,
+            UnitSize::Giant => 8,
+        }
+    }
+}
+
+/// The `/<n><fmt><unit>` suffix of the `x` command, e.g. `x/4xg`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExamineFormat {
+    pub count: usize,
+    pub format: DisplayFormat,
+    pub unit: UnitSize,
+}
+
+impl Default for ExamineFormat {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            format: DisplayFormat::Hex,
+            unit: UnitSize::Word,
+        }
+    }
+}
+
+impl FromStr for ExamineFormat {
+    type Err = anyhow::Error;
+
+    /// Parse the part after the `/`. The count, format, and unit are all optional.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut format = ExamineFormat::default();
+
+        let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 {
+            format.count = s[..digits]
+                .parse()
+                .map_err(|e| anyhow!("Invalid count in {:?}: {e}", s))?;
+        }
+
+        for c in s[digits..].chars() {
+            match c {
+                'x' => format.format = DisplayFormat::Hex,
+                'd' => format.format = DisplayFormat::Decimal,
+                'u' => format.format = DisplayFormat::Unsigned,
+                'o' => format.format = DisplayFormat::Octal,
+                'c' => {
+                    format.format = DisplayFormat::Char;
+                    format.unit = UnitSize::Byte;
+                }
+                's' => format.format = DisplayFormat::String,
+                'b' => format.unit = UnitSize::Byte,
+                'h' => format.unit = UnitSize::HalfWord,
+                'w' => format.unit = UnitSize::Word,
+                'g' => format.unit = UnitSize::Giant,
+                _ => return Err(anyhow!("Unknown format letter {:?} in {:?}", c, s)),
+            }
+        }
+
+        Ok(format)
+    }
+}
+
+impl MemoryCommand {
+    /// Parse the arguments of `memory read|write ...`.
+    pub fn parse_memory(args: &[String]) -> Result<Self> {
+        match args {
+            [op, address, rest @ ..] if op == "read" => {
+                let address = Expression::parse(address)?;
+                let len = match rest {
+                    [] => DEFAULT_READ_LEN,
+                    [len] => {
+                        let len = Expression::constant(len)
+                            .map_err(|e| anyhow!("Invalid length: {e}"))?;
+                        usize::try_from(len)
+                            .ok()
+                            .filter(|len| *len <= MAX_READ_LEN)
+                            .ok_or_else(|| {
+                                anyhow!(
+                                    "Invalid length {len}, it must be at most {MAX_READ_LEN} bytes"
+                                )
+                            })?
+                    }
+                    _ => return Err(anyhow!("usage: memory read <address> [len]")),
+                };
+                Ok(MemoryCommand::Read { address, len })
+            }
+            [op, address, bytes @ ..] if op == "write" && !bytes.is_empty() => {
+                let address = Expression::parse(address)?;
+                let data = bytes
+                    .iter()
+                    .map(|b| parse_byte(b))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(MemoryCommand::Write { address, data })
+            }
+            _ => Err(anyhow!(
+                "usage: memory read <address> [len] | memory write <address> <byte>..."
+            )),
+        }
+    }
+
+    /// Parse the `x[/<n><fmt><unit>] <address>` command; `cmd` is the first word.
+    /// The address may contain spaces, e.g. `x/2g $rsp + 8`.
+    pub fn parse_examine(cmd: &str, args: &[String]) -> Result<Self> {
+        let format = match cmd.split_once('/') {
+            Some((_, spec)) => spec.parse()?,
+            None => ExamineFormat::default(),
+        };
+        if args.is_empty() {
+            return Err(anyhow!("usage: x/<n><fmt><unit> <address>"));
+        }
+        Ok(MemoryCommand::Examine {
+            address: Expression::from_args(args)?,
+            format,
+        })
+    }
+
+    /// Run the command against the inferior, returning the text to show the user.
+    pub fn execute(&self, process: &mut Process) -> Result<String> {
+        match self {
+            MemoryCommand::Read { address, len } => {
+                let address = process.evaluate_address(address)?;
+                let data = process.read_memory(address, *len)?;
+                Ok(hex_dump(address, &data))
+            }
+            MemoryCommand::Write { address, data } => {
+                let address = process.evaluate_address(address)?;
+                process.write_memory(address, data)?;
+                Ok(format!("wrote {} bytes at {address}", data.len()))
+            }
+            MemoryCommand::Examine { address, format } => {
+                let address = process.evaluate_address(address)?;
+                examine(process, address, format)
+            }
+        }
+    }
+}
+
+fn parse_byte(s: &str) -> Result<u8> {
+    let value = Expression::constant(s).map_err(|e| anyhow!("Invalid byte: {e}"))?;
+    u8::try_from(value).map_err(|_| anyhow!("Invalid byte {:?}: out of range", s))
+}
+
+/// Classic 16-bytes-per-line hex dump, with an ASCII column.
+pub fn hex_dump(address: VirtualAddress, data: &[u8]) -> String {
+    let mut out = String::new();
+    for (i, chunk) in data.chunks(16).enumerate() {
+        let line_addr = address.offset((i * 16) as i64);
+        let hex: Vec<String> = chunk.iter().map(|b| format!("{b:02x}")).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        let _ = writeln!(out, "{line_addr:#018x}: {:<47}  |{ascii}|", hex.join(" "));
+    }
+    out.trim_end().to_string()
+}
+
+fn examine(process: &Process, address: VirtualAddress, format: &ExamineFormat) -> Result<String> {
+    if matches!(format.format, DisplayFormat::String) {
+        return examine_strings(process, address, format.count);
+    }
+
+    let unit = format.unit.bytes();
+    let len = format
+        .count
+        .checked_mul(unit)
+        .ok_or_else(|| anyhow!("Cannot examine {} units of {unit} bytes", format.count))?;
+    let data = process.read_memory(address, len)?;
+    let per_line = (16 / unit).min(8);
+
+    let mut out = String::new();
+    for (i, line) in data.chunks(per_line * unit).enumerate() {
+        let line_addr = address.offset((i * per_line * unit) as i64);
+        let values: Vec<String> = line
+            .chunks(unit)
+            .map(|bytes| format_unit(bytes, format))
+            .collect();
+        let _ = writeln!(out, "{line_addr:#018x}: {}", values.join("  "));
+    }
+    Ok(out.trim_end().to_string())
+}
+
+/// Format a single (little endian) unit of memory.
+fn format_unit(bytes: &[u8], format: &ExamineFormat) -> String {
+    let mut buf = [0u8; 8];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    let value = u64::from_le_bytes(buf);
+    let width = bytes.len() * 2;
+
+    match format.format {
+        DisplayFormat::Hex => format!("0x{value:0width$x}"),
+        DisplayFormat::Unsigned => value.to_string(),
+        DisplayFormat::Octal => format!("{value:#o}"),
+        DisplayFormat::Decimal => {
+            // sign extend from the unit size
+            let shift = 64 - bytes.len() * 8;
+            (((value << shift) as i64) >> shift).to_string()
+        }
+        DisplayFormat::Char => {
+            let c = value as u8;
+            format!("{c} {:?}", c as char)
+        }
+        DisplayFormat::String => unreachable!("strings are handled separately"),
+    }
+}
+
+fn examine_strings(process: &Process, address: VirtualAddress, count: usize) -> Result<String> {
+    let mut out = String::new();
+    let mut cur = address;
+    for _ in 0..count {
+        let s = read_c_string(process, cur)?;
+        let _ = writeln!(out, "{cur:#018x}: {:?}", String::from_utf8_lossy(&s));
+        // skip past the NUL terminator
+        cur = cur.offset(s.len() as i64 + 1);
+    }
+    Ok(out.trim_end().to_string())
+}
+
+/// Read a NUL-terminated string (without the NUL) from the inferior.
+fn read_c_string(process: &Process, address: VirtualAddress) -> Result<Vec<u8>> {
+    const CHUNK: u64 = 64;
+    let mut s = Vec::new();
+    while s.len() < MAX_STRING_LEN {
+        // don't let a chunk cross into the next page, which may not be mapped
+        let cur = address.offset(s.len() as i64);
+        let len = CHUNK.min(PAGE_SIZE - (cur.addr() % PAGE_SIZE));
+        let chunk = process.read_memory(cur, len as usize)?;
+        match chunk.iter().position(|&b| b == 0) {
+            Some(nul) => {
+                s.extend_from_slice(&chunk[..nul]);
+                return Ok(s);
+            }
+            None => s.extend_from_slice(&chunk),
+        }
+    }
+    s.truncate(MAX_STRING_LEN);
+    Ok(s)
+}
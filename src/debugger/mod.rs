@@ -1,9 +1,56 @@
 use anyhow::{Result, anyhow};
-use tracing::trace;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{info, trace};
 
+use nix::unistd::Pid;
+
+use crate::debugger::disassemble::DisassembleCommand;
+use crate::debugger::handle::HandleCommand;
+use crate::debugger::info::InfoCommand;
+use crate::debugger::memory::MemoryCommand;
+use crate::debugger::register::RegisterCommand;
+use crate::debugger::set::SetCommand;
+use crate::debugger::thread::ThreadCommand;
+use crate::expression::{ExprKind, Expression};
 use crate::history::CommandHistory;
-use crate::process::Process;
-use crate::process::stoppoint::{StoppointId, VirtualAddress};
+use crate::options::parse_pid;
+use crate::process::stoppoint::breakpoint::{BreakpointId, BreakpointLocation};
+use crate::process::stoppoint::hardware::{HardwareMode, HardwareStoppoint};
+use crate::process::stoppoint::watchpoint::Watchpoint;
+use crate::process::syscalls::parse_syscall;
+use crate::process::{Process, ProcessState, StopReason, TrapType};
+
+pub mod disassemble;
+pub mod handle;
+pub mod info;
+pub mod memory;
+pub mod register;
+pub mod set;
+pub mod thread;
+
+/// Number of bytes watched when `watch` isn't given a size.
+const DEFAULT_WATCH_SIZE: usize = 8;
+/// Maximum number of lines of command output shown in the echo area.
+pub(crate) const MAX_ECHO_LINES: usize = 16;
+
+/// Set by the terminal thread on `Ctrl`+`c`, as the main loop is busy while a
+/// command list keeps continuing the inferior.
+static INTERRUPT_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Ask a running breakpoint command list to stop at the next stop.
+pub fn request_interrupt() {
+    INTERRUPT_REQUESTED.store(true, Ordering::Relaxed);
+}
+
+/// Forget an interrupt that came in when nothing was running.
+pub fn clear_interrupt() {
+    INTERRUPT_REQUESTED.store(false, Ordering::Relaxed);
+}
+
+fn take_interrupt() -> bool {
+    INTERRUPT_REQUESTED.swap(false, Ordering::Relaxed)
+}
 
 pub struct Debugger {
     /// Flag if the program is currently being debugged.
@@ -52,13 +99,66 @@ impl Debugger {
                 process.attach(args)?;
                 self.debugging = true;
             }
+            Command::Attach(pid) => {
+                process.attach_to_pid(pid)?;
+                self.debugging = true;
+            }
             Command::Continue => {
-                process.resume()?;
-                process.wait_on_signal()?;
+                continue_inferior(process)?;
+                res = self.report_stop(process)?;
+            }
+            Command::StepInstruction(count) => {
+                for _ in 0..count {
+                    process.step_instruction()?;
+                    if !matches!(process.state(), ProcessState::Stopped) {
+                        break;
+                    }
+                }
+                res = self.report_stop(process)?;
+            }
+            Command::Break { address, hardware } => {
+                let location = BreakpointLocation::Address(process.evaluate_address(&address)?);
+                let command = match hardware {
+                    true => BreakpointCommand::CreateHardware(location),
+                    false => BreakpointCommand::Create(location),
+                };
+                let message = process.breakpoint_command(command)?;
+                res = DispatchResult::Message(message);
+            }
+            Command::Watch {
+                address,
+                size,
+                mode,
+            } => {
+                let address = process.evaluate_address(&address)?;
+                let stoppoint = HardwareStoppoint::watch(address, size, mode)?;
+                let message = process.breakpoint_command(BreakpointCommand::Watch(stoppoint))?;
+                res = DispatchResult::Message(message);
             }
             Command::Breakpoint(cmd) => {
-                process.breakpoint_command(cmd)?;
-                // wait_on_signal?? i don't think so, but ....
+                let message = process.breakpoint_command(cmd)?;
+                res = DispatchResult::Message(message);
+            }
+            Command::Memory(cmd) => {
+                res = DispatchResult::Message(cmd.execute(process)?);
+            }
+            Command::Disassemble(cmd) => {
+                res = DispatchResult::Message(cmd.execute(process)?);
+            }
+            Command::Register(cmd) => {
+                res = DispatchResult::Message(cmd.execute(process)?);
+            }
+            Command::Info(cmd) => {
+                res = DispatchResult::Message(cmd.execute(process)?);
+            }
+            Command::Thread(cmd) => {
+                res = DispatchResult::Message(cmd.execute(process)?);
+            }
+            Command::Set(cmd) => {
+                res = DispatchResult::Message(cmd.execute(process)?);
+            }
+            Command::Handle(cmd) => {
+                res = DispatchResult::Message(cmd.execute(process)?);
             }
             Command::Quit => {
                 process.destroy()?;
@@ -70,35 +170,343 @@ impl Debugger {
         Ok(res)
     }
 
+    /// Describe why the inferior stopped, then run the command list of the
+    /// breakpoint, watchpoint or catchpoint it stopped at, if any. A `continue`
+    /// in the list resumes the inferior, and the list for wherever it stops next
+    /// is run in turn, until a stop without one, or an interrupt.
+    ///
+    /// Every stop message and command output goes to the log as it happens;
+    /// only the last lines are kept for the echo area.
+    fn report_stop(&mut self, process: &mut Process) -> Result<DispatchResult> {
+        let mut output = EchoOutput::default();
+        loop {
+            if let DispatchResult::Message(message) = stop_message(process) {
+                output.push(message);
+            }
+            let commands = process
+                .stoppoint_hit()
+                .and_then(|id| process.stop_policy(id))
+                .map(|policy| policy.commands().to_vec())
+                .unwrap_or_default();
+
+            let mut resumed = false;
+            for line in commands {
+                info!(command = line, "Running breakpoint command");
+                let result = match Command::try_from(line) {
+                    // handled here rather than by `dispatch_command`, so a
+                    // tracing breakpoint in a hot loop doesn't recurse
+                    Ok(Command::Continue) => continue_inferior(process).map(|_| {
+                        resumed = true;
+                        DispatchResult::Normal
+                    }),
+                    Ok(command) => self.dispatch_command(command, process),
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(DispatchResult::Normal) => {}
+                    Ok(DispatchResult::Message(message)) => output.push(message),
+                    Ok(DispatchResult::Exit) => return Ok(DispatchResult::Exit),
+                    Err(e) => {
+                        // like gdb, give up on the rest of the list
+                        output.push(format!("error: {e}"));
+                        break;
+                    }
+                }
+                // the rest of the list is for this stop, not the next one
+                if resumed {
+                    break;
+                }
+            }
+            if !resumed {
+                break;
+            }
+            if take_interrupt() {
+                // report where we ended up, but don't run its commands
+                if let DispatchResult::Message(message) = stop_message(process) {
+                    output.push(message);
+                }
+                output.push("Interrupted, not running any more breakpoint commands".to_string());
+                break;
+            }
+        }
+
+        Ok(output.into_result())
+    }
+
+    /// Attach to an already-running process, e.g. from the `--pid` CLI option.
+    pub fn attach(&mut self, pid: Pid, process: &mut Process) -> Result<DispatchResult> {
+        self.dispatch_command(Command::Attach(pid), process)
+    }
+
     pub fn is_debugging(&self) -> bool {
         self.debugging
     }
 }
 
+/// Resume the inferior, and wait for it to stop again.
+fn continue_inferior(process: &mut Process) -> Result<()> {
+    process.resume()?;
+    if matches!(process.state(), ProcessState::Running) {
+        process.wait_on_signal()?;
+    }
+    Ok(())
+}
+
+/// The output of a stop and its command lists: the last lines for the echo
+/// area, with everything written to the log as it's pushed.
+#[derive(Default)]
+struct EchoOutput {
+    lines: VecDeque<String>,
+    /// Lines only in the log.
+    dropped: usize,
+}
+
+impl EchoOutput {
+    fn push(&mut self, message: String) {
+        info!("{message}");
+        self.lines.extend(message.lines().map(String::from));
+        // leave room for the line saying the rest is in the log
+        while self.lines.len() > MAX_ECHO_LINES - 1 {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    fn into_result(self) -> DispatchResult {
+        if self.lines.is_empty() {
+            return DispatchResult::Normal;
+        }
+        let mut lines = Vec::from(self.lines);
+        if self.dropped > 0 {
+            lines.insert(
+                0,
+                format!("({} earlier lines are in the log)", self.dropped),
+            );
+        }
+        DispatchResult::Message(lines.join("\n"))
+    }
+}
+
+/// Describe why the inferior last stopped, for display in the echo area.
+fn stop_message(process: &Process) -> DispatchResult {
+    let (Some(pid), Some(reason)) = (process.pid(), process.last_stop()) else {
+        return DispatchResult::Normal;
+    };
+    let StopReason::Stopped { tid, trap, pc, .. } = reason else {
+        return DispatchResult::Message(format!("Process {pid} {reason}"));
+    };
+
+    // only name the thread when there's a choice of them
+    let who = match process.threads().count() {
+        0 | 1 if *tid == pid => format!("Process {pid}"),
+        _ => format!("Process {pid} thread {tid}"),
+    };
+    let mut message = match (
+        trap,
+        process.breakpoint_at(*pc),
+        process.watchpoint_hit(),
+        process.catchpoint_hit(),
+    ) {
+        (_, _, _, Some(catchpoint)) => {
+            format!("{who} hit catchpoint {} at {pc}", catchpoint.id())
+        }
+        (_, _, Some(watchpoint), _) => {
+            format!("{who} hit watchpoint {} at {pc}", watchpoint.id())
+        }
+        (Some(TrapType::SoftwareBreak | TrapType::HardwareBreak), Some(breakpoint), _, _) => {
+            format!("{who} hit breakpoint {} at {pc}", breakpoint.id())
+        }
+        _ => format!("{who} {reason}"),
+    };
+    if let Some(symbol) = process.symbolize(*pc) {
+        message.push_str(&format!(" <{symbol}>"));
+    }
+    if let Some(watchpoint) = process.watchpoint_hit() {
+        message.push('\n');
+        message.push_str(&describe_watch_values(watchpoint));
+    }
+    if let Some(syscall) = process.last_syscall() {
+        message.push('\n');
+        message.push_str(&syscall.to_string());
+    }
+    DispatchResult::Message(message)
+}
+
+/// The watched value before and after a watchpoint triggered.
+fn describe_watch_values(watchpoint: &Watchpoint) -> String {
+    let show = |value: Option<u64>| match value {
+        Some(value) => format!("{value:#x}"),
+        None => "<unreadable>".to_string(),
+    };
+    let (old, new) = (watchpoint.previous_value(), watchpoint.value());
+    if old == new {
+        format!("{} = {}", watchpoint.address(), show(new))
+    } else {
+        format!("Old value = {}\nNew value = {}", show(old), show(new))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum DispatchResult {
     Normal,
+    /// The command completed, and produced some output for the user.
+    Message(String),
     Exit,
 }
 
 #[derive(Clone, Debug)]
 pub enum BreakpointCommand {
-    Create(VirtualAddress),
-    Delete(StoppointId),
-    Enable(StoppointId),
-    Disable(StoppointId),
+    Create(BreakpointLocation),
+    /// Create a breakpoint using the debug registers rather than `int3`.
+    CreateHardware(BreakpointLocation),
+    /// Create a hardware watchpoint.
+    Watch(HardwareStoppoint),
+    /// Create a catchpoint for the syscalls with these numbers, or for any
+    /// syscall if there are none.
+    Catch(Vec<u64>),
+    Delete(BreakpointId),
+    Enable(BreakpointId),
+    Disable(BreakpointId),
+    /// Continue through the next `count` hits without stopping.
+    Ignore {
+        id: BreakpointId,
+        count: u64,
+    },
+    /// Only stop when the expression is true (non-zero), or always if `None`.
+    Condition {
+        id: BreakpointId,
+        condition: Option<Expression>,
+    },
+    /// Debugger commands to run on each hit, replacing any previous list.
+    Commands {
+        id: BreakpointId,
+        commands: Vec<String>,
+    },
 }
 
 #[derive(Clone, Debug)]
 pub enum Command {
     /// Start or connect to the inferior process.
     Run(Vec<String>),
+    /// Attach to an already-running process by PID.
+    Attach(Pid),
     Continue,
+    /// Execute the given number of machine instructions.
+    StepInstruction(u64),
+    /// Set a breakpoint at the address the expression evaluates to, e.g.
+    /// `break 0x401136` or `break $rip+8`. Function and line breakpoints are
+    /// parsed straight into a `BreakpointCommand::Create` (or
+    /// `CreateHardware`, for `hbreak`).
+    Break {
+        address: Expression,
+        hardware: bool,
+    },
     Breakpoint(BreakpointCommand),
-    /// Exit the debugger (and kill inferior process if it was launched).
+    /// Set a hardware watchpoint on `size` bytes at the address the expression
+    /// evaluates to, e.g. `watch/4 COUNTER`.
+    Watch {
+        address: Expression,
+        size: usize,
+        mode: HardwareMode,
+    },
+    /// Read, write, or examine the inferior's memory.
+    Memory(MemoryCommand),
+    /// Disassemble a function or address range.
+    Disassemble(DisassembleCommand),
+    /// Read or write the inferior's registers.
+    Register(RegisterCommand),
+    /// Describe the debugger's state, e.g. `info breakpoints`.
+    Info(InfoCommand),
+    /// List the inferior's threads, or switch to another one.
+    Thread(ThreadCommand),
+    /// Change a setting, e.g. `set follow-fork-mode child`.
+    Set(SetCommand),
+    /// Change how a signal is handled, e.g. `handle SIGALRM stop`.
+    Handle(HandleCommand),
+    /// Exit the debugger (and kill inferior process if it was launched, or
+    /// detach from it if we attached).
     Quit,
 }
 
+/// Parse `watch[/<size>] <address>` (or `awatch`, to also trap on reads).
+fn parse_watch(cmd: &str, mode: HardwareMode, args: &[String]) -> Result<Command> {
+    let size = match cmd.split_once('/') {
+        Some((_, size)) => size
+            .parse()
+            .map_err(|e| anyhow!("Invalid watchpoint size {:?}: {e}", size))?,
+        None => DEFAULT_WATCH_SIZE,
+    };
+    if args.is_empty() {
+        return Err(anyhow!("usage: {cmd}[/<size>] <address>"));
+    }
+    Ok(Command::Watch {
+        address: Expression::from_args(args)?,
+        size,
+        mode,
+    })
+}
+
+/// Parse `catch syscall [<name>|<number>]...`.
+fn parse_catch(args: &[String]) -> Result<Command> {
+    match args {
+        [what, syscalls @ ..] if what == "syscall" => {
+            let syscalls = syscalls
+                .iter()
+                .map(|s| parse_syscall(s))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Command::Breakpoint(BreakpointCommand::Catch(syscalls)))
+        }
+        _ => Err(anyhow!("usage: catch syscall [<name>|<number>]...")),
+    }
+}
+
+/// Split a `;` separated command list, checking that each command parses (so
+/// mistakes are reported now, not when the breakpoint is hit).
+fn parse_command_list(list: &str) -> Result<Vec<String>> {
+    list.split(';')
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .map(|command| {
+            Command::try_from(command.to_string())
+                .map(|_| command.to_string())
+                .map_err(|e| anyhow!("In `{command}`: {e}"))
+        })
+        .collect()
+}
+
+/// Parse the argument of `break` or `hbreak`: a source line (`src/main.rs:12`),
+/// a function name (`main`, `my_crate::foo`), or an address expression.
+fn parse_break(args: &[String], hardware: bool) -> Result<Command> {
+    let create = |location| match hardware {
+        true => Command::Breakpoint(BreakpointCommand::CreateHardware(location)),
+        false => Command::Breakpoint(BreakpointCommand::Create(location)),
+    };
+
+    if let [arg] = args
+        && let Some((file, line)) = arg.rsplit_once(':')
+        && !file.is_empty()
+        && !file.ends_with(':')
+        && let Ok(line) = line.parse::<u64>()
+    {
+        let location = BreakpointLocation::Line {
+            file: file.into(),
+            line,
+        };
+        return Ok(create(location));
+    }
+
+    let expression = Expression::from_args(args)?;
+    // a bare name breaks on every function of that name
+    if let ExprKind::Symbol(name) = &expression.root().kind {
+        let location = BreakpointLocation::Function(name.clone());
+        return Ok(create(location));
+    }
+    Ok(Command::Break {
+        address: expression,
+        hardware,
+    })
+}
+
 impl TryFrom<String> for Command {
     type Error = anyhow::Error;
 
@@ -109,19 +517,74 @@ impl TryFrom<String> for Command {
 
         let command = match cmd.to_lowercase().as_str() {
             "run" | "r" => Command::Run(args),
+            "attach" => match args.as_slice() {
+                [pid] => Command::Attach(parse_pid(pid)?),
+                _ => return Err(anyhow!("usage: attach <pid>")),
+            },
             "continue" | "c" => Command::Continue,
+            "stepi" | "si" => match args.as_slice() {
+                [] => Command::StepInstruction(1),
+                [count] => Command::StepInstruction(
+                    count
+                        .parse::<u64>()
+                        .map_err(|e| anyhow!("invalid step count {:?}: {e}", count))?,
+                ),
+                _ => return Err(anyhow!("usage: stepi [count]")),
+            },
             "quit" | "q" => Command::Quit,
-            "break" | "b" => {
-                Command::Breakpoint(BreakpointCommand::Create(VirtualAddress::try_from(args)?))
+            "break" | "b" => parse_break(&args, false)?,
+            "hbreak" => parse_break(&args, true)?,
+            "catch" => parse_catch(&args)?,
+            "memory" | "mem" => Command::Memory(MemoryCommand::parse_memory(&args)?),
+            "disassemble" | "disas" => Command::Disassemble(DisassembleCommand::parse(&args)?),
+            "register" | "reg" => Command::Register(RegisterCommand::parse(&args)?),
+            "info" | "i" => Command::Info(InfoCommand::parse(&args)?),
+            "thread" => Command::Thread(ThreadCommand::parse(&args)?),
+            "set" => Command::Set(SetCommand::parse(&args)?),
+            "handle" => Command::Handle(HandleCommand::parse(&args)?),
+            c if c == "watch" || c.starts_with("watch/") => {
+                parse_watch(c, HardwareMode::Write, &args)?
+            }
+            c if c == "awatch" || c.starts_with("awatch/") => {
+                parse_watch(c, HardwareMode::ReadWrite, &args)?
+            }
+            c if c == "x" || c.starts_with("x/") => {
+                Command::Memory(MemoryCommand::parse_examine(c, &args)?)
             }
             "delete" => {
-                Command::Breakpoint(BreakpointCommand::Delete(StoppointId::try_from(args)?))
+                Command::Breakpoint(BreakpointCommand::Delete(BreakpointId::try_from(args)?))
             }
             "enable" => {
-                Command::Breakpoint(BreakpointCommand::Enable(StoppointId::try_from(args)?))
+                Command::Breakpoint(BreakpointCommand::Enable(BreakpointId::try_from(args)?))
             }
+            "ignore" => match args.as_slice() {
+                [id, count] => Command::Breakpoint(BreakpointCommand::Ignore {
+                    id: BreakpointId::try_from(vec![id.clone()])?,
+                    count: count
+                        .parse::<u64>()
+                        .map_err(|e| anyhow!("Invalid ignore count {:?}: {e}", count))?,
+                }),
+                _ => return Err(anyhow!("usage: ignore <breakpoint> <count>")),
+            },
+            "condition" => match args.as_slice() {
+                [id, condition @ ..] => Command::Breakpoint(BreakpointCommand::Condition {
+                    id: BreakpointId::try_from(vec![id.clone()])?,
+                    condition: match condition {
+                        [] => None,
+                        _ => Some(Expression::from_args(condition)?),
+                    },
+                }),
+                _ => return Err(anyhow!("usage: condition <breakpoint> [<expression>]")),
+            },
+            "commands" => match args.as_slice() {
+                [id, commands @ ..] => Command::Breakpoint(BreakpointCommand::Commands {
+                    id: BreakpointId::try_from(vec![id.clone()])?,
+                    commands: parse_command_list(&commands.join(" "))?,
+                }),
+                _ => return Err(anyhow!("usage: commands <breakpoint> [<command>; ...]")),
+            },
             "disable" => {
-                Command::Breakpoint(BreakpointCommand::Disable(StoppointId::try_from(args)?))
+                Command::Breakpoint(BreakpointCommand::Disable(BreakpointId::try_from(args)?))
             }
             _ => return Err(anyhow!("unknown command: {:?}", value)),
         };
@@ -0,0 +1,157 @@
+//! The `register read` and `register write` commands.
+
+use anyhow::{Result, anyhow};
+use std::fmt::Write;
+
+use crate::expression::Expression;
+use crate::process::Process;
+use crate::process::arch::{Arch, HostArch};
+use crate::process::register_info::{
+    RegisterFormat, RegisterInfo, RegisterType, RegisterValue, f64_to_long_double,
+    register_info_by_name, registers_info_iter,
+};
+
+#[derive(Clone, Debug)]
+pub enum RegisterCommand {
+    /// `register read [<name>|all|gp|fp|debug]`, the general purpose registers
+    /// by default.
+    Read(RegisterSelection),
+    /// `register write <name> <value>`: the value is an expression for integer
+    /// registers, e.g. `register write rax $rbx + 8`, or a number for the
+    /// floating point and vector ones.
+    Write {
+        register: RegisterInfo,
+        value: String,
+    },
+}
+
+/// Which registers `register read` shows.
+#[derive(Clone, Debug)]
+pub enum RegisterSelection {
+    /// A single register, including subregisters like `eax`.
+    Named(RegisterInfo),
+    /// Every register, apart from the subregisters.
+    All,
+    GeneralPurpose,
+    FloatingPoint,
+    Debug,
+}
+
+impl RegisterSelection {
+    fn includes(&self, info: &RegisterInfo) -> bool {
+        match self {
+            RegisterSelection::Named(named) => named.name == info.name,
+            RegisterSelection::All => {
+                !matches!(info.register_type, RegisterType::SubGeneralPurpose)
+            }
+            RegisterSelection::GeneralPurpose => {
+                matches!(info.register_type, RegisterType::GeneralPurpose)
+            }
+            RegisterSelection::FloatingPoint => {
+                matches!(info.register_type, RegisterType::FloatingPoint)
+            }
+            RegisterSelection::Debug => matches!(info.register_type, RegisterType::Debug),
+        }
+    }
+}
+
+impl RegisterCommand {
+    /// Parse the arguments of `register read|write ...`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        match args {
+            [op] if op == "read" => Ok(RegisterCommand::Read(RegisterSelection::GeneralPurpose)),
+            [op, which] if op == "read" => {
+                let selection = match which.as_str() {
+                    "all" => RegisterSelection::All,
+                    "gp" => RegisterSelection::GeneralPurpose,
+                    "fp" => RegisterSelection::FloatingPoint,
+                    "debug" => RegisterSelection::Debug,
+                    name => RegisterSelection::Named(lookup(name)?),
+                };
+                Ok(RegisterCommand::Read(selection))
+            }
+            [op, name, value @ ..] if op == "write" && !value.is_empty() => {
+                Ok(RegisterCommand::Write {
+                    register: lookup(name)?,
+                    value: value.join(" "),
+                })
+            }
+            _ => Err(anyhow!(
+                "usage: register read [<name>|all|gp|fp|debug] | register write <name> <value>"
+            )),
+        }
+    }
+
+    /// Run the command against the inferior, returning the text to show the user.
+    pub fn execute(&self, process: &mut Process) -> Result<String> {
+        match self {
+            RegisterCommand::Read(selection) => {
+                let registers: Vec<_> = registers_info_iter()
+                    .filter(|info| selection.includes(info))
+                    .collect();
+                let width = registers
+                    .iter()
+                    .map(|info| info.name.len())
+                    .max()
+                    .unwrap_or(0);
+                let mut out = String::new();
+                for info in &registers {
+                    let value = read(process, info)?;
+                    let _ = writeln!(out, "{:>width$} = {value}", info.name);
+                }
+                Ok(out.trim_end().to_string())
+            }
+            RegisterCommand::Write { register, value } => {
+                let value = parse_value(process, register, value)?;
+                process.write_register(register.register, value)?;
+                Ok(format!("{} = {}", register.name, read(process, register)?))
+            }
+        }
+    }
+}
+
+/// Find a register by name, with or without a leading `$`. `pc` and `sp`
+/// work on every architecture.
+fn lookup(name: &str) -> Result<RegisterInfo> {
+    let name = name.strip_prefix('$').unwrap_or(name);
+    let name = match name {
+        "pc" => HostArch::PC_REGISTER,
+        "sp" => HostArch::SP_REGISTER,
+        name => name,
+    };
+    register_info_by_name(name).ok_or_else(|| anyhow!("Unknown register {name:?}"))
+}
+
+fn read(process: &Process, info: &RegisterInfo) -> Result<RegisterValue> {
+    process
+        .read_register(info.register)
+        .ok_or_else(|| anyhow!("Registers are not available, the process is not stopped"))
+}
+
+/// Parse the value for `register write`, according to the register's format.
+fn parse_value(process: &Process, info: &RegisterInfo, value: &str) -> Result<RegisterValue> {
+    let invalid =
+        |e: &dyn std::fmt::Display| anyhow!("Invalid value {value:?} for {}: {e}", info.name);
+    let float = || value.parse::<f64>().map_err(|e| invalid(&e));
+    let vector = || {
+        match value.strip_prefix("0x") {
+            Some(hex) => u128::from_str_radix(hex, 16),
+            None => value.parse::<u128>(),
+        }
+        .map_err(|e| invalid(&e))
+    };
+
+    match info.format {
+        RegisterFormat::Float | RegisterFormat::Double => Ok(RegisterValue::Double(float()?)),
+        RegisterFormat::LongDouble => Ok(RegisterValue::LongDouble(f64_to_long_double(float()?))),
+        RegisterFormat::Byte64 => {
+            let v = u64::try_from(vector()?).map_err(|e| invalid(&e))?;
+            Ok(RegisterValue::Byte64(v.to_le_bytes()))
+        }
+        RegisterFormat::Byte128 => Ok(RegisterValue::Byte128(vector()?.to_le_bytes())),
+        _ => {
+            let v = Expression::parse(value)?.evaluate(process)?;
+            Ok(RegisterValue::Uint64(v))
+        }
+    }
+}
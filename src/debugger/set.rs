@@ -0,0 +1,55 @@
+//! The `set` command, for the debugger's settings.
+
+use anyhow::{Result, anyhow};
+
+use crate::process::Process;
+use crate::process::fork::FollowForkMode;
+
+#[derive(Clone, Debug)]
+pub enum SetCommand {
+    /// `set follow-fork-mode parent|child`: which process to debug after a
+    /// fork.
+    FollowForkMode(FollowForkMode),
+    /// `set detach-on-fork on|off`: whether to detach from the process that
+    /// isn't followed, or hold it stopped.
+    DetachOnFork(bool),
+}
+
+impl SetCommand {
+    /// Parse the arguments of `set ...`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        match args {
+            [name, value] if name == "follow-fork-mode" => match value.as_str() {
+                "parent" => Ok(SetCommand::FollowForkMode(FollowForkMode::Parent)),
+                "child" => Ok(SetCommand::FollowForkMode(FollowForkMode::Child)),
+                _ => Err(anyhow!("usage: set follow-fork-mode parent|child")),
+            },
+            [name, value] if name == "detach-on-fork" => match value.as_str() {
+                "on" => Ok(SetCommand::DetachOnFork(true)),
+                "off" => Ok(SetCommand::DetachOnFork(false)),
+                _ => Err(anyhow!("usage: set detach-on-fork on|off")),
+            },
+            _ => Err(anyhow!(
+                "usage: set follow-fork-mode parent|child | set detach-on-fork on|off"
+            )),
+        }
+    }
+
+    /// Change the setting, returning the text to show the user.
+    pub fn execute(&self, process: &mut Process) -> Result<String> {
+        let mut settings = process.fork_settings();
+        let message = match self {
+            SetCommand::FollowForkMode(mode) => {
+                settings.follow_fork_mode = *mode;
+                format!("follow-fork-mode is now {mode}")
+            }
+            SetCommand::DetachOnFork(detach) => {
+                settings.detach_on_fork = *detach;
+                let value = if *detach { "on" } else { "off" };
+                format!("detach-on-fork is now {value}")
+            }
+        };
+        process.set_fork_settings(settings);
+        Ok(message)
+    }
+}
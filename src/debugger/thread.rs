@@ -0,0 +1,151 @@
+//! The `thread list` and `thread <tid>` commands.
+
+use anyhow::{Result, anyhow};
+use nix::unistd::Pid;
+use std::fmt::Write;
+
+use crate::options::parse_pid;
+use crate::process::thread::{Thread, ThreadState};
+use crate::process::{Process, StopReason, TrapType};
+
+#[derive(Clone, Debug)]
+pub enum ThreadCommand {
+    /// `thread list`: every thread, with the current one marked.
+    List,
+    /// `thread <tid>`: make the thread the current one, whose registers are
+    /// shown and which is stepped.
+    Select(Pid),
+}
+
+impl ThreadCommand {
+    /// Parse the arguments of `thread ...`.
+    pub fn parse(args: &[String]) -> Result<Self> {
+        match args {
+            [list] if list == "list" => Ok(ThreadCommand::List),
+            [tid] => Ok(ThreadCommand::Select(parse_pid(tid)?)),
+            _ => Err(anyhow!("usage: thread list | thread <tid>")),
+        }
+    }
+
+    /// Run the command, returning the text to show the user.
+    pub fn execute(&self, process: &mut Process) -> Result<String> {
+        match self {
+            ThreadCommand::List => {
+                let rows = thread_rows(process);
+                if rows.is_empty() {
+                    return Ok("No threads".to_string());
+                }
+                Ok(format_thread_rows(&rows))
+            }
+            ThreadCommand::Select(tid) => {
+                process.select_thread(*tid)?;
+                let thread = process.current_thread().expect("just selected");
+                Ok(format!(
+                    "Switched to thread {tid} at {}",
+                    describe_location(process, thread)
+                ))
+            }
+        }
+    }
+}
+
+/// A line of the thread list.
+#[derive(Clone, Debug)]
+pub struct ThreadRow {
+    pub tid: Pid,
+    /// The thread's name, from `/proc/<pid>/task/<tid>/comm`.
+    pub name: String,
+    pub state: ThreadState,
+    /// Why it last stopped, e.g. `breakpoint`.
+    pub reason: String,
+    /// Its PC and the symbol containing it.
+    pub location: String,
+    /// Whether it's the current thread.
+    pub current: bool,
+}
+
+/// The thread list, in order of thread ID.
+pub fn thread_rows(process: &Process) -> Vec<ThreadRow> {
+    let current = process.current_thread().map(Thread::tid);
+    process
+        .threads()
+        .map(|thread| ThreadRow {
+            tid: thread.tid(),
+            name: thread.name().unwrap_or_default().to_string(),
+            state: thread.state(),
+            reason: thread
+                .stop_reason()
+                .map(describe_reason)
+                .unwrap_or_default(),
+            location: describe_location(process, thread),
+            current: Some(thread.tid()) == current,
+        })
+        .collect()
+}
+
+/// Lay the rows out in columns, under a header, with the current thread
+/// marked.
+fn format_thread_rows(rows: &[ThreadRow]) -> String {
+    let tid_width = rows
+        .iter()
+        .map(|r| r.tid.to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max(3);
+    let name_width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0).max(4);
+    let reason_width = rows
+        .iter()
+        .map(|r| r.reason.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    let mut out = format!(
+        "  {:<tid_width$} {:<name_width$} {:<7} {:<reason_width$} Where\n",
+        "Tid", "Name", "State", "Reason"
+    );
+    for row in rows {
+        let marker = if row.current { '*' } else { ' ' };
+        let _ = writeln!(
+            out,
+            "{marker} {:<tid_width$} {:<name_width$} {:<7} {:<reason_width$} {}",
+            row.tid.to_string(),
+            row.name,
+            row.state.to_string(),
+            row.reason,
+            row.location
+        );
+    }
+    out.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Why a thread stopped, in a word or two, e.g. `breakpoint` or `SIGSTOP`.
+fn describe_reason(reason: &StopReason) -> String {
+    match reason {
+        StopReason::Stopped { trap, signal, .. } => match trap {
+            Some(TrapType::SoftwareBreak) => "breakpoint".to_string(),
+            Some(TrapType::HardwareBreak) => "hardware breakpoint".to_string(),
+            Some(TrapType::Watchpoint) => "watchpoint".to_string(),
+            Some(TrapType::SingleStep) => "single step".to_string(),
+            Some(TrapType::SyscallEntry) => "syscall entry".to_string(),
+            Some(TrapType::SyscallExit) => "syscall exit".to_string(),
+            Some(TrapType::Unknown) | None => signal.to_string(),
+        },
+        StopReason::Exited(code) => format!("exited ({code})"),
+        StopReason::Terminated(signal) => format!("terminated ({signal})"),
+    }
+}
+
+/// The thread's PC and the symbol containing it, e.g. `0x401136 <main+4>`.
+fn describe_location(process: &Process, thread: &Thread) -> String {
+    let Some(pc) = process.thread_pc(thread.tid()) else {
+        return String::new();
+    };
+    match process.symbolize(pc) {
+        Some(symbol) => format!("{pc} <{symbol}>"),
+        None => pc.to_string(),
+    }
+}
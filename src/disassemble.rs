@@ -0,0 +1,130 @@
+//! Machine code disassembly, currently only for x86_64 (via `iced-x86`).
+
+use anyhow::Result;
+
+use crate::process::stoppoint::VirtualAddress;
+
+/// The longest possible x86 instruction.
+pub const MAX_INSTRUCTION_LEN: usize = 15;
+
+/// A single decoded instruction.
+#[derive(Clone, Debug)]
+pub struct Instruction {
+    pub address: VirtualAddress,
+    pub bytes: Vec<u8>,
+    /// Intel syntax, e.g. `mov rbp, rsp`.
+    pub text: String,
+    /// The address a call or jump goes to, or a RIP-relative memory operand
+    /// refers to, which is worth labelling with its symbol.
+    pub target: Option<VirtualAddress>,
+}
+
+impl Instruction {
+    /// The instruction text, with the target labelled by `symbolize`, e.g.
+    /// `call 0x5555555551a0 <foo>`.
+    pub fn annotated_text(&self, symbolize: impl Fn(VirtualAddress) -> Option<String>) -> String {
+        match self.target.and_then(symbolize) {
+            Some(symbol) => format!("{} <{symbol}>", self.text),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Decode up to `count` instructions from `code`, which is located at `address`.
+/// Decoding stops early at the end of `code`.
+#[cfg(target_arch = "x86_64")]
+pub fn disassemble(code: &[u8], address: VirtualAddress, count: usize) -> Result<Vec<Instruction>> {
+    use iced_x86::{Decoder, DecoderOptions, Formatter, IntelFormatter, OpKind};
+
+    let mut decoder = Decoder::with_ip(64, code, address.addr(), DecoderOptions::NONE);
+    let mut formatter = IntelFormatter::new();
+    // match the way we print addresses everywhere else, e.g. `call 0x401136`
+    let options = formatter.options_mut();
+    options.set_hex_prefix("0x");
+    options.set_hex_suffix("");
+    options.set_uppercase_hex(false);
+    options.set_branch_leading_zeros(false);
+    options.set_space_after_operand_separator(true);
+    let mut instructions = Vec::with_capacity(count.min(code.len()));
+    let mut decoded = iced_x86::Instruction::default();
+
+    while decoder.can_decode() && instructions.len() < count {
+        let offset = decoder.position();
+        decoder.decode_out(&mut decoded);
+        // an instruction cut off by the end of the buffer, rather than invalid
+        if decoded.is_invalid() && decoder.last_error() == iced_x86::DecoderError::NoMoreBytes {
+            break;
+        }
+
+        let mut text = String::new();
+        formatter.format(&decoded, &mut text);
+        let target = match decoded.op0_kind() {
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => {
+                Some(decoded.near_branch_target())
+            }
+            _ if decoded.is_ip_rel_memory_operand() => Some(decoded.ip_rel_memory_address()),
+            _ => None,
+        };
+        instructions.push(Instruction {
+            address: VirtualAddress::from(decoded.ip()),
+            bytes: code[offset..offset + decoded.len()].to_vec(),
+            text,
+            target: target.map(VirtualAddress::from),
+        });
+    }
+    Ok(instructions)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn disassemble(
+    _code: &[u8],
+    _address: VirtualAddress,
+    _count: usize,
+) -> Result<Vec<Instruction>> {
+    Err(anyhow::anyhow!(
+        "Disassembly is not supported on this architecture"
+    ))
+}
+
+#[cfg(all(test, target_arch = "x86_64"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_until_end_of_code() {
+        // push rbp; mov rbp,rsp; ret; then the first byte of a `mov`
+        let code = [0x55, 0x48, 0x89, 0xe5, 0xc3, 0x48];
+        let instructions = disassemble(&code, VirtualAddress::from(0x1000), 10).unwrap();
+        let text: Vec<_> = instructions.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(text, ["push rbp", "mov rbp, rsp", "ret"]);
+        assert_eq!(instructions[1].address, VirtualAddress::from(0x1001));
+        assert_eq!(instructions[1].bytes, [0x48, 0x89, 0xe5]);
+
+        let instructions = disassemble(&code, VirtualAddress::from(0x1000), 2).unwrap();
+        assert_eq!(instructions.len(), 2);
+    }
+
+    #[test]
+    fn branch_and_rip_relative_targets() {
+        // call 0x1010; lea rax,[rip+0x20]; nop
+        let code = [
+            0xe8, 0x0b, 0x00, 0x00, 0x00, 0x48, 0x8d, 0x05, 0x20, 0x00, 0x00, 0x00, 0x90,
+        ];
+        let instructions = disassemble(&code, VirtualAddress::from(0x1000), 10).unwrap();
+        let targets: Vec<_> = instructions.iter().map(|i| i.target).collect();
+        assert_eq!(
+            targets,
+            [
+                Some(VirtualAddress::from(0x1010)),
+                Some(VirtualAddress::from(0x102c)),
+                None
+            ]
+        );
+
+        let symbolize = |address: VirtualAddress| Some(format!("foo+{}", address.addr() - 0x1010));
+        assert_eq!(
+            instructions[0].annotated_text(symbolize),
+            "call 0x1010 <foo+0>"
+        );
+    }
+}
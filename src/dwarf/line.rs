@@ -0,0 +1,247 @@
+//! The DWARF line table, flattened into an address ordered index.
+
+use anyhow::{Result, anyhow};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::dwarf::{DwarfSlice, load_dwarf};
+use crate::elf::Elf;
+
+/// Addresses used to mark the line sequences of functions removed by the
+/// linker (e.g. `--gc-sections`); these overlap real code.
+fn is_tombstone(address: u64) -> bool {
+    address == 0 || address >= u64::MAX - 1
+}
+
+/// A row of the line table: the source location of the instructions starting
+/// at `address` (up to the next row's address).
+#[derive(Clone, Copy, Debug)]
+pub struct LineRow {
+    /// File address.
+    pub address: u64,
+    /// Index into [`LineTable::files`].
+    pub file: usize,
+    /// Line number, 0 if the instructions don't correspond to any line.
+    pub line: u64,
+    pub column: u64,
+    /// Recommended breakpoint location (the start of a statement).
+    pub is_stmt: bool,
+    /// Where the function's prologue ends, if the compiler told us.
+    pub prologue_end: bool,
+    /// First address past the end of a sequence; doesn't describe any code.
+    pub end_sequence: bool,
+}
+
+/// A source file and line, as shown to the user.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceLocation {
+    pub file: PathBuf,
+    pub line: u64,
+}
+
+impl fmt::Display for SourceLocation {
+    /// Only the file name is shown, to keep it short.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.file.file_name().unwrap_or(self.file.as_os_str());
+        write!(f, "{}:{}", name.to_string_lossy(), self.line)
+    }
+}
+
+/// The line tables of all compilation units in an object.
+#[derive(Debug, Default)]
+pub struct LineTable {
+    /// Every file referred to by the rows, with paths made absolute (where the
+    /// compilation directory is known).
+    files: Vec<PathBuf>,
+    /// All rows, with sequences sorted by address. Within a sequence the rows
+    /// are in ascending address order, ending with an `end_sequence` row.
+    rows: Vec<LineRow>,
+}
+
+impl LineTable {
+    /// Parse the `.debug_line` section (DWARF 2 through 5). Returns `None` if the
+    /// object has no line information.
+    pub fn parse(elf: &Elf) -> Result<Option<Self>> {
+        if elf.section_by_name(".debug_line").is_none() {
+            return Ok(None);
+        }
+        let dwarf = load_dwarf(elf).map_err(|e| anyhow!("Cannot load DWARF: {e}"))?;
+
+        let mut files: Vec<PathBuf> = Vec::new();
+        let mut file_indices: HashMap<PathBuf, usize> = HashMap::new();
+        // sequences, keyed by start address
+        let mut sequences: BTreeMap<u64, Vec<LineRow>> = BTreeMap::new();
+
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+
+            // the unit's file indices, mapped to ours
+            let mut unit_files: HashMap<u64, usize> = HashMap::new();
+            let mut sequence = Vec::new();
+            let mut rows = program.rows();
+            while let Some((header, row)) = rows.next_row()? {
+                let file = match unit_files.get(&row.file_index()) {
+                    Some(&idx) => idx,
+                    None => {
+                        let path = file_path(&dwarf, &unit, header, row.file_index())
+                            .unwrap_or_else(|| PathBuf::from("<unknown>"));
+                        let idx = *file_indices.entry(path.clone()).or_insert_with(|| {
+                            files.push(path);
+                            files.len() - 1
+                        });
+                        unit_files.insert(row.file_index(), idx);
+                        idx
+                    }
+                };
+
+                sequence.push(LineRow {
+                    address: row.address(),
+                    file,
+                    line: row.line().map_or(0, |l| l.get()),
+                    column: match row.column() {
+                        gimli::ColumnType::LeftEdge => 0,
+                        gimli::ColumnType::Column(c) => c.get(),
+                    },
+                    is_stmt: row.is_stmt(),
+                    prologue_end: row.prologue_end(),
+                    end_sequence: row.end_sequence(),
+                });
+
+                if row.end_sequence() {
+                    let sequence = std::mem::take(&mut sequence);
+                    let start = sequence[0].address;
+                    if !is_tombstone(start) {
+                        sequences.insert(start, sequence);
+                    }
+                }
+            }
+        }
+
+        let rows = sequences.into_values().flatten().collect();
+        Ok(Some(LineTable { files, rows }))
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    pub fn file(&self, row: &LineRow) -> &Path {
+        &self.files[row.file]
+    }
+
+    pub fn rows(&self) -> &[LineRow] {
+        &self.rows
+    }
+
+    /// The row describing the instruction at the given file address.
+    pub fn row_for_address(&self, file_address: u64) -> Option<&LineRow> {
+        let idx = self.rows.partition_point(|r| r.address <= file_address);
+        let row = self.rows[..idx].last()?;
+        // past the end of the sequence, so not covered by any row
+        if row.end_sequence {
+            return None;
+        }
+        Some(row)
+    }
+
+    /// The source location of the instruction at the given file address.
+    pub fn location_for_address(&self, file_address: u64) -> Option<SourceLocation> {
+        let row = self.row_for_address(file_address)?;
+        (row.line != 0).then(|| SourceLocation {
+            file: self.file(row).to_path_buf(),
+            line: row.line,
+        })
+    }
+
+    /// The files matching a user supplied path: either the same path, or one
+    /// ending with it (so `src/main.rs` matches `/home/me/proj/src/main.rs`).
+    pub fn matching_files(&self, path: &Path) -> Vec<usize> {
+        self.files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| file.as_path() == path || file.ends_with(path))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// File addresses to break at for the given source line.
+    ///
+    /// Like gdb, if the line has no code the next line that does is used
+    /// instead (e.g. for a blank line or comment). A line may have several
+    /// addresses, e.g. if it was inlined or the loop condition was duplicated,
+    /// so we take the start of each run of rows for the line.
+    pub fn addresses_for_line(&self, path: &Path, line: u64) -> Vec<u64> {
+        let mut addresses = Vec::new();
+        for file in self.matching_files(path) {
+            let candidates = || {
+                self.rows
+                    .iter()
+                    .filter(move |r| r.file == file && r.is_stmt && !r.end_sequence)
+            };
+            let Some(best) = candidates().map(|r| r.line).filter(|&l| l >= line).min() else {
+                continue;
+            };
+
+            for (idx, row) in self.rows.iter().enumerate() {
+                if row.file != file || row.line != best || !row.is_stmt || row.end_sequence {
+                    continue;
+                }
+                let continues_run = idx > 0 && {
+                    let prev = &self.rows[idx - 1];
+                    !prev.end_sequence && prev.file == file && prev.line == best
+                };
+                if !continues_run {
+                    addresses.push(row.address);
+                }
+            }
+        }
+        addresses.sort();
+        addresses.dedup();
+        addresses
+    }
+
+    /// The end of the prologue of the function spanning `[low, high)`: the
+    /// address marked `prologue_end` if there is one, otherwise the start of
+    /// the second line in the function (as gdb does).
+    pub fn prologue_end(&self, low: u64, high: u64) -> Option<u64> {
+        let start = self.rows.partition_point(|r| r.address < low);
+        let rows = self.rows[start..]
+            .iter()
+            .take_while(|r| r.address < high && !r.end_sequence);
+
+        if let Some(row) = rows.clone().find(|r| r.prologue_end) {
+            return Some(row.address);
+        }
+        let first_line = rows.clone().next()?.line;
+        rows.skip(1)
+            .find(|r| r.is_stmt && r.line != first_line && r.address > low)
+            .map(|r| r.address)
+    }
+}
+
+/// Build the full path of a file in the line program header.
+fn file_path(
+    dwarf: &gimli::Dwarf<DwarfSlice<'_>>,
+    unit: &gimli::Unit<DwarfSlice<'_>>,
+    header: &gimli::LineProgramHeader<DwarfSlice<'_>>,
+    index: u64,
+) -> Option<PathBuf> {
+    let file = header.file(index)?;
+    let mut path = PathBuf::new();
+    // later components replace earlier ones if they're absolute
+    if let Some(comp_dir) = &unit.comp_dir {
+        path.push(comp_dir.to_string_lossy().as_ref());
+    }
+    if let Some(dir) = file.directory(header) {
+        let dir = dwarf.attr_string(unit, dir).ok()?;
+        path.push(dir.to_string_lossy().as_ref());
+    }
+    let name = dwarf.attr_string(unit, file.path_name()).ok()?;
+    path.push(name.to_string_lossy().as_ref());
+    Some(path)
+}
@@ -0,0 +1,24 @@
+//! DWARF debug information, read with the `gimli` crate.
+//!
+//! Currently this is only the line table (`.debug_line`), which maps between
+//! addresses and source locations.
+
+use gimli::{EndianSlice, LittleEndian};
+
+use crate::elf::Elf;
+
+pub mod line;
+
+/// DWARF sections borrowed from the bytes of an ELF file.
+pub(crate) type DwarfSlice<'a> = EndianSlice<'a, LittleEndian>;
+
+/// Load the DWARF sections of an ELF object. Missing sections are empty.
+pub(crate) fn load_dwarf(elf: &Elf) -> gimli::Result<gimli::Dwarf<DwarfSlice<'_>>> {
+    gimli::Dwarf::load(|id| -> gimli::Result<DwarfSlice<'_>> {
+        let data = elf
+            .section_by_name(id.name())
+            .map(|section| elf.section_data(section))
+            .unwrap_or(&[]);
+        Ok(EndianSlice::new(data, LittleEndian))
+    })
+}
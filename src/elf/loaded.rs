@@ -0,0 +1,237 @@
+//! The set of ELF objects (the executable and shared objects) loaded into the
+//! inferior, and where they are loaded.
+
+use anyhow::{Result, anyhow};
+use nix::unistd::Pid;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{trace, warn};
+
+use crate::dwarf::line::SourceLocation;
+use crate::elf::{Elf, Symbol, SymbolType};
+use crate::process::stoppoint::VirtualAddress;
+
+/// One line of `/proc/<pid>/maps`.
+#[derive(Clone, Debug)]
+pub struct MemoryMapping {
+    pub start: u64,
+    pub end: u64,
+    pub permissions: String,
+    /// Offset into the mapped file.
+    pub offset: u64,
+    /// The mapped file, or pseudo-path such as `[stack]`; `None` for anonymous
+    /// mappings.
+    pub path: Option<String>,
+}
+
+impl MemoryMapping {
+    pub fn is_executable(&self) -> bool {
+        self.permissions.contains('x')
+    }
+
+    /// The path of the backing file, if this mapping is of a regular file.
+    pub fn file(&self) -> Option<&Path> {
+        self.path
+            .as_deref()
+            .filter(|p| p.starts_with('/') && !p.ends_with(" (deleted)"))
+            .map(Path::new)
+    }
+}
+
+/// Parse `/proc/<pid>/maps`.
+pub fn read_memory_maps(pid: Pid) -> Result<Vec<MemoryMapping>> {
+    let path = format!("/proc/{pid}/maps");
+    let maps = fs::read_to_string(&path).map_err(|e| anyhow!("Cannot read {path}: {e}"))?;
+    maps.lines().map(parse_mapping).collect()
+}
+
+fn parse_mapping(line: &str) -> Result<MemoryMapping> {
+    // e.g. "55d4c0a00000-55d4c0a01000 r--p 00000000 fd:01 1234   /usr/bin/cat"
+    let err = || anyhow!("Malformed memory mapping: {:?}", line);
+    let mut fields = line.splitn(6, ' ');
+    let (start, end) = fields
+        .next()
+        .and_then(|r| r.split_once('-'))
+        .ok_or_else(err)?;
+    let permissions = fields.next().ok_or_else(err)?.to_string();
+    let offset = fields.next().ok_or_else(err)?;
+    let _device = fields.next();
+    let _inode = fields.next();
+    let path = fields
+        .next()
+        .map(|p| p.trim_start().to_string())
+        .filter(|p| !p.is_empty());
+
+    let hex = |s: &str| u64::from_str_radix(s, 16).map_err(|_| err());
+    Ok(MemoryMapping {
+        start: hex(start)?,
+        end: hex(end)?,
+        permissions,
+        offset: hex(offset)?,
+        path,
+    })
+}
+
+/// Parse `/proc/<pid>/auxv` into a map of `AT_*` type to value.
+pub fn read_auxv(pid: Pid) -> Result<HashMap<u64, u64>> {
+    let path = format!("/proc/{pid}/auxv");
+    let data = fs::read(&path).map_err(|e| anyhow!("Cannot read {path}: {e}"))?;
+    let mut auxv = HashMap::new();
+    for entry in data.chunks_exact(16) {
+        let key = u64::from_ne_bytes(entry[..8].try_into().expect("8 bytes"));
+        let value = u64::from_ne_bytes(entry[8..].try_into().expect("8 bytes"));
+        if key == libc::AT_NULL {
+            break;
+        }
+        auxv.insert(key, value);
+    }
+    Ok(auxv)
+}
+
+/// The executable, and every shared object we've seen mapped into the inferior.
+#[derive(Debug)]
+pub struct LoadedObjects {
+    /// The executable is always first.
+    objects: Vec<Elf>,
+    /// Files we've tried to load (successfully or not), so we don't keep
+    /// re-parsing them on every stop.
+    seen: HashSet<PathBuf>,
+}
+
+impl LoadedObjects {
+    pub fn new(executable: Elf) -> Self {
+        let seen = HashSet::from([executable.path().to_path_buf()]);
+        Self {
+            objects: vec![executable],
+            seen,
+        }
+    }
+
+    pub fn executable(&self) -> &Elf {
+        &self.objects[0]
+    }
+
+    /// All objects, starting with the executable.
+    pub fn iter(&self) -> impl Iterator<Item = &Elf> {
+        self.objects.iter()
+    }
+
+    /// Bring the load biases and set of shared objects up to date with the
+    /// inferior's current memory mappings.
+    pub fn refresh(&mut self, pid: Pid) -> Result<()> {
+        let maps = read_memory_maps(pid)?;
+
+        // The entry point is the most reliable way to find the executable, as
+        // it works even when the executable's path no longer matches (e.g.
+        // it's been replaced on disk).
+        let auxv = read_auxv(pid)?;
+        let executable = &mut self.objects[0];
+        match auxv.get(&libc::AT_ENTRY) {
+            Some(&entry) => {
+                let bias = executable.load_bias_from_entry(entry);
+                executable.set_load_bias(bias);
+            }
+            None => warn!(?pid, "No AT_ENTRY in auxv, cannot compute load bias"),
+        }
+
+        for mapping in &maps {
+            let Some(file) = mapping.file() else {
+                continue;
+            };
+            if let Some(elf) = self.objects[1..].iter_mut().find(|o| o.path() == file) {
+                // the object may have been unloaded and loaded again elsewhere
+                if mapping.offset == 0
+                    && let Some(bias) = elf.load_bias_from_mapping(mapping.start, mapping.offset)
+                {
+                    elf.set_load_bias(bias);
+                }
+                continue;
+            }
+            if mapping.offset != 0 || !self.seen.insert(file.to_path_buf()) {
+                continue;
+            }
+
+            match Elf::open(file) {
+                Ok(mut elf) => {
+                    let Some(bias) = elf.load_bias_from_mapping(mapping.start, mapping.offset)
+                    else {
+                        continue;
+                    };
+                    elf.set_load_bias(bias);
+                    trace!(
+                        ?file,
+                        bias = format_args!("{bias:#x}"),
+                        "Loaded shared object"
+                    );
+                    self.objects.push(elf);
+                }
+                Err(e) => trace!(?file, "Not loading mapped file: {e}"),
+            }
+        }
+        Ok(())
+    }
+
+    /// The object whose loaded segments contain the given address.
+    pub fn object_containing(&self, address: VirtualAddress) -> Option<&Elf> {
+        self.objects.iter().find(|o| o.contains(address))
+    }
+
+    /// Runtime address of the first defined symbol with the given name,
+    /// searching the executable first.
+    pub fn symbol_address(&self, name: &str) -> Option<VirtualAddress> {
+        self.objects.iter().find_map(|elf| {
+            elf.symbols_by_name(name)
+                .next()
+                .map(|sym| elf.to_virtual(sym.value))
+        })
+    }
+
+    /// Runtime addresses of every function with the given name, just past
+    /// their prologues.
+    pub fn find_functions(&self, name: &str) -> Vec<VirtualAddress> {
+        let mut addresses: Vec<VirtualAddress> = self
+            .objects
+            .iter()
+            .flat_map(|elf| {
+                elf.symbols_by_name(name)
+                    .filter(|sym| sym.kind == SymbolType::Function)
+                    .map(|sym| elf.to_virtual(elf.skip_prologue(sym)))
+            })
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+        addresses
+    }
+
+    /// Runtime addresses to break at for a source line, in any object.
+    pub fn find_line(&self, file: &Path, line: u64) -> Vec<VirtualAddress> {
+        let mut addresses: Vec<VirtualAddress> = self
+            .objects
+            .iter()
+            .filter_map(|elf| Some((elf, elf.line_table()?)))
+            .flat_map(|(elf, table)| {
+                table
+                    .addresses_for_line(file, line)
+                    .into_iter()
+                    .map(|address| elf.to_virtual(address))
+            })
+            .collect();
+        addresses.sort();
+        addresses.dedup();
+        addresses
+    }
+
+    /// The source location of the given runtime address.
+    pub fn source_location(&self, address: VirtualAddress) -> Option<SourceLocation> {
+        let elf = self.object_containing(address)?;
+        elf.source_location(elf.to_file_address(address))
+    }
+
+    /// The symbol containing the given runtime address, and its object.
+    pub fn symbol_containing(&self, address: VirtualAddress) -> Option<(&Elf, &Symbol)> {
+        let elf = self.object_containing(address)?;
+        let symbol = elf.symbol_containing(elf.to_file_address(address))?;
+        Some((elf, symbol))
+    }
+}
@@ -0,0 +1,787 @@
+//! Parsing of ELF executables and shared objects.
+//!
+//! We only support 64-bit, little endian objects, which covers all of the
+//! architectures we target (x86_64, aarch64, riscv64). The whole file is read
+//! into memory, and headers, sections, segments, and symbols are parsed up
+//! front; section contents are borrowed from the file's bytes on demand (for
+//! DWARF, disassembly, etc).
+//!
+//! Addresses within an ELF file ("file addresses") are relative to where the
+//! object was linked; the "load bias" is the difference between that and where
+//! the object is mapped into the inferior. For non-PIE executables the bias is
+//! zero.
+
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::dwarf::line::{LineTable, SourceLocation};
+use crate::process::stoppoint::VirtualAddress;
+
+pub mod loaded;
+mod prologue;
+
+const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+
+const FILE_HEADER_SIZE: usize = 64;
+const SECTION_HEADER_SIZE: usize = 64;
+const PROGRAM_HEADER_SIZE: usize = 56;
+const SYMBOL_SIZE: usize = 24;
+
+/// Section index used when the real value doesn't fit in the file header.
+const SHN_XINDEX: u16 = 0xffff;
+
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_NOTE: u32 = 7;
+pub const SHT_NOBITS: u32 = 8;
+pub const SHT_DYNSYM: u32 = 11;
+
+pub const PT_LOAD: u32 = 1;
+pub const PT_NOTE: u32 = 4;
+
+/// Segment flag: the segment is executable.
+pub const PF_X: u32 = 1;
+
+const NT_GNU_BUILD_ID: u32 = 3;
+
+/// The `e_type` field of the file header.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ObjectType {
+    Relocatable,
+    /// Fixed address executable (non-PIE).
+    Executable,
+    /// Shared object or position independent executable.
+    SharedObject,
+    Core,
+    Other(u16),
+}
+
+impl From<u16> for ObjectType {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => ObjectType::Relocatable,
+            2 => ObjectType::Executable,
+            3 => ObjectType::SharedObject,
+            4 => ObjectType::Core,
+            other => ObjectType::Other(other),
+        }
+    }
+}
+
+/// The fields of the ELF file header we care about.
+#[derive(Clone, Debug)]
+pub struct FileHeader {
+    pub object_type: ObjectType,
+    /// `e_machine`, e.g. 62 (`EM_X86_64`).
+    pub machine: u16,
+    /// File address of the entry point.
+    pub entry: u64,
+    pub program_header_offset: u64,
+    pub section_header_offset: u64,
+    pub program_header_count: usize,
+    pub section_header_count: usize,
+    pub section_names_index: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct SectionHeader {
+    pub name: String,
+    pub kind: u32,
+    pub flags: u64,
+    /// File address of the section, zero if not loaded.
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub info: u32,
+    pub entry_size: u64,
+}
+
+impl SectionHeader {
+    pub fn contains(&self, file_address: u64) -> bool {
+        self.address != 0
+            && self.address <= file_address
+            && file_address < self.address.saturating_add(self.size)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProgramHeader {
+    pub kind: u32,
+    pub flags: u32,
+    pub offset: u64,
+    /// File address of the segment.
+    pub address: u64,
+    pub file_size: u64,
+    pub memory_size: u64,
+    pub align: u64,
+}
+
+impl ProgramHeader {
+    pub fn contains(&self, file_address: u64) -> bool {
+        self.address <= file_address && file_address < self.address.saturating_add(self.memory_size)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SymbolType {
+    NoType,
+    Object,
+    Function,
+    Section,
+    File,
+    Tls,
+    /// GNU indirect function (e.g. many of glibc's string functions).
+    IndirectFunction,
+    Other(u8),
+}
+
+impl From<u8> for SymbolType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SymbolType::NoType,
+            1 => SymbolType::Object,
+            2 => SymbolType::Function,
+            3 => SymbolType::Section,
+            4 => SymbolType::File,
+            6 => SymbolType::Tls,
+            10 => SymbolType::IndirectFunction,
+            other => SymbolType::Other(other),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SymbolBinding {
+    Local,
+    Global,
+    Weak,
+    Other(u8),
+}
+
+impl From<u8> for SymbolBinding {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SymbolBinding::Local,
+            1 => SymbolBinding::Global,
+            2 => SymbolBinding::Weak,
+            other => SymbolBinding::Other(other),
+        }
+    }
+}
+
+/// An entry from `.symtab` or `.dynsym`.
+#[derive(Clone, Debug)]
+pub struct Symbol {
+    /// The raw (possibly mangled) name.
+    pub name: String,
+    /// The demangled name, for Rust and C++ symbols. Rust names omit the hash
+    /// suffix, e.g. `my_crate::foo`.
+    pub demangled: Option<String>,
+    /// File address of the symbol.
+    pub value: u64,
+    pub size: u64,
+    pub kind: SymbolType,
+    pub binding: SymbolBinding,
+    /// Index of the section the symbol is defined in, zero if undefined.
+    pub section_index: u16,
+}
+
+impl Symbol {
+    /// The demangled name if there is one, otherwise the raw name.
+    pub fn display_name(&self) -> &str {
+        self.demangled.as_deref().unwrap_or(&self.name)
+    }
+
+    pub fn is_defined(&self) -> bool {
+        self.section_index != 0
+    }
+
+    pub fn contains(&self, file_address: u64) -> bool {
+        self.value <= file_address && file_address < self.value.saturating_add(self.size.max(1))
+    }
+}
+
+/// A parsed ELF object.
+pub struct Elf {
+    path: PathBuf,
+    data: Vec<u8>,
+    header: FileHeader,
+    sections: Vec<SectionHeader>,
+    segments: Vec<ProgramHeader>,
+    /// Defined symbols, sorted by address.
+    symbols: Vec<Symbol>,
+    /// Index into `symbols` by raw and demangled name.
+    symbols_by_name: HashMap<String, Vec<usize>>,
+    build_id: Option<Vec<u8>>,
+    /// Difference between the file addresses and where the object is loaded
+    /// in the inferior. Zero until known.
+    load_bias: u64,
+    /// The DWARF line table, parsed on first use.
+    line_table: OnceLock<Option<LineTable>>,
+}
+
+impl std::fmt::Debug for Elf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Elf")
+            .field("path", &self.path)
+            .field("header", &self.header)
+            .field("load_bias", &format_args!("{:#x}", self.load_bias))
+            .finish_non_exhaustive()
+    }
+}
+
+impl Elf {
+    /// Read and parse the ELF object at `path`.
+    pub fn open(path: &Path) -> Result<Self> {
+        let path =
+            fs::canonicalize(path).map_err(|e| anyhow!("Cannot resolve path {:?}: {e}", path))?;
+        let data = fs::read(&path).map_err(|e| anyhow!("Cannot read {:?}: {e}", path))?;
+        Self::parse(path, data)
+    }
+
+    /// Parse an ELF object from its bytes; `path` is only used for display and
+    /// for matching against the inferior's memory mappings.
+    pub fn parse(path: PathBuf, data: Vec<u8>) -> Result<Self> {
+        let bytes = Bytes(&data);
+        let header = parse_file_header(&bytes).map_err(|e| anyhow!("{:?}: {e}", path))?;
+        let sections = parse_sections(&bytes, &header)?;
+        let segments = parse_segments(&bytes, &header)?;
+
+        let mut elf = Elf {
+            path,
+            data,
+            header,
+            sections,
+            segments,
+            symbols: Vec::new(),
+            symbols_by_name: HashMap::new(),
+            build_id: None,
+            load_bias: 0,
+            line_table: OnceLock::new(),
+        };
+        elf.symbols = elf.parse_symbols()?;
+        for (idx, sym) in elf.symbols.iter().enumerate() {
+            for name in lookup_names(sym) {
+                let entries = elf.symbols_by_name.entry(name).or_default();
+                if entries.last() != Some(&idx) {
+                    entries.push(idx);
+                }
+            }
+        }
+        elf.build_id = elf.parse_build_id();
+        Ok(elf)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    pub fn sections(&self) -> &[SectionHeader] {
+        &self.sections
+    }
+
+    pub fn segments(&self) -> &[ProgramHeader] {
+        &self.segments
+    }
+
+    /// All defined symbols, sorted by address.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<&SectionHeader> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// The section which contains the given file address.
+    pub fn section_containing(&self, file_address: u64) -> Option<&SectionHeader> {
+        self.sections.iter().find(|s| s.contains(file_address))
+    }
+
+    /// The contents of a section; empty for sections without data in the file
+    /// (e.g. `.bss`).
+    pub fn section_data(&self, section: &SectionHeader) -> &[u8] {
+        if section.kind == SHT_NOBITS {
+            return &[];
+        }
+        let start = section.offset as usize;
+        let end = start.saturating_add(section.size as usize);
+        self.data.get(start..end).unwrap_or(&[])
+    }
+
+    /// The file's bytes at the given file address, up to the end of the
+    /// containing section.
+    pub fn bytes_at(&self, file_address: u64) -> Option<&[u8]> {
+        let section = self.section_containing(file_address)?;
+        let data = self.section_data(section);
+        data.get((file_address - section.address) as usize..)
+    }
+
+    /// The DWARF line table, if the object has debug info.
+    pub fn line_table(&self) -> Option<&LineTable> {
+        self.line_table
+            .get_or_init(|| {
+                LineTable::parse(self).unwrap_or_else(|e| {
+                    warn!(path = ?self.path, "Cannot parse line table: {e}");
+                    None
+                })
+            })
+            .as_ref()
+    }
+
+    /// The source location of the given file address, if known.
+    pub fn source_location(&self, file_address: u64) -> Option<SourceLocation> {
+        self.line_table()?.location_for_address(file_address)
+    }
+
+    /// The address of the first instruction after the function's prologue
+    /// (frame setup), which is where a breakpoint on the function should go so
+    /// the frame is complete when it's hit.
+    ///
+    /// The line table is used if there is one, otherwise we fall back to
+    /// recognising common instruction sequences.
+    pub fn skip_prologue(&self, function: &Symbol) -> u64 {
+        if let Some(end) = self.line_table().and_then(|table| {
+            table.prologue_end(function.value, function.value.saturating_add(function.size))
+        }) {
+            return end;
+        }
+        let Some(code) = self.bytes_at(function.value) else {
+            return function.value;
+        };
+        let len = function.size.min(code.len() as u64) as usize;
+        function.value + prologue::prologue_length(&code[..len]) as u64
+    }
+
+    /// All symbols with exactly the given name. Both raw and demangled names are
+    /// matched; C++ names match with or without their parameter list.
+    pub fn symbols_by_name(&self, name: &str) -> impl Iterator<Item = &Symbol> {
+        self.symbols_by_name
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|&idx| &self.symbols[idx])
+    }
+
+    /// The symbol starting exactly at the given file address, preferring
+    /// functions.
+    pub fn symbol_at(&self, file_address: u64) -> Option<&Symbol> {
+        let start = self.symbols.partition_point(|s| s.value < file_address);
+        let candidates = self.symbols[start..]
+            .iter()
+            .take_while(|s| s.value == file_address);
+        candidates
+            .clone()
+            .find(|s| s.kind == SymbolType::Function)
+            .or_else(|| candidates.clone().next())
+    }
+
+    /// The function or object symbol whose range contains the given file address.
+    pub fn symbol_containing(&self, file_address: u64) -> Option<&Symbol> {
+        let end = self.symbols.partition_point(|s| s.value <= file_address);
+        self.symbols[..end]
+            .iter()
+            .rev()
+            .filter(|s| matches!(s.kind, SymbolType::Function | SymbolType::Object))
+            .find(|s| s.contains(file_address))
+    }
+
+    /// The GNU build ID, if the object has one.
+    pub fn build_id(&self) -> Option<&[u8]> {
+        self.build_id.as_deref()
+    }
+
+    /// The build ID as a hex string, as shown by `readelf -n`/`file`.
+    pub fn build_id_hex(&self) -> Option<String> {
+        self.build_id.as_ref().map(|id| {
+            id.iter().fold(String::new(), |mut s, b| {
+                let _ = write!(s, "{b:02x}");
+                s
+            })
+        })
+    }
+
+    pub fn load_bias(&self) -> u64 {
+        self.load_bias
+    }
+
+    pub fn set_load_bias(&mut self, load_bias: u64) {
+        self.load_bias = load_bias;
+    }
+
+    /// Compute the load bias of an executable from the inferior's `AT_ENTRY`
+    /// auxiliary vector entry (the runtime address of the entry point).
+    pub fn load_bias_from_entry(&self, runtime_entry: u64) -> u64 {
+        runtime_entry.wrapping_sub(self.header.entry)
+    }
+
+    /// Compute the load bias from the start address of the mapping of file
+    /// offset `offset` of this object.
+    pub fn load_bias_from_mapping(&self, start: u64, offset: u64) -> Option<u64> {
+        let segment = self
+            .segments
+            .iter()
+            .filter(|s| s.kind == PT_LOAD)
+            .find(|s| page_align(s.offset) == offset)?;
+        Some(start.wrapping_sub(page_align(segment.address)))
+    }
+
+    /// Convert a file address into an address within the inferior.
+    pub fn to_virtual(&self, file_address: u64) -> VirtualAddress {
+        VirtualAddress::from(file_address.wrapping_add(self.load_bias))
+    }
+
+    /// Convert an address within the inferior into a file address.
+    pub fn to_file_address(&self, address: VirtualAddress) -> u64 {
+        address.addr().wrapping_sub(self.load_bias)
+    }
+
+    /// Whether the given address in the inferior is within one of this
+    /// object's loaded segments.
+    pub fn contains(&self, address: VirtualAddress) -> bool {
+        let file_address = self.to_file_address(address);
+        self.segments
+            .iter()
+            .any(|s| s.kind == PT_LOAD && s.contains(file_address))
+    }
+
+    fn parse_symbols(&self) -> Result<Vec<Symbol>> {
+        let bytes = Bytes(&self.data);
+        let mut symbols = Vec::new();
+
+        for table in self
+            .sections
+            .iter()
+            .filter(|s| s.kind == SHT_SYMTAB || s.kind == SHT_DYNSYM)
+        {
+            let Some(strings) = self.sections.get(table.link as usize) else {
+                return Err(anyhow!("Symbol table {} has no string table", table.name));
+            };
+            let count = table.size as usize / SYMBOL_SIZE;
+            for i in 0..count {
+                let entry = bytes.entry(table.offset, i, SYMBOL_SIZE)?;
+                let info = entry.u8(4)?;
+                let name = bytes.string_at(strings.offset, entry.u32(0)?)?;
+                let symbol = Symbol {
+                    demangled: demangle(&name),
+                    name,
+                    kind: SymbolType::from(info & 0xf),
+                    binding: SymbolBinding::from(info >> 4),
+                    section_index: entry.u16(6)?,
+                    value: entry.u64(8)?,
+                    size: entry.u64(16)?,
+                };
+                if symbol.name.is_empty() || !symbol.is_defined() {
+                    continue;
+                }
+                symbols.push(symbol);
+            }
+        }
+
+        // .dynsym is usually a subset of .symtab
+        symbols.sort_by(|a, b| a.value.cmp(&b.value).then_with(|| a.name.cmp(&b.name)));
+        symbols.dedup_by(|a, b| a.value == b.value && a.name == b.name);
+        Ok(symbols)
+    }
+
+    fn parse_build_id(&self) -> Option<Vec<u8>> {
+        let from_sections = self
+            .sections
+            .iter()
+            .filter(|s| s.kind == SHT_NOTE)
+            .map(|s| self.section_data(s));
+        let from_segments = self.segments.iter().filter(|s| s.kind == PT_NOTE).map(|s| {
+            let start = s.offset as usize;
+            self.data
+                .get(start..start.saturating_add(s.file_size as usize))
+                .unwrap_or(&[])
+        });
+
+        from_sections
+            .chain(from_segments)
+            .find_map(|notes| find_note(notes, b"GNU", NT_GNU_BUILD_ID))
+            .map(|desc| desc.to_vec())
+    }
+}
+
+/// Demangle a Rust (legacy or v0) or C++ symbol name.
+pub fn demangle(name: &str) -> Option<String> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        // the alternate format leaves off the hash
+        return Some(format!("{demangled:#}"));
+    }
+    if name.starts_with("_Z") {
+        return cpp_demangle::Symbol::new(name).ok()?.demangle().ok();
+    }
+    None
+}
+
+/// The names a symbol can be looked up by.
+fn lookup_names(symbol: &Symbol) -> Vec<String> {
+    let mut names = vec![symbol.name.clone()];
+    if let Some(demangled) = &symbol.demangled {
+        names.push(demangled.clone());
+        // `ns::foo(int)` can also be found as `ns::foo`
+        if let Some((base, _)) = demangled.split_once('(')
+            && !base.is_empty()
+        {
+            names.push(base.to_string());
+        }
+    }
+    names
+}
+
+/// Round down to the start of the page.
+fn page_align(value: u64) -> u64 {
+    value & !0xfff
+}
+
+/// Find the descriptor of the note with the given owner and type.
+fn find_note<'a>(mut notes: &'a [u8], owner: &[u8], kind: u32) -> Option<&'a [u8]> {
+    let align4 = |n: usize| (n + 3) & !3;
+    while notes.len() >= 12 {
+        let bytes = Bytes(notes);
+        let name_size = bytes.u32(0).ok()? as usize;
+        let desc_size = bytes.u32(4).ok()? as usize;
+        let note_type = bytes.u32(8).ok()?;
+        let name_start = 12;
+        let desc_start = name_start + align4(name_size);
+        let next = desc_start + align4(desc_size);
+
+        let name = notes.get(name_start..name_start + name_size)?;
+        let desc = notes.get(desc_start..desc_start + desc_size)?;
+        if note_type == kind && name.strip_suffix(b"\0").unwrap_or(name) == owner {
+            return Some(desc);
+        }
+        notes = notes.get(next..)?;
+    }
+    None
+}
+
+fn parse_file_header(bytes: &Bytes) -> Result<FileHeader> {
+    if bytes.0.len() < FILE_HEADER_SIZE || &bytes.0[..4] != ELF_MAGIC {
+        return Err(anyhow!("Not an ELF file"));
+    }
+    if bytes.u8(4)? != ELFCLASS64 {
+        return Err(anyhow!("Only 64-bit ELF files are supported"));
+    }
+    if bytes.u8(5)? != ELFDATA2LSB {
+        return Err(anyhow!("Only little endian ELF files are supported"));
+    }
+
+    Ok(FileHeader {
+        object_type: ObjectType::from(bytes.u16(16)?),
+        machine: bytes.u16(18)?,
+        entry: bytes.u64(24)?,
+        program_header_offset: bytes.u64(32)?,
+        section_header_offset: bytes.u64(40)?,
+        program_header_count: bytes.u16(56)? as usize,
+        section_header_count: bytes.u16(60)? as usize,
+        section_names_index: bytes.u16(62)? as usize,
+    })
+}
+
+fn parse_sections(bytes: &Bytes, header: &FileHeader) -> Result<Vec<SectionHeader>> {
+    if header.section_header_offset == 0 {
+        return Ok(Vec::new());
+    }
+    let section_at = |i: usize| -> Result<SectionHeader> {
+        let entry = bytes.entry(header.section_header_offset, i, SECTION_HEADER_SIZE)?;
+        Ok(SectionHeader {
+            name: String::new(),
+            kind: entry.u32(4)?,
+            flags: entry.u64(8)?,
+            address: entry.u64(16)?,
+            offset: entry.u64(24)?,
+            size: entry.u64(32)?,
+            link: entry.u32(40)?,
+            info: entry.u32(44)?,
+            entry_size: entry.u64(56)?,
+        })
+    };
+
+    // When there are too many sections for the file header, the real count
+    // and string table index are stored in the first section header.
+    let first = section_at(0)?;
+    let count = match header.section_header_count {
+        0 => first.size as usize,
+        n => n,
+    };
+    let names_index = match header.section_names_index as u16 {
+        SHN_XINDEX => first.link as usize,
+        n => n as usize,
+    };
+
+    let mut sections = (0..count).map(section_at).collect::<Result<Vec<_>>>()?;
+    let names_offset = sections
+        .get(names_index)
+        .filter(|s| s.kind == SHT_STRTAB)
+        .map(|s| s.offset);
+    if let Some(names_offset) = names_offset {
+        for (i, section) in sections.iter_mut().enumerate() {
+            let entry = bytes.entry(header.section_header_offset, i, SECTION_HEADER_SIZE)?;
+            section.name = bytes.string_at(names_offset, entry.u32(0)?)?;
+        }
+    }
+    Ok(sections)
+}
+
+fn parse_segments(bytes: &Bytes, header: &FileHeader) -> Result<Vec<ProgramHeader>> {
+    (0..header.program_header_count)
+        .map(|i| {
+            let entry = bytes.entry(header.program_header_offset, i, PROGRAM_HEADER_SIZE)?;
+            Ok(ProgramHeader {
+                kind: entry.u32(0)?,
+                flags: entry.u32(4)?,
+                offset: entry.u64(8)?,
+                address: entry.u64(16)?,
+                file_size: entry.u64(32)?,
+                memory_size: entry.u64(40)?,
+                align: entry.u64(48)?,
+            })
+        })
+        .collect()
+}
+
+/// Bounds checked, little endian reads from the file's bytes.
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Bytes<'a> {
+    /// Entry `index` of a table of `size` byte entries at offset `table`, e.g.
+    /// a section header.
+    fn entry(&self, table: u64, index: usize, size: usize) -> Result<Bytes<'a>> {
+        let start = usize::try_from(table)
+            .ok()
+            .and_then(|table| table.checked_add(index.checked_mul(size)?));
+        start
+            .and_then(|start| self.0.get(start..start.checked_add(size)?))
+            .map(Bytes)
+            .ok_or_else(|| {
+                anyhow!("ELF file truncated: cannot read entry {index} of the table at {table:#x}")
+            })
+    }
+
+    fn slice<const N: usize>(&self, off: usize) -> Result<[u8; N]> {
+        self.0
+            .get(off..off.saturating_add(N))
+            .and_then(|s| s.try_into().ok())
+            .ok_or_else(|| anyhow!("ELF file truncated: cannot read {N} bytes at {off:#x}"))
+    }
+
+    fn u8(&self, off: usize) -> Result<u8> {
+        Ok(self.slice::<1>(off)?[0])
+    }
+
+    fn u16(&self, off: usize) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.slice(off)?))
+    }
+
+    fn u32(&self, off: usize) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.slice(off)?))
+    }
+
+    fn u64(&self, off: usize) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.slice(off)?))
+    }
+
+    /// Read the string at offset `index` of the string table at `table`.
+    fn string_at(&self, table: u64, index: u32) -> Result<String> {
+        let off = usize::try_from(table)
+            .ok()
+            .and_then(|table| table.checked_add(index as usize))
+            .ok_or_else(|| anyhow!("ELF string offset {table:#x}+{index:#x} out of bounds"))?;
+        self.c_string(off)
+    }
+
+    /// Read a NUL-terminated string (e.g. from a string table).
+    fn c_string(&self, off: usize) -> Result<String> {
+        let rest = self
+            .0
+            .get(off..)
+            .ok_or_else(|| anyhow!("ELF string offset {off:#x} out of bounds"))?;
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| anyhow!("Unterminated ELF string at {off:#x}"))?;
+        Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn demangle_rust_and_cpp_names() {
+        assert_eq!(
+            demangle("_ZN8my_crate3foo17h0123456789abcdefE").as_deref(),
+            Some("my_crate::foo")
+        );
+        assert_eq!(
+            demangle("_RNvCs15kBYyAo9fc_8my_crate3foo").as_deref(),
+            Some("my_crate::foo")
+        );
+        assert_eq!(demangle("_ZN2ns3fooEi").as_deref(), Some("ns::foo(int)"));
+        assert_eq!(demangle("main"), None);
+    }
+
+    #[test]
+    fn hostile_offsets_are_errors() {
+        // a file header with the section and program header tables at the very
+        // end of the address space
+        let mut data = vec![0u8; FILE_HEADER_SIZE];
+        data[..4].copy_from_slice(ELF_MAGIC);
+        data[4] = ELFCLASS64;
+        data[5] = ELFDATA2LSB;
+        data[32..40].copy_from_slice(&(u64::MAX - 8).to_le_bytes());
+        data[40..48].copy_from_slice(&(u64::MAX - 8).to_le_bytes());
+        data[56..58].copy_from_slice(&2u16.to_le_bytes());
+        data[60..62].copy_from_slice(&2u16.to_le_bytes());
+        assert!(Elf::parse("hostile".into(), data).is_err());
+
+        let bytes = Bytes(&[0; 16]);
+        assert!(bytes.entry(8, usize::MAX, 64).is_err());
+        assert!(bytes.string_at(u64::MAX, u32::MAX).is_err());
+
+        let section = SectionHeader {
+            name: String::new(),
+            kind: 0,
+            flags: 0,
+            address: u64::MAX - 4,
+            offset: 0,
+            size: 16,
+            link: 0,
+            info: 0,
+            entry_size: 0,
+        };
+        assert!(section.contains(u64::MAX - 1));
+        assert!(!section.contains(0));
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn prologue_heuristics() {
+        // endbr64; push %rbp; mov %rsp,%rbp; sub $0x10,%rsp; mov %edi,-4(%rbp)
+        let code = [
+            0xf3, 0x0f, 0x1e, 0xfa, 0x55, 0x48, 0x89, 0xe5, 0x48, 0x83, 0xec, 0x10, 0x89, 0x7d,
+            0xfc,
+        ];
+        assert_eq!(prologue::prologue_length(&code), 12);
+        // no recognisable frame setup
+        assert_eq!(prologue::prologue_length(&[0x89, 0x7d, 0xfc]), 0);
+        // truncated
+        assert_eq!(prologue::prologue_length(&[0x55, 0x48, 0x83, 0xec]), 1);
+    }
+}
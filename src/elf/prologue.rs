@@ -0,0 +1,38 @@
+//! Heuristic prologue analysis, for when there's no debug info to tell us
+//! where a function's body starts.
+//!
+//! This recognises the common frame setup sequences emitted by GCC, Clang and
+//! rustc. Anything unrecognised ends the prologue, so at worst we break at the
+//! function's first instruction.
+
+/// Length in bytes of the prologue at the start of `code`.
+#[cfg(target_arch = "x86_64")]
+pub fn prologue_length(code: &[u8]) -> usize {
+    const PATTERNS: &[&[u8]] = &[
+        // endbr64
+        &[0xf3, 0x0f, 0x1e, 0xfa],
+        // push %rbp
+        &[0x55],
+        // mov %rsp,%rbp
+        &[0x48, 0x89, 0xe5],
+    ];
+
+    let mut len = 0;
+    for pattern in PATTERNS {
+        if code[len..].starts_with(pattern) {
+            len += pattern.len();
+        }
+    }
+    // sub $imm8,%rsp / sub $imm32,%rsp
+    match code.get(len..len + 3) {
+        Some([0x48, 0x83, 0xec]) if code.len() >= len + 4 => len += 4,
+        Some([0x48, 0x81, 0xec]) if code.len() >= len + 7 => len += 7,
+        _ => {}
+    }
+    len
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn prologue_length(_code: &[u8]) -> usize {
+    0
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+
+use crate::expression::{Span, error_at};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    /// Integer literal (hex, octal, binary, or decimal).
+    Number(u64),
+    /// Register reference, e.g. `$rip`. The name excludes the `$`.
+    Register(String),
+    /// Symbol name, e.g. `main` or `my_crate::foo`.
+    Identifier(String),
+    Plus,
+    Minus,
+    Star,
+    /// `!`
+    Not,
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    LParen,
+    RParen,
+    /// End of the input.
+    Eof,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+impl TokenKind {
+    /// How the token is referred to in error messages.
+    pub fn describe(&self) -> String {
+        match self {
+            TokenKind::Number(n) => format!("number `{n}`"),
+            TokenKind::Register(r) => format!("register `${r}`"),
+            TokenKind::Identifier(i) => format!("symbol `{i}`"),
+            TokenKind::Plus => "`+`".to_string(),
+            TokenKind::Minus => "`-`".to_string(),
+            TokenKind::Star => "`*`".to_string(),
+            TokenKind::Not => "`!`".to_string(),
+            TokenKind::Eq => "`==`".to_string(),
+            TokenKind::Ne => "`!=`".to_string(),
+            TokenKind::Lt => "`<`".to_string(),
+            TokenKind::Le => "`<=`".to_string(),
+            TokenKind::Gt => "`>`".to_string(),
+            TokenKind::Ge => "`>=`".to_string(),
+            TokenKind::And => "`&&`".to_string(),
+            TokenKind::Or => "`||`".to_string(),
+            TokenKind::LParen => "`(`".to_string(),
+            TokenKind::RParen => "`)`".to_string(),
+            TokenKind::Eof => "end of input".to_string(),
+        }
+    }
+}
+
+/// Split the input into tokens. The last token is always `Eof`.
+pub fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        let start = pos;
+        let kind = match c {
+            b' ' | b'\t' => {
+                pos += 1;
+                continue;
+            }
+            b'+' => {
+                pos += 1;
+                TokenKind::Plus
+            }
+            b'-' => {
+                pos += 1;
+                TokenKind::Minus
+            }
+            b'*' => {
+                pos += 1;
+                TokenKind::Star
+            }
+            // operators that are one or two characters long
+            b'!' | b'<' | b'>' => {
+                let followed_by_eq = bytes.get(pos + 1) == Some(&b'=');
+                pos += 1 + followed_by_eq as usize;
+                match (c, followed_by_eq) {
+                    (b'!', false) => TokenKind::Not,
+                    (b'!', true) => TokenKind::Ne,
+                    (b'<', false) => TokenKind::Lt,
+                    (b'<', true) => TokenKind::Le,
+                    (b'>', false) => TokenKind::Gt,
+                    _ => TokenKind::Ge,
+                }
+            }
+            // operators that must be doubled
+            b'=' | b'&' | b'|' => {
+                if bytes.get(pos + 1) != Some(&c) {
+                    let doubled =
+                        format!("unexpected character, did you mean `{0}{0}`?", c as char);
+                    return Err(error_at(input, Span::new(start, start + 1), &doubled));
+                }
+                pos += 2;
+                match c {
+                    b'=' => TokenKind::Eq,
+                    b'&' => TokenKind::And,
+                    _ => TokenKind::Or,
+                }
+            }
+            b'(' => {
+                pos += 1;
+                TokenKind::LParen
+            }
+            b')' => {
+                pos += 1;
+                TokenKind::RParen
+            }
+            b'0'..=b'9' => {
+                pos = scan_while(bytes, pos, |c| c.is_ascii_alphanumeric() || c == b'_');
+                TokenKind::Number(parse_number(input, Span::new(start, pos))?)
+            }
+            b'$' => {
+                pos = scan_while(bytes, pos + 1, |c| c.is_ascii_alphanumeric() || c == b'_');
+                if pos == start + 1 {
+                    return Err(error_at(
+                        input,
+                        Span::new(start, pos),
+                        "expected a register name after `$`",
+                    ));
+                }
+                TokenKind::Register(input[start + 1..pos].to_lowercase())
+            }
+            c if is_identifier_start(c) => {
+                pos = scan_identifier(bytes, pos);
+                TokenKind::Identifier(input[start..pos].to_string())
+            }
+            _ => {
+                let len = input[start..].chars().next().map_or(1, |c| c.len_utf8());
+                return Err(error_at(
+                    input,
+                    Span::new(start, start + len),
+                    "unexpected character",
+                ));
+            }
+        };
+        tokens.push(Token {
+            kind,
+            span: Span::new(start, pos),
+        });
+    }
+
+    tokens.push(Token {
+        kind: TokenKind::Eof,
+        span: Span::new(input.len(), input.len()),
+    });
+    Ok(tokens)
+}
+
+fn scan_while(bytes: &[u8], mut pos: usize, pred: impl Fn(u8) -> bool) -> usize {
+    while pos < bytes.len() && pred(bytes[pos]) {
+        pos += 1;
+    }
+    pos
+}
+
+fn is_identifier_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_' || c == b'.'
+}
+
+/// Identifiers may contain path separators (`::`), so that Rust and C++
+/// names can be used as-is.
+fn scan_identifier(bytes: &[u8], mut pos: usize) -> usize {
+    loop {
+        pos = scan_while(bytes, pos, |c| {
+            c.is_ascii_alphanumeric() || c == b'_' || c == b'.' || c == b'$'
+        });
+        if bytes.get(pos) == Some(&b':') && bytes.get(pos + 1) == Some(&b':') {
+            pos += 2;
+        } else {
+            return pos;
+        }
+    }
+}
+
+/// Parse an integer literal: `0x` hex, `0o` (or C-style leading `0`) octal,
+/// `0b` binary, or decimal. Underscores are allowed as separators.
+fn parse_number(input: &str, span: Span) -> Result<u64> {
+    let text = input[span.start..span.end].replace('_', "");
+    let lower = text.to_ascii_lowercase();
+    let (digits, radix) = if let Some(hex) = lower.strip_prefix("0x") {
+        (hex, 16)
+    } else if let Some(oct) = lower.strip_prefix("0o") {
+        (oct, 8)
+    } else if let Some(bin) = lower.strip_prefix("0b") {
+        (bin, 2)
+    } else if lower.len() > 1 && lower.starts_with('0') {
+        (&lower[1..], 8)
+    } else {
+        (lower.as_str(), 10)
+    };
+
+    u64::from_str_radix(digits, radix)
+        .map_err(|e| error_at(input, span, &format!("invalid number literal: {e}")))
+}
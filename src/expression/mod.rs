@@ -0,0 +1,558 @@
+//! A small expression language for command arguments.
+//!
+//! This covers address expressions, shared by every command that takes an
+//! address: integer literals (`0x401136`, `0o17`, `42`), register references
+//! (`$rip`), symbol names (`main`, `my_crate::foo`), and `+`/`-`/`*`
+//! arithmetic with parentheses, e.g. `$rsp+0x10` or `main+4`.
+//!
+//! The same expressions serve as conditions (e.g. for breakpoints), with
+//! comparisons (`==`, `!=`, `<`, `<=`, `>`, `>=`), which are 1 when true and
+//! 0 when false, `&&`, `||` and `!`, and memory reads: `*$rsp` reads 8 bytes,
+//! and a cast reads fewer, e.g. `*(u32*)($rsp+8)`. Any non-zero value is true,
+//! e.g. `$rdi == 0x10 && *(u32*)($rsp+8) > 3`.
+//!
+//! Parsing is independent of the inferior, so syntax errors are reported as
+//! soon as a command is entered. Evaluation needs the registers, symbols, and
+//! memory of the inferior, provided via [`EvalContext`].
+
+use anyhow::{Result, anyhow};
+use std::fmt;
+
+use crate::expression::lexer::{Token, TokenKind, tokenize};
+
+mod lexer;
+
+/// Byte range of a token/expression within the input string.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+}
+
+/// Build an error that points at the offending part of the input, e.g.
+///
+/// ```text
+/// unexpected `)` at column 6
+///   $rip+)
+///        ^
+/// ```
+fn error_at(input: &str, span: Span, message: &str) -> anyhow::Error {
+    let width = input[span.start..span.end].chars().count().max(1);
+    let indent = input[..span.start].chars().count();
+    anyhow!(
+        "{message} at column {}\n  {input}\n  {}{}",
+        indent + 1,
+        " ".repeat(indent),
+        "^".repeat(width)
+    )
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// Logical and, which only evaluates the right hand side if the left
+    /// is true.
+    And,
+    /// Logical or, which only evaluates the right hand side if the left
+    /// is false.
+    Or,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprKind {
+    Literal(u64),
+    Register(String),
+    Symbol(String),
+    Negate(Box<Expr>),
+    /// Logical not: 1 if the operand is 0, otherwise 0.
+    Not(Box<Expr>),
+    /// Read `size` bytes of memory at the address, e.g. `*(u32*)$rsp`.
+    Deref {
+        size: usize,
+        address: Box<Expr>,
+    },
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
+
+/// A node in the expression tree, along with where it came from in the input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+/// The values an expression may refer to, typically backed by the inferior.
+pub trait EvalContext {
+    /// Value of the named register (name without the `$`, lowercase).
+    fn register_value(&self, name: &str) -> Result<u64>;
+    /// Address of the named symbol.
+    fn symbol_address(&self, name: &str) -> Result<u64>;
+    /// The `size` (1, 2, 4, or 8) bytes of memory at `address`, as an
+    /// unsigned little endian value.
+    fn read_memory(&self, address: u64, size: usize) -> Result<u64>;
+}
+
+/// Context for expressions which may only contain literals.
+struct Constant;
+
+impl EvalContext for Constant {
+    fn register_value(&self, _name: &str) -> Result<u64> {
+        Err(anyhow!("registers are not allowed here"))
+    }
+
+    fn symbol_address(&self, _name: &str) -> Result<u64> {
+        Err(anyhow!("symbols are not allowed here"))
+    }
+
+    fn read_memory(&self, _address: u64, _size: usize) -> Result<u64> {
+        Err(anyhow!("memory reads are not allowed here"))
+    }
+}
+
+/// A parsed expression, retaining its source text for error reporting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    source: String,
+    root: Expr,
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Expression {
+    /// Parse an address expression or condition.
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser {
+            input,
+            tokens,
+            pos: 0,
+        };
+        let root = parser.expression()?;
+        parser.expect(TokenKind::Eof)?;
+        Ok(Self {
+            source: input.to_string(),
+            root,
+        })
+    }
+
+    /// Parse the arguments of a command as a single expression, allowing for
+    /// whitespace within the expression (e.g. `break $rip + 8`).
+    pub fn from_args(args: &[String]) -> Result<Self> {
+        if args.is_empty() {
+            return Err(anyhow!("expected an address expression"));
+        }
+        Self::parse(&args.join(" "))
+    }
+
+    /// Parse and evaluate an expression that must not refer to the inferior,
+    /// e.g. a length or a byte value.
+    pub fn constant(input: &str) -> Result<u64> {
+        Self::parse(input)?.evaluate(&Constant)
+    }
+
+    pub fn root(&self) -> &Expr {
+        &self.root
+    }
+
+    /// Evaluate the expression to a 64-bit value. Arithmetic wraps, as it
+    /// would in the inferior, and comparisons are unsigned.
+    pub fn evaluate(&self, ctx: &dyn EvalContext) -> Result<u64> {
+        self.eval(&self.root, ctx)
+    }
+
+    fn eval(&self, expr: &Expr, ctx: &dyn EvalContext) -> Result<u64> {
+        let value = match &expr.kind {
+            ExprKind::Literal(n) => *n,
+            ExprKind::Register(name) => ctx
+                .register_value(name)
+                .map_err(|e| error_at(&self.source, expr.span, &e.to_string()))?,
+            ExprKind::Symbol(name) => ctx
+                .symbol_address(name)
+                .map_err(|e| error_at(&self.source, expr.span, &e.to_string()))?,
+            ExprKind::Negate(inner) => self.eval(inner, ctx)?.wrapping_neg(),
+            ExprKind::Not(inner) => (self.eval(inner, ctx)? == 0) as u64,
+            ExprKind::Deref { size, address } => {
+                let address = self.eval(address, ctx)?;
+                ctx.read_memory(address, *size)
+                    .map_err(|e| error_at(&self.source, expr.span, &e.to_string()))?
+            }
+            ExprKind::Binary(BinaryOp::And, lhs, rhs) => {
+                (self.eval(lhs, ctx)? != 0 && self.eval(rhs, ctx)? != 0) as u64
+            }
+            ExprKind::Binary(BinaryOp::Or, lhs, rhs) => {
+                (self.eval(lhs, ctx)? != 0 || self.eval(rhs, ctx)? != 0) as u64
+            }
+            ExprKind::Binary(op, lhs, rhs) => {
+                let lhs = self.eval(lhs, ctx)?;
+                let rhs = self.eval(rhs, ctx)?;
+                match op {
+                    BinaryOp::Add => lhs.wrapping_add(rhs),
+                    BinaryOp::Sub => lhs.wrapping_sub(rhs),
+                    BinaryOp::Mul => lhs.wrapping_mul(rhs),
+                    BinaryOp::Eq => (lhs == rhs) as u64,
+                    BinaryOp::Ne => (lhs != rhs) as u64,
+                    BinaryOp::Lt => (lhs < rhs) as u64,
+                    BinaryOp::Le => (lhs <= rhs) as u64,
+                    BinaryOp::Gt => (lhs > rhs) as u64,
+                    BinaryOp::Ge => (lhs >= rhs) as u64,
+                    BinaryOp::And | BinaryOp::Or => unreachable!("short circuited above"),
+                }
+            }
+        };
+        Ok(value)
+    }
+}
+
+/// Recursive descent parser over the token stream.
+///
+/// ```text
+/// expression := and ('||' and)*
+/// and        := comparison ('&&' comparison)*
+/// comparison := sum (('==' | '!=' | '<' | '<=' | '>' | '>=') sum)?
+/// sum        := product (('+' | '-') product)*
+/// product    := unary ('*' unary)*
+/// unary      := '-' unary | '!' unary | '*' cast? unary | primary
+/// cast       := '(' ('u8' | 'u16' | 'u32' | 'u64') '*' ')'
+/// primary    := number | register | symbol | '(' expression ')'
+/// ```
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if !matches!(token.kind, TokenKind::Eof) {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, kind: TokenKind) -> Result<Token> {
+        let token = self.peek();
+        if token.kind == kind {
+            return Ok(self.advance());
+        }
+        Err(self.unexpected(&format!("expected {}", kind.describe())))
+    }
+
+    fn unexpected(&self, expected: &str) -> anyhow::Error {
+        let token = self.peek();
+        error_at(
+            self.input,
+            token.span,
+            &format!("{expected}, found {}", token.kind.describe()),
+        )
+    }
+
+    fn expression(&mut self) -> Result<Expr> {
+        self.binary(Self::and, |kind| match kind {
+            TokenKind::Or => Some(BinaryOp::Or),
+            _ => None,
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        self.binary(Self::comparison, |kind| match kind {
+            TokenKind::And => Some(BinaryOp::And),
+            _ => None,
+        })
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let lhs = self.sum()?;
+        let op = match self.peek().kind {
+            TokenKind::Eq => BinaryOp::Eq,
+            TokenKind::Ne => BinaryOp::Ne,
+            TokenKind::Lt => BinaryOp::Lt,
+            TokenKind::Le => BinaryOp::Le,
+            TokenKind::Gt => BinaryOp::Gt,
+            TokenKind::Ge => BinaryOp::Ge,
+            _ => return Ok(lhs),
+        };
+        self.advance();
+        let rhs = self.sum()?;
+        let span = lhs.span.to(rhs.span);
+        Ok(Expr {
+            kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+            span,
+        })
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        self.binary(Self::product, |kind| match kind {
+            TokenKind::Plus => Some(BinaryOp::Add),
+            TokenKind::Minus => Some(BinaryOp::Sub),
+            _ => None,
+        })
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        self.binary(Self::unary, |kind| match kind {
+            TokenKind::Star => Some(BinaryOp::Mul),
+            _ => None,
+        })
+    }
+
+    /// A left associative chain of operands, joined by the operators `op`
+    /// accepts.
+    fn binary(
+        &mut self,
+        operand: fn(&mut Self) -> Result<Expr>,
+        op: fn(&TokenKind) -> Option<BinaryOp>,
+    ) -> Result<Expr> {
+        let mut lhs = operand(self)?;
+        while let Some(op) = op(&self.peek().kind) {
+            self.advance();
+            let rhs = operand(self)?;
+            let span = lhs.span.to(rhs.span);
+            lhs = Expr {
+                kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+                span,
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        let op = self.peek().clone();
+        if !matches!(op.kind, TokenKind::Minus | TokenKind::Not | TokenKind::Star) {
+            return self.primary();
+        }
+        self.advance();
+        let size = match op.kind {
+            TokenKind::Star => self.cast()?,
+            _ => 8,
+        };
+        let inner = Box::new(self.unary()?);
+        let span = op.span.to(inner.span);
+        let kind = match op.kind {
+            TokenKind::Minus => ExprKind::Negate(inner),
+            TokenKind::Not => ExprKind::Not(inner),
+            _ => ExprKind::Deref {
+                size,
+                address: inner,
+            },
+        };
+        Ok(Expr { kind, span })
+    }
+
+    /// The number of bytes a `*` reads: set by a pointer cast like `(u32*)`,
+    /// if one follows, otherwise 8.
+    fn cast(&mut self) -> Result<usize> {
+        let is_cast = matches!(
+            self.tokens.get(self.pos..self.pos + 4),
+            Some([
+                Token {
+                    kind: TokenKind::LParen,
+                    ..
+                },
+                Token {
+                    kind: TokenKind::Identifier(_),
+                    ..
+                },
+                Token {
+                    kind: TokenKind::Star,
+                    ..
+                },
+                Token {
+                    kind: TokenKind::RParen,
+                    ..
+                },
+            ])
+        );
+        if !is_cast {
+            return Ok(8);
+        }
+        self.advance();
+        let ty = self.advance();
+        let size = match &ty.kind {
+            TokenKind::Identifier(name) if name == "u8" => 1,
+            TokenKind::Identifier(name) if name == "u16" => 2,
+            TokenKind::Identifier(name) if name == "u32" => 4,
+            TokenKind::Identifier(name) if name == "u64" => 8,
+            _ => {
+                return Err(error_at(
+                    self.input,
+                    ty.span,
+                    "expected `u8`, `u16`, `u32` or `u64`",
+                ));
+            }
+        };
+        self.advance();
+        self.advance();
+        Ok(size)
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let token = self.peek().clone();
+        let kind = match token.kind {
+            TokenKind::Number(n) => ExprKind::Literal(n),
+            TokenKind::Register(name) => ExprKind::Register(name),
+            TokenKind::Identifier(name) => ExprKind::Symbol(name),
+            TokenKind::LParen => {
+                self.advance();
+                let inner = self.expression()?;
+                let close = self.expect(TokenKind::RParen)?;
+                return Ok(Expr {
+                    kind: inner.kind,
+                    span: token.span.to(close.span),
+                });
+            }
+            _ => return Err(self.unexpected("expected a number, register, or symbol")),
+        };
+        self.advance();
+        Ok(Expr {
+            kind,
+            span: token.span,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Ctx;
+
+    impl EvalContext for Ctx {
+        fn register_value(&self, name: &str) -> Result<u64> {
+            match name {
+                "rip" => Ok(0x401000),
+                _ => Err(anyhow!("unknown register `{name}`")),
+            }
+        }
+
+        fn symbol_address(&self, name: &str) -> Result<u64> {
+            match name {
+                "my_crate::foo" => Ok(0x2000),
+                _ => Err(anyhow!("no symbol named `{name}`")),
+            }
+        }
+
+        // 0x1000 onwards holds the bytes 0x00, 0x01, 0x02...
+        fn read_memory(&self, address: u64, size: usize) -> Result<u64> {
+            if !(0x1000..0x1100).contains(&address) {
+                return Err(anyhow!("cannot read memory at {address:#x}"));
+            }
+            let mut bytes = [0u8; 8];
+            for (i, byte) in bytes[..size].iter_mut().enumerate() {
+                *byte = (address - 0x1000) as u8 + i as u8;
+            }
+            Ok(u64::from_le_bytes(bytes))
+        }
+    }
+
+    fn eval(input: &str) -> Result<u64> {
+        Expression::parse(input)?.evaluate(&Ctx)
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(eval("0x401136").unwrap(), 0x401136);
+        assert_eq!(eval("0X1F").unwrap(), 0x1f);
+        assert_eq!(eval("0o17").unwrap(), 0o17);
+        assert_eq!(eval("017").unwrap(), 0o17);
+        assert_eq!(eval("0b101").unwrap(), 0b101);
+        assert_eq!(eval("4198710").unwrap(), 4198710);
+        assert_eq!(eval("0").unwrap(), 0);
+        assert_eq!(eval("1_000").unwrap(), 1000);
+    }
+
+    #[test]
+    fn arithmetic_registers_and_symbols() {
+        assert_eq!(eval("$rip+8").unwrap(), 0x401008);
+        assert_eq!(eval("$RIP - 0x10").unwrap(), 0x400ff0);
+        assert_eq!(eval("my_crate::foo+4").unwrap(), 0x2004);
+        assert_eq!(eval("(1 + 2) - (3 - 4)").unwrap(), 4);
+        assert_eq!(eval("-1").unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn conditions_and_memory() {
+        assert_eq!(eval("2 * 3 + 1").unwrap(), 7);
+        assert_eq!(eval("1 + 2 * 3").unwrap(), 7);
+        assert_eq!(eval("$rip == 0x401000").unwrap(), 1);
+        assert_eq!(eval("$rip != 0x401000").unwrap(), 0);
+        assert_eq!(eval("1 < 2 && 2 <= 2 && 3 > 2 && 3 >= 4").unwrap(), 0);
+        assert_eq!(eval("0 || 5").unwrap(), 1);
+        assert_eq!(eval("!0 && !!7").unwrap(), 1);
+        // unsigned, so -1 is the largest value
+        assert_eq!(eval("-1 > 0").unwrap(), 1);
+
+        assert_eq!(eval("*0x1000").unwrap(), 0x0706050403020100);
+        assert_eq!(eval("*(u8*)0x1001").unwrap(), 0x01);
+        assert_eq!(eval("*(u16*)(0x1000 + 2)").unwrap(), 0x0302);
+        assert_eq!(eval("*(u32*)0x1004 == 0x07060504").unwrap(), 1);
+        assert_eq!(eval("*(u8*)(0x1000 + *(u8*)0x1010) * 2").unwrap(), 0x20);
+
+        // the right hand side isn't evaluated (or read) unless needed
+        assert_eq!(eval("0 && *0").unwrap(), 0);
+        assert_eq!(eval("1 || *0").unwrap(), 1);
+        let err = eval("1 && *(u32*)0").unwrap_err().to_string();
+        assert!(err.contains("cannot read memory at 0x0"), "{err}");
+        assert!(err.ends_with("     ^^^^^^^^"), "{err}");
+
+        let err = eval("*(i32*)0x1000").unwrap_err().to_string();
+        assert!(
+            err.contains("expected `u8`, `u16`, `u32` or `u64`"),
+            "{err}"
+        );
+        let err = eval("$rip = 1").unwrap_err().to_string();
+        assert!(err.contains("did you mean `==`?"), "{err}");
+        let err = Expression::constant("*0x1000").unwrap_err().to_string();
+        assert!(err.contains("memory reads are not allowed here"), "{err}");
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        let err = eval("$rip+)").unwrap_err().to_string();
+        assert!(err.contains("column 6"), "{err}");
+        assert!(err.ends_with("  $rip+)\n       ^"), "{err}");
+
+        let err = eval("0x12g4").unwrap_err().to_string();
+        assert!(err.contains("invalid number literal"), "{err}");
+        assert!(err.ends_with("^^^^^^"), "{err}");
+
+        let err = eval("$rip + $nope").unwrap_err().to_string();
+        assert!(err.contains("unknown register"), "{err}");
+        assert!(err.ends_with("       ^^^^^"), "{err}");
+
+        let err = Expression::constant("$rsp+1").unwrap_err().to_string();
+        assert!(err.contains("registers are not allowed here"), "{err}");
+
+        let err = eval("1 2").unwrap_err().to_string();
+        assert!(
+            err.contains("expected end of input, found number `2`"),
+            "{err}"
+        );
+    }
+}
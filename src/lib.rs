@@ -1,6 +1,10 @@
 use ratatui::crossterm::event::KeyEvent;
 
 pub mod debugger;
+pub mod disassemble;
+pub mod dwarf;
+pub mod elf;
+pub mod expression;
 pub mod history;
 pub mod options;
 pub mod process;
@@ -59,6 +59,7 @@ fn init_logging() -> Result<WorkerGuard> {
 
 fn main() -> Result<()> {
     let options = Options::from_env()?;
+    let attach_pid = options.pid;
 
     let _guard = init_logging()?;
 
@@ -74,6 +75,13 @@ fn main() -> Result<()> {
     let (tui_shutdown_tx, tui_shutdown_rx) = unbounded();
     let mut tui = Tui::new(tui_tx, tui_shutdown_rx)?;
 
+    if let Some(pid) = attach_pid
+        && let Err(e) = debugger.attach(pid, &mut process)
+    {
+        tui.record_command_response(format!("error: {e}"));
+        error!("Error attaching to {pid}: {:?}", e);
+    }
+
     // the main event loop
     loop {
         tui.render(&debugger, &process)?;
@@ -97,6 +105,9 @@ fn main() -> Result<()> {
                                     Ok(DispatchResult::Normal) => {
                                         // nop? we'll redraw on the next loop iteration
                                     }
+                                    Ok(DispatchResult::Message(message)) => {
+                                        tui.record_command_response(message);
+                                    }
                                     Ok(DispatchResult::Exit) => {
                                         tui.record_command_response("exiting debugger");
                                         break;
@@ -107,6 +118,24 @@ fn main() -> Result<()> {
                                     }
                                 }
                             }
+                            Ok(EventResult::Breakpoint(command)) => {
+                                match process.breakpoint_command(command) {
+                                    Ok(message) => tui.record_command_response(message),
+                                    Err(e) => {
+                                        tui.record_command_response(format!("error: {e}"));
+                                        error!("Error: {:?}", e)
+                                    }
+                                }
+                            }
+                            Ok(EventResult::Thread(command)) => {
+                                match command.execute(&mut process) {
+                                    Ok(message) => tui.record_command_response(message),
+                                    Err(e) => {
+                                        tui.record_command_response(format!("error: {e}"));
+                                        error!("Error: {:?}", e)
+                                    }
+                                }
+                            }
                             Ok(EventResult::Quit) => {
                                 break
                             },
@@ -1,6 +1,7 @@
-use std::{env, path::PathBuf};
+use std::{env, fs, path::PathBuf};
 
 use anyhow::{Result, anyhow};
+use nix::unistd::Pid;
 
 /// Configuration to enable or disable linux ASLR on the inferior processes.
 #[derive(Copy, Clone, Debug)]
@@ -13,6 +14,9 @@ pub enum Aslr {
 #[derive(Clone, Debug)]
 pub struct Options {
     pub executable: PathBuf,
+    /// PID of an already-running process to attach to at startup (`--pid <pid>`).
+    /// When set, `executable` is resolved from `/proc/<pid>/exe`.
+    pub pid: Option<Pid>,
 }
 
 impl Options {
@@ -22,19 +26,41 @@ impl Options {
     }
 
     /// Parse options from an iterator of strings (for tests).
-    pub fn from_args<I, S>(mut args: I) -> Result<Self>
+    ///
+    /// Accepts either `<executable>` or `--pid <pid>` (alias `-p`).
+    pub fn from_args<I, S>(args: I) -> Result<Self>
     where
         I: Iterator<Item = S>,
         S: Into<String>,
     {
-        let executable = args
-            .next()
-            .map(|s| s.into())
-            .ok_or_else(|| anyhow!("expected executable path as first argument"))?;
+        let mut executable = None;
+        let mut pid = None;
 
-        let options = Options {
-            executable: PathBuf::from(executable),
+        let mut args = args.map(|s| s.into());
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--pid" | "-p" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("expected a PID after {arg}"))?;
+                    pid = Some(parse_pid(&value)?);
+                }
+                _ if executable.is_none() => executable = Some(PathBuf::from(arg)),
+                _ => return Err(anyhow!("unexpected argument: {:?}", arg)),
+            }
+        }
+
+        let executable = match (executable, pid) {
+            (Some(executable), _) => executable,
+            (None, Some(pid)) => executable_for_pid(pid)?,
+            (None, None) => {
+                return Err(anyhow!(
+                    "expected executable path as first argument (or --pid <pid>)"
+                ));
+            }
         };
+
+        let options = Options { executable, pid };
         options.validate()?;
         Ok(options)
     }
@@ -48,6 +74,29 @@ impl Options {
             return Err(anyhow!("executable does not exist: {:?}", self.executable));
         }
 
+        if let Some(pid) = self.pid
+            && !PathBuf::from(format!("/proc/{pid}")).exists()
+        {
+            return Err(anyhow!("no such process: {pid}"));
+        }
+
         Ok(())
     }
 }
+
+/// Parse a user-supplied PID.
+pub fn parse_pid(value: &str) -> Result<Pid> {
+    let raw = value
+        .parse::<i32>()
+        .map_err(|e| anyhow!("invalid PID {:?}: {e}", value))?;
+    if raw <= 0 {
+        return Err(anyhow!("invalid PID {:?}: must be positive", value));
+    }
+    Ok(Pid::from_raw(raw))
+}
+
+/// Resolve the executable of a running process via `/proc/<pid>/exe`.
+pub fn executable_for_pid(pid: Pid) -> Result<PathBuf> {
+    let exe = format!("/proc/{pid}/exe");
+    fs::read_link(&exe).map_err(|e| anyhow!("cannot read {exe}: {e}"))
+}
@@ -0,0 +1,176 @@
+//! The architecture-specific details of debugging: which trap instruction to
+//! use for a software breakpoint, where the PC ends up after hitting one, and
+//! which registers hold the PC, stack pointer, etc.
+//!
+//! All of the architectures are always compiled (they're just data), so they
+//! can be tested anywhere; [`HostArch`] is the one we're actually running on.
+
+use crate::process::stoppoint::VirtualAddress;
+use crate::process::syscalls::Syscall;
+use crate::process::syscalls::x86_64::X86_64_SYSCALLS;
+
+/// Architecture-specific constants and rules.
+///
+/// Registers are referred to by their name in the register table, e.g. `rsp`.
+pub trait Arch {
+    /// The longest trap instruction, i.e. how many bytes of the original
+    /// instruction [`Arch::breakpoint_instruction`] may need to look at.
+    const MAX_BREAKPOINT_LEN: usize;
+
+    /// How far past the trap instruction the PC is when a software breakpoint
+    /// is reported.
+    const BREAKPOINT_PC_OFFSET: u64;
+
+    const PC_REGISTER: &'static str;
+    const SP_REGISTER: &'static str;
+    const FP_REGISTER: &'static str;
+
+    /// The register holding the return address on function entry, if there
+    /// is one (on x86_64 it's on the stack).
+    const RETURN_ADDRESS_REGISTER: Option<&'static str>;
+
+    /// The architecture's syscalls, ordered by number. Only x86_64 has a
+    /// table so far; the others' syscalls are known by number alone.
+    const SYSCALLS: &'static [Syscall];
+
+    /// The trap instruction to write over the instruction starting with
+    /// `original`, which holds at least [`Arch::MAX_BREAKPOINT_LEN`] bytes.
+    fn breakpoint_instruction(original: &[u8]) -> &'static [u8];
+
+    /// Where the breakpoint is, given the PC when it was reported.
+    fn breakpoint_address(pc: VirtualAddress) -> VirtualAddress {
+        VirtualAddress::from(pc.addr() - Self::BREAKPOINT_PC_OFFSET)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct X86_64;
+
+/// `int3`
+const X86_64_INT3: &[u8] = &[0xcc];
+
+impl Arch for X86_64 {
+    const MAX_BREAKPOINT_LEN: usize = X86_64_INT3.len();
+    // the trap is reported after the `int3` executed
+    const BREAKPOINT_PC_OFFSET: u64 = 1;
+    const PC_REGISTER: &'static str = "rip";
+    const SP_REGISTER: &'static str = "rsp";
+    const FP_REGISTER: &'static str = "rbp";
+    const RETURN_ADDRESS_REGISTER: Option<&'static str> = None;
+    const SYSCALLS: &'static [Syscall] = X86_64_SYSCALLS;
+
+    fn breakpoint_instruction(_original: &[u8]) -> &'static [u8] {
+        X86_64_INT3
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Aarch64;
+
+/// `brk #0`
+const AARCH64_BRK: &[u8] = &0xd420_0000_u32.to_le_bytes();
+
+impl Arch for Aarch64 {
+    const MAX_BREAKPOINT_LEN: usize = AARCH64_BRK.len();
+    // the PC is left on the `brk`
+    const BREAKPOINT_PC_OFFSET: u64 = 0;
+    const PC_REGISTER: &'static str = "pc";
+    const SP_REGISTER: &'static str = "sp";
+    const FP_REGISTER: &'static str = "x29";
+    const RETURN_ADDRESS_REGISTER: Option<&'static str> = Some("x30");
+    const SYSCALLS: &'static [Syscall] = &[];
+
+    fn breakpoint_instruction(_original: &[u8]) -> &'static [u8] {
+        AARCH64_BRK
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Riscv64;
+
+/// `ebreak`
+const RISCV64_EBREAK: &[u8] = &0x0010_0073_u32.to_le_bytes();
+/// `c.ebreak`, from the compressed instruction extension.
+const RISCV64_C_EBREAK: &[u8] = &0x9002_u16.to_le_bytes();
+
+impl Arch for Riscv64 {
+    const MAX_BREAKPOINT_LEN: usize = RISCV64_EBREAK.len();
+    // the PC is left on the `ebreak`
+    const BREAKPOINT_PC_OFFSET: u64 = 0;
+    const PC_REGISTER: &'static str = "pc";
+    const SP_REGISTER: &'static str = "x2";
+    const FP_REGISTER: &'static str = "x8";
+    const RETURN_ADDRESS_REGISTER: Option<&'static str> = Some("x1");
+    const SYSCALLS: &'static [Syscall] = &[];
+
+    /// Compressed (2 byte) instructions get a `c.ebreak`, so we don't clobber
+    /// the next instruction, which may be a jump target.
+    fn breakpoint_instruction(original: &[u8]) -> &'static [u8] {
+        // all 32-bit instructions have 0b11 as their lowest bits
+        if original[0] & 0b11 == 0b11 {
+            RISCV64_EBREAK
+        } else {
+            RISCV64_C_EBREAK
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub type HostArch = X86_64;
+#[cfg(target_arch = "aarch64")]
+pub type HostArch = Aarch64;
+#[cfg(target_arch = "riscv64")]
+pub type HostArch = Riscv64;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::register_info::register_info_by_name;
+
+    #[test]
+    fn x86_64_breakpoints() {
+        assert_eq!(X86_64::breakpoint_instruction(&[0x55]), [0xcc]);
+        assert_eq!(
+            X86_64::breakpoint_address(0x1001.into()),
+            VirtualAddress::from(0x1000)
+        );
+    }
+
+    #[test]
+    fn aarch64_breakpoints() {
+        let brk = Aarch64::breakpoint_instruction(&[0xfd, 0x7b, 0xbf, 0xa9]);
+        assert_eq!(brk, [0x00, 0x00, 0x20, 0xd4]);
+        assert_eq!(brk.len(), Aarch64::MAX_BREAKPOINT_LEN);
+        assert_eq!(
+            Aarch64::breakpoint_address(0x1000.into()),
+            VirtualAddress::from(0x1000)
+        );
+    }
+
+    #[test]
+    fn riscv64_breakpoints() {
+        // addi sp, sp, -16
+        let ebreak = Riscv64::breakpoint_instruction(&0xff01_0113_u32.to_le_bytes());
+        assert_eq!(ebreak, [0x73, 0x00, 0x10, 0x00]);
+        // c.addi sp, -16, followed by something else
+        let c_ebreak = Riscv64::breakpoint_instruction(&[0x41, 0x11, 0x06, 0xe4]);
+        assert_eq!(c_ebreak, [0x02, 0x90]);
+        assert_eq!(
+            Riscv64::breakpoint_address(0x1000.into()),
+            VirtualAddress::from(0x1000)
+        );
+    }
+
+    #[test]
+    fn host_registers_exist() {
+        let mut names = vec![
+            HostArch::PC_REGISTER,
+            HostArch::SP_REGISTER,
+            HostArch::FP_REGISTER,
+        ];
+        names.extend(HostArch::RETURN_ADDRESS_REGISTER);
+        for name in names {
+            assert!(register_info_by_name(name).is_some(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,62 @@
+//! What to do when the inferior forks: which side to keep debugging, and what
+//! becomes of the other.
+
+use nix::unistd::Pid;
+use std::fmt;
+
+/// Which process to debug after a `fork` (or `vfork`).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum FollowForkMode {
+    /// Keep debugging the process that called `fork`.
+    #[default]
+    Parent,
+    /// Switch to the new child process.
+    Child,
+}
+
+impl fmt::Display for FollowForkMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FollowForkMode::Parent => write!(f, "parent"),
+            FollowForkMode::Child => write!(f, "child"),
+        }
+    }
+}
+
+/// The `follow-fork-mode` and `detach-on-fork` settings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ForkSettings {
+    pub follow_fork_mode: FollowForkMode,
+    /// Whether the process that isn't followed is detached from (with our
+    /// breakpoints removed), or held stopped until the debugger is done.
+    pub detach_on_fork: bool,
+}
+
+impl Default for ForkSettings {
+    fn default() -> Self {
+        Self {
+            follow_fork_mode: FollowForkMode::Parent,
+            detach_on_fork: true,
+        }
+    }
+}
+
+/// A process on the other side of a fork, which is still traced but isn't the
+/// one being debugged. It's kept stopped.
+#[derive(Clone, Debug)]
+pub(super) struct HeldProcess {
+    pub pid: Pid,
+    pub threads: Vec<Pid>,
+}
+
+/// A `vfork` whose child shares the parent's memory, and so our breakpoints,
+/// until it execs or exits.
+#[derive(Debug)]
+pub(super) enum PendingVfork {
+    /// We're following the parent, and took the breakpoints out to detach the
+    /// child. They go back in when the parent reports `PTRACE_EVENT_VFORK_DONE`.
+    TrapsRemoved,
+    /// We're following the child. The parent is held in its `vfork` until the
+    /// child no longer shares its memory, and then detached (or held).
+    Parent { parent: HeldProcess, detach: bool },
+}
@@ -1,12 +1,13 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use crossbeam_channel::{Receiver, Sender, TryRecvError};
 use mio::unix::SourceFd;
 use mio::{Events, Interest, Poll, Token};
+use nix::fcntl::{FcntlArg, OFlag, fcntl};
+use nix::libc;
 use nix::unistd::Pid;
 use std::io::Read;
 use std::os::fd::RawFd;
 use std::os::fd::{AsRawFd, OwnedFd};
-use std::os::unix::io::FromRawFd;
 use std::time::Duration;
 use tracing::{error, trace};
 
@@ -14,14 +15,44 @@ use nix::sys::ptrace;
 use std::collections::HashMap;
 use std::fs::File;
 
+use crate::process::arch::{Arch, HostArch};
+use crate::process::is_gone;
+use crate::process::registers::{read_debug_register, write_debug_register};
 use crate::process::stoppoint::breakpoint_site::BreakpointSite;
-use crate::process::stoppoint::{INTERRUPT_INSTRUCTION, StoppointId};
+use crate::process::stoppoint::hardware::{
+    HARDWARE_SLOTS, HardwareStoppoint, dr6_triggered_slots, dr7_with_slot,
+};
+use crate::process::stoppoint::{StoppointId, VirtualAddress};
+
+const DR6: usize = 6;
+const DR7: usize = 7;
 
 /// It's actually the PTY's merged stdout/stderr
 const STDOUT: Token = Token(0);
 
-/// Represents a process ("inferior") that the debugger has spawned
-/// under a pseudo-terminal (PTY).  
+/// How the debugger came to be tracing the inferior.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LaunchMode {
+    /// We forked and exec'd the inferior ourselves; it is ours to kill.
+    Spawned,
+    /// We attached to an already-running process; we must detach (and leave it
+    /// running) when we are done.
+    Attached,
+}
+
+/// The pseudo-terminal (PTY) handles for an inferior the debugger spawned.
+#[derive(Debug)]
+pub struct InferiorPty {
+    /// PTY master fd (used for resize/ioctl).
+    pub _master_fd: RawFd,
+    /// Writer to inferior's stdin (own fd).
+    pub _writer: File,
+    /// The raw file descriptor for the inferior's stdout/stderr.
+    pub reader_fd: OwnedFd,
+}
+
+/// Represents a process ("inferior") that the debugger is tracing, either
+/// one it has spawned under a pseudo-terminal (PTY), or one it has attached to.
 ///
 /// This structure owns all handles necessary for I/O, resizing, and
 /// signal control of the inferior process.  It is the debugger’s view
@@ -30,48 +61,117 @@ const STDOUT: Token = Token(0);
 pub struct Inferior {
     /// PID of the inferior process.
     pub pid: Pid,
-    /// PTY master fd (used for resize/ioctl).
-    pub _master_fd: RawFd,
-    /// Writer to inferior's stdin (own fd).
-    pub _writer: File,
-    /// The raw file descriptor for the inferior's stdout/stderr.
-    pub reader_fd: OwnedFd,
+    /// Thread IDs of the inferior's live threads, the first being the `pid`.
+    /// Each has its own debug registers, so hardware stoppoints are programmed
+    /// into all of them.
+    pub threads: Vec<Pid>,
+    /// Whether we spawned the inferior or attached to it.
+    pub launch_mode: LaunchMode,
+    /// PTY handles. Only available when we spawned the inferior; the output of
+    /// an attached process goes wherever it was already going.
+    pub pty: Option<InferiorPty>,
 
     /// The active, enabled breakpoints on this running inferior.
     /// The map's values are the original instructions that we replaced with
-    /// `int3`.
-    pub breakpoint_sites: HashMap<StoppointId, u8>,
+    /// trap instructions.
+    pub breakpoint_sites: HashMap<StoppointId, SavedInstruction>,
+
+    /// The stoppoint programmed into each of the debug address registers.
+    pub hardware_slots: [Option<StoppointId>; HARDWARE_SLOTS],
+}
+
+/// The original instruction bytes we replaced with a trap instruction (e.g.
+/// `int3`) when inserting a breakpoint.
+#[derive(Clone, Debug)]
+pub struct SavedInstruction {
+    pub address: VirtualAddress,
+    pub data: Vec<u8>,
+    /// The trap instruction now in its place.
+    pub trap: &'static [u8],
 }
 
 impl Inferior {
+    /// Create the inferior for a process we attached to via `PTRACE_ATTACH`.
+    pub fn attached(pid: Pid) -> Self {
+        Self {
+            pid,
+            threads: vec![pid],
+            launch_mode: LaunchMode::Attached,
+            pty: None,
+            breakpoint_sites: Default::default(),
+            hardware_slots: Default::default(),
+        }
+    }
+
     pub fn pid(&self) -> Pid {
         self.pid
     }
 
+    pub fn is_attached(&self) -> bool {
+        matches!(self.launch_mode, LaunchMode::Attached)
+    }
+
+    /// Start tracking a new thread, programming the hardware stoppoints into
+    /// its debug registers (which a new thread doesn't inherit).
+    pub fn add_thread(&mut self, tid: Pid) -> Result<()> {
+        if self.threads.contains(&tid) {
+            return Ok(());
+        }
+        if self.hardware_slots.iter().any(Option::is_some) {
+            for slot in 0..HARDWARE_SLOTS {
+                write_debug_register(tid, slot, read_debug_register(self.pid, slot)?)?;
+            }
+            write_debug_register(tid, DR7, read_debug_register(self.pid, DR7)?)?;
+        }
+        self.threads.push(tid);
+        Ok(())
+    }
+
+    /// Forget a thread that has exited.
+    pub fn remove_thread(&mut self, tid: Pid) {
+        self.threads.retain(|t| *t != tid);
+    }
+
     /// Enable the breakpoint in the inferior process.
     pub fn enable_breakpoint_site(&mut self, breakpoint_site: &BreakpointSite) -> Result<()> {
+        if breakpoint_site.is_hardware() {
+            let stoppoint = HardwareStoppoint::execute(breakpoint_site.address());
+            self.enable_hardware_stoppoint(breakpoint_site.id(), &stoppoint)?;
+            return Ok(());
+        }
         if self.breakpoint_sites.contains_key(&breakpoint_site.id()) {
             return Ok(());
         }
 
-        let instruction_line = ptrace::read(self.pid, breakpoint_site.address().addr() as _)?;
-        let saved_instruction = (instruction_line & 0xff) as u8;
+        let address = breakpoint_site.address();
+        let mut instruction_line = ptrace::read(self.pid, address.addr() as _)?.to_le_bytes();
+        let trap = HostArch::breakpoint_instruction(&instruction_line);
+        let saved_instruction = instruction_line[..trap.len()].to_vec();
 
-        let new_instruction_line = (instruction_line & !0xFF) | INTERRUPT_INSTRUCTION;
+        instruction_line[..trap.len()].copy_from_slice(trap);
         ptrace::write(
             self.pid,
-            breakpoint_site.address().addr() as _,
-            new_instruction_line,
+            address.addr() as _,
+            i64::from_le_bytes(instruction_line),
         )?;
 
-        self.breakpoint_sites
-            .insert(breakpoint_site.id(), saved_instruction);
+        self.breakpoint_sites.insert(
+            breakpoint_site.id(),
+            SavedInstruction {
+                address,
+                data: saved_instruction,
+                trap,
+            },
+        );
 
         Ok(())
     }
 
     /// Disable the breakpoint in the inferior process.
     pub fn disable_breakpoint_site(&mut self, breakpoint_site: &BreakpointSite) -> Result<()> {
+        if breakpoint_site.is_hardware() {
+            return self.disable_hardware_stoppoint(breakpoint_site.id());
+        }
         let saved_instruction = match self.breakpoint_sites.remove(&breakpoint_site.id()) {
             Some(v) => v,
             None => {
@@ -79,15 +179,151 @@ impl Inferior {
             }
         };
 
-        let instruction_line = ptrace::read(self.pid, breakpoint_site.address().addr() as _)?;
-        let restored_line = (instruction_line & !0xFF) | saved_instruction as i64;
-        ptrace::write(
-            self.pid,
-            breakpoint_site.address().addr() as _,
-            restored_line,
-        )?;
+        patch_instruction(self.pid, breakpoint_site.address(), &saved_instruction.data)
+    }
+
+    /// Program a free debug register for the stoppoint, returning its slot.
+    pub fn enable_hardware_stoppoint(
+        &mut self,
+        id: StoppointId,
+        stoppoint: &HardwareStoppoint,
+    ) -> Result<usize> {
+        if let Some(slot) = self.hardware_slot(id) {
+            return Ok(slot);
+        }
+        let slot = self
+            .hardware_slots
+            .iter()
+            .position(Option::is_none)
+            .ok_or_else(|| {
+                anyhow!(
+                    "All {HARDWARE_SLOTS} hardware debug registers are in use; delete or disable a hardware breakpoint or watchpoint first"
+                )
+            })?;
+
+        let mut armed = Vec::new();
+        for &tid in &self.threads {
+            match arm_slot(tid, slot, stoppoint) {
+                Ok(()) => armed.push(tid),
+                // it's exiting, and its debug registers go with it
+                Err(e) if is_gone(&e) => trace!(?tid, "Thread is gone"),
+                Err(e) => {
+                    // the slot stays free, so none of the threads may trap on it
+                    for tid in armed {
+                        if let Err(e) = disarm_slot(tid, slot) {
+                            error!(?tid, slot, "Cannot clear debug register: {e}");
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        trace!(?id, slot, ?stoppoint, "Enabled hardware stoppoint");
+
+        self.hardware_slots[slot] = Some(id);
+        Ok(slot)
+    }
+
+    /// Clear the debug register used by the stoppoint, if any.
+    pub fn disable_hardware_stoppoint(&mut self, id: StoppointId) -> Result<()> {
+        let Some(slot) = self.hardware_slot(id) else {
+            return Ok(());
+        };
+        for &tid in &self.threads {
+            match disarm_slot(tid, slot) {
+                Ok(()) => {}
+                Err(e) if is_gone(&e) => trace!(?tid, "Thread is gone"),
+                // the slot stays taken, so the threads still armed with it
+                // have their traps recognised
+                Err(e) => return Err(e),
+            }
+        }
+        self.hardware_slots[slot] = None;
         Ok(())
     }
+
+    fn hardware_slot(&self, id: StoppointId) -> Option<usize> {
+        self.hardware_slots.iter().position(|s| *s == Some(id))
+    }
+
+    /// The hardware stoppoint that caused the thread's last `SIGTRAP`, if any.
+    /// DR6 is reset afterwards, as its status bits are sticky.
+    pub fn take_hardware_stoppoint_hit(&mut self, tid: Pid) -> Result<Option<StoppointId>> {
+        if self.hardware_slots.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+        let dr6 = read_debug_register(tid, DR6)?;
+        write_debug_register(tid, DR6, 0)?;
+        Ok(dr6_triggered_slots(dr6).find_map(|slot| self.hardware_slots[slot]))
+    }
+
+    /// Switch to debugging the (stopped) child of a fork. It has a copy of our
+    /// traps in its memory, but not of the debug registers.
+    pub fn follow(&mut self, child: Pid) -> Result<()> {
+        self.add_thread(child)?;
+        self.pid = child;
+        self.threads = vec![child];
+        Ok(())
+    }
+
+    /// Take our breakpoints out of a process with a copy of the inferior's
+    /// memory (or that shares it, after a `vfork`), and clear the debug
+    /// registers of its threads, so it can run without us.
+    pub fn remove_stoppoints_from(&self, pid: Pid, threads: &[Pid]) -> Result<()> {
+        for saved in self.breakpoint_sites.values() {
+            patch_instruction(pid, saved.address, &saved.data)?;
+        }
+        if self.hardware_slots.iter().any(Option::is_some) {
+            for &tid in threads {
+                write_debug_register(tid, DR7, 0)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Put back the traps that `remove_stoppoints_from` took out of memory we
+    /// shared with a `vfork` child.
+    pub fn reinsert_traps(&self) -> Result<()> {
+        for saved in self.breakpoint_sites.values() {
+            patch_instruction(self.pid, saved.address, saved.trap)?;
+        }
+        Ok(())
+    }
+
+    /// Forget our traps, debug registers and threads, which an `exec` has
+    /// replaced along with the rest of the process image.
+    pub fn reset_for_exec(&mut self) {
+        self.breakpoint_sites.clear();
+        self.hardware_slots = Default::default();
+        self.threads = vec![self.pid];
+    }
+}
+
+/// Point a thread's debug register `slot` at the stoppoint, and enable it.
+fn arm_slot(tid: Pid, slot: usize, stoppoint: &HardwareStoppoint) -> Result<()> {
+    // the address first, so the slot is never enabled with a stale one
+    write_debug_register(tid, slot, stoppoint.address.addr())?;
+    let dr7 = read_debug_register(tid, DR7)?;
+    write_debug_register(tid, DR7, dr7_with_slot(dr7, slot, Some(stoppoint)))
+}
+
+/// Disable a thread's debug register `slot`, and clear its address.
+fn disarm_slot(tid: Pid, slot: usize) -> Result<()> {
+    let dr7 = read_debug_register(tid, DR7)?;
+    write_debug_register(tid, DR7, dr7_with_slot(dr7, slot, None))?;
+    write_debug_register(tid, slot, 0)
+}
+
+/// Overwrite the start of the instruction at `address` with `bytes`.
+fn patch_instruction(pid: Pid, address: VirtualAddress, bytes: &[u8]) -> Result<()> {
+    let mut instruction_line = ptrace::read(pid, address.addr() as _)?.to_le_bytes();
+    instruction_line[..bytes.len()].copy_from_slice(bytes);
+    ptrace::write(
+        pid,
+        address.addr() as _,
+        i64::from_le_bytes(instruction_line),
+    )?;
+    Ok(())
 }
 
 pub fn read_inferior_logging(
@@ -97,13 +333,21 @@ pub fn read_inferior_logging(
 ) {
     let mut poll = Poll::new().unwrap();
     let mut events = Events::with_capacity(128);
-    let mut source_fd = SourceFd(&fd.as_raw_fd());
+    let raw_fd = fd.as_raw_fd();
+    let mut source_fd = SourceFd(&raw_fd);
 
     poll.registry()
         .register(&mut source_fd, STDOUT, Interest::READABLE)
         .unwrap();
 
-    let mut file = unsafe { std::fs::File::from_raw_fd(fd.as_raw_fd()) };
+    // the poll is edge-triggered, so each event must be read until there's
+    // nothing left, without blocking on the last read.
+    if let Err(e) = fcntl(&fd, FcntlArg::F_SETFL(OFlag::O_NONBLOCK)) {
+        error!(?e, "Cannot make the inferior's PTY non-blocking");
+    }
+
+    // hand ownership of the fd to the `File`, so it's closed exactly once
+    let mut file = File::from(fd);
     let mut buffer = [0u8; 4096];
 
     loop {
@@ -117,7 +361,11 @@ pub fn read_inferior_logging(
                 );
                 continue;
             }
-            if event.is_readable() {
+            // a hang-up (the inferior exited) also needs a read, to observe the EIO
+            if !event.is_readable() && !event.is_read_closed() {
+                continue;
+            }
+            loop {
                 match file.read(&mut buffer) {
                     Ok(0) => {
                         trace!("EOF reached");
@@ -131,22 +379,30 @@ pub fn read_inferior_logging(
                             error!("Error when sending to loggin_tx channel: {:?}", e)
                         }
                     }
-                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                        // nop, ignore
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(e) if e.raw_os_error() == Some(libc::EIO) => {
+                        // linux reports EIO on the master once every slave fd has
+                        // been closed, i.e. the inferior is gone.
+                        trace!("inferior closed its end of the PTY");
+                        return;
                     }
                     Err(e) => {
                         error!(?e, "Error while reading inferior process out");
+                        break;
                     }
                 }
             }
+        }
 
-            match shutdown_channel.try_recv() {
-                Ok(_) | Err(TryRecvError::Disconnected) => {
-                    trace!("Stop signal received at inferior reader");
-                    break;
-                }
-                Err(TryRecvError::Empty) => {}
+        // check for shutdown on every poll timeout, not just when there's output,
+        // otherwise a quiet inferior keeps this thread alive forever.
+        match shutdown_channel.try_recv() {
+            Ok(_) | Err(TryRecvError::Disconnected) => {
+                trace!("Stop signal received at inferior reader");
+                break;
             }
+            Err(TryRecvError::Empty) => {}
         }
     }
 }
@@ -0,0 +1,155 @@
+//! Reading and writing arbitrary ranges of the inferior's memory.
+//!
+//! We prefer `process_vm_readv`/`process_vm_writev` as they move an entire
+//! range in a single syscall, rather than a word at a time like
+//! `PTRACE_PEEKDATA`/`PTRACE_POKEDATA`. However, `process_vm_writev` respects
+//! page protections (so it can't write to the read-only text pages), and both
+//! may be unavailable (e.g. seccomp), so we fall back to `/proc/<pid>/mem`,
+//! which the kernel lets a tracer read and write regardless of protections.
+
+use anyhow::{Result, anyhow};
+use nix::sys::uio::{RemoteIoVec, process_vm_readv, process_vm_writev};
+use std::fs::OpenOptions;
+use std::io::{IoSlice, IoSliceMut};
+use std::os::unix::fs::FileExt;
+use tracing::trace;
+
+use crate::process::inferior::Inferior;
+use crate::process::stoppoint::VirtualAddress;
+
+pub(crate) const PAGE_SIZE: u64 = 4096;
+
+/// Largest range read in one go, so that a typo in a length (or a register
+/// used as one) is an error rather than an attempt to allocate it all.
+pub(crate) const MAX_READ_LEN: usize = 1024 * 1024;
+
+impl Inferior {
+    /// Read `len` bytes of the inferior's memory, starting at `address`.
+    ///
+    /// Note: this returns the memory exactly as it is in the inferior, including
+    /// any trap instructions we've inserted. Most callers want
+    /// [`Inferior::read_memory_without_traps`].
+    pub fn read_memory(&self, address: VirtualAddress, len: usize) -> Result<Vec<u8>> {
+        if len > MAX_READ_LEN {
+            return Err(anyhow!(
+                "Cannot read {len} bytes at {address}, at most {MAX_READ_LEN} bytes can be read at once"
+            ));
+        }
+        let mut data = vec![0u8; len];
+        let read = self.read_vm(address, &mut data);
+        if read < len {
+            trace!(
+                ?address,
+                len, read, "process_vm_readv came up short, using /proc/pid/mem"
+            );
+            self.read_proc_mem(address.offset(read as i64), &mut data[read..])?;
+        }
+        Ok(data)
+    }
+
+    /// Read the inferior's memory, with our breakpoints' trap instructions (e.g.
+    /// `int3`) replaced by the original instruction bytes.
+    pub fn read_memory_without_traps(
+        &self,
+        address: VirtualAddress,
+        len: usize,
+    ) -> Result<Vec<u8>> {
+        let mut data = self.read_memory(address, len)?;
+        let end = address.offset(len as i64);
+        for saved in self.breakpoint_sites.values() {
+            for (i, byte) in saved.data.iter().enumerate() {
+                let trap_address = saved.address.offset(i as i64);
+                if address <= trap_address && trap_address < end {
+                    data[(trap_address.addr() - address.addr()) as usize] = *byte;
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// Write `data` into the inferior's memory, starting at `address`.
+    ///
+    /// If the range covers any of our inserted breakpoints, the saved original
+    /// instruction is updated instead, and the trap left in place. The saved
+    /// instructions are only updated once the write has succeeded.
+    pub fn write_memory(&mut self, address: VirtualAddress, data: &[u8]) -> Result<()> {
+        let mut patched = data.to_vec();
+        let mut saved_updates = Vec::new();
+        let end = address.offset(data.len() as i64);
+        for (id, saved) in &self.breakpoint_sites {
+            for i in 0..saved.data.len() {
+                let trap_address = saved.address.offset(i as i64);
+                if address <= trap_address && trap_address < end {
+                    let idx = (trap_address.addr() - address.addr()) as usize;
+                    saved_updates.push((*id, i, data[idx]));
+                    patched[idx] = saved.trap[i];
+                }
+            }
+        }
+
+        let remote = [RemoteIoVec {
+            base: address.addr() as usize,
+            len: patched.len(),
+        }];
+        match process_vm_writev(self.pid, &[IoSlice::new(&patched)], &remote) {
+            Ok(n) if n == patched.len() => {}
+            res => {
+                trace!(
+                    ?address,
+                    ?res,
+                    "process_vm_writev failed, using /proc/pid/mem"
+                );
+                self.write_proc_mem(address, &patched)?;
+            }
+        }
+
+        for (id, i, byte) in saved_updates {
+            if let Some(saved) = self.breakpoint_sites.get_mut(&id) {
+                saved.data[i] = byte;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read as much as possible with `process_vm_readv`, returning the number of
+    /// bytes read.
+    ///
+    /// The remote side is split at page boundaries, as the syscall stops at the
+    /// first chunk it cannot read; this way we get everything up to the first
+    /// unreadable page, rather than nothing at all.
+    fn read_vm(&self, address: VirtualAddress, data: &mut [u8]) -> usize {
+        let mut remote = Vec::new();
+        let mut cur = address.addr();
+        let end = cur + data.len() as u64;
+        while cur < end {
+            let next_page = (cur & !(PAGE_SIZE - 1)) + PAGE_SIZE;
+            let chunk_end = next_page.min(end);
+            remote.push(RemoteIoVec {
+                base: cur as usize,
+                len: (chunk_end - cur) as usize,
+            });
+            cur = chunk_end;
+        }
+
+        process_vm_readv(self.pid, &mut [IoSliceMut::new(data)], &remote).unwrap_or(0)
+    }
+
+    fn read_proc_mem(&self, address: VirtualAddress, data: &mut [u8]) -> Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let mem = OpenOptions::new()
+            .read(true)
+            .open(format!("/proc/{}/mem", self.pid))?;
+        mem.read_exact_at(data, address.addr())
+            .map_err(|e| anyhow!("Cannot read {} bytes at {address}: {e}", data.len()))
+    }
+
+    fn write_proc_mem(&self, address: VirtualAddress, data: &[u8]) -> Result<()> {
+        let mem = OpenOptions::new()
+            .write(true)
+            .open(format!("/proc/{}/mem", self.pid))?;
+        mem.write_all_at(data, address.addr())
+            .map_err(|e| anyhow!("Cannot write {} bytes at {address}: {e}", data.len()))
+    }
+}
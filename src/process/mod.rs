@@ -1,37 +1,62 @@
 use anyhow::{Result, anyhow};
 use crossbeam_channel::{Receiver, Sender};
+use nix::errno::Errno;
+use nix::fcntl::{FcntlArg, FdFlag, fcntl};
 use nix::libc;
 use nix::pty::{Winsize, openpty};
 use nix::sys::personality::{self, Persona};
 use nix::sys::ptrace;
 use nix::sys::signal::{Signal, kill};
-use nix::sys::wait::{WaitStatus, waitpid};
+use nix::sys::wait::{WaitPidFlag, WaitStatus, waitpid};
 use nix::unistd::{
     ForkResult, Pid, close, dup, dup2_stderr, dup2_stdin, dup2_stdout, execvp, fork, setsid,
 };
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ffi::{CStr, CString};
+use std::fmt;
 use std::fs::File;
 use std::ops::{Deref, DerefMut};
 use std::os::fd::AsRawFd;
 use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
-use std::thread::{self, JoinHandle};
-use tracing::trace;
+use std::thread::JoinHandle;
+use tracing::{info, trace, warn};
 
 use crate::debugger::BreakpointCommand;
-use crate::options::{Aslr, Options};
-use crate::process::inferior::{Inferior, read_inferior_logging};
-use crate::process::register_info::{Register, RegisterValue};
+use crate::disassemble::{Instruction, MAX_INSTRUCTION_LEN, disassemble};
+use crate::dwarf::line::SourceLocation;
+use crate::elf::Elf;
+use crate::elf::loaded::LoadedObjects;
+use crate::expression::{EvalContext, Expression};
+use crate::options::{Aslr, Options, executable_for_pid};
+use crate::process::arch::{Arch, HostArch};
+use crate::process::fork::{FollowForkMode, ForkSettings, HeldProcess, PendingVfork};
+use crate::process::inferior::{Inferior, InferiorPty, LaunchMode, read_inferior_logging};
+use crate::process::memory::PAGE_SIZE;
+use crate::process::register_info::{Register, RegisterValue, register_info_by_name};
 use crate::process::registers::{RegisterSnapshot, read_all_registers};
+use crate::process::signals::{SignalDisposition, SignalTable, is_group_stop};
+use crate::process::stoppoint::breakpoint::{Breakpoint, BreakpointId, BreakpointLocation};
 use crate::process::stoppoint::breakpoint_site::BreakpointSite;
+use crate::process::stoppoint::catchpoint::Catchpoint;
+use crate::process::stoppoint::hardware::{HARDWARE_SLOTS, HardwareStoppoint};
+use crate::process::stoppoint::stop_policy::StopPolicy;
+use crate::process::stoppoint::watchpoint::Watchpoint;
 use crate::process::stoppoint::{StoppointId, VirtualAddress};
+use crate::process::syscalls::{SyscallEntry, SyscallStop};
+use crate::process::thread::{Thread, ThreadState, list_threads, tgkill};
 
+pub mod arch;
+pub mod fork;
 mod inferior;
+pub(crate) mod memory;
 pub mod register_info;
 mod registers;
+pub mod signals;
 pub mod stoppoint;
+pub mod syscalls;
+pub mod thread;
 
 #[derive(Clone, Debug)]
 pub enum ProcessState {
@@ -46,6 +71,92 @@ pub enum ProcessState {
     Exited,
     /// The inferior process terminated, either normally or forcefully.
     Terminated,
+    /// The debugger detached from the inferior, which continues running on its own.
+    Detached,
+}
+
+/// The reason a `SIGTRAP` was raised, decoded from the `si_code` of the
+/// signal's `siginfo_t`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrapType {
+    /// Hit a software breakpoint (e.g. `int3`).
+    SoftwareBreak,
+    /// Completed a `PTRACE_SINGLESTEP`.
+    SingleStep,
+    /// Triggered a hardware breakpoint (debug registers).
+    HardwareBreak,
+    /// Triggered a watchpoint, which is reported as a hardware breakpoint and
+    /// told apart by the debug register that caught it.
+    Watchpoint,
+    /// Making a syscall, while tracing them with `PTRACE_SYSCALL`.
+    SyscallEntry,
+    /// Returning from a syscall.
+    SyscallExit,
+    /// Something else, like the `SIGTRAP` delivered after an `exec`.
+    Unknown,
+}
+
+impl TrapType {
+    fn from_siginfo(info: &libc::siginfo_t) -> Self {
+        match info.si_code {
+            // x86 reports `int3` as SI_KERNEL, other arches as TRAP_BRKPT
+            libc::SI_KERNEL | TRAP_BRKPT => TrapType::SoftwareBreak,
+            TRAP_TRACE => TrapType::SingleStep,
+            TRAP_HWBKPT => TrapType::HardwareBreak,
+            _ => TrapType::Unknown,
+        }
+    }
+}
+
+// si_code values for SIGTRAP, from `asm-generic/siginfo.h` (not exported by libc).
+const TRAP_BRKPT: i32 = 1;
+const TRAP_TRACE: i32 = 2;
+const TRAP_HWBKPT: i32 = 4;
+
+/// Largest range `disassemble_range` accepts, to catch typos like a missing
+/// `+` on the length.
+const MAX_DISASSEMBLY_RANGE: u64 = 64 * 1024;
+
+/// Why the inferior last stopped running.
+#[derive(Clone, Debug)]
+pub enum StopReason {
+    /// The inferior exited normally, with the given exit code.
+    Exited(i32),
+    /// The inferior was terminated by a signal.
+    Terminated(Signal),
+    /// The inferior is stopped, awaiting the debugger.
+    Stopped {
+        /// The thread that stopped.
+        tid: Pid,
+        signal: Signal,
+        /// Only populated for `SIGTRAP`.
+        trap: Option<TrapType>,
+        pc: VirtualAddress,
+    },
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::Exited(code) => write!(f, "exited with status {code}"),
+            StopReason::Terminated(signal) => write!(f, "terminated by {signal}"),
+            StopReason::Stopped {
+                signal, trap, pc, ..
+            } => {
+                write!(f, "stopped at {pc} ({signal}")?;
+                match trap {
+                    Some(TrapType::SoftwareBreak) => write!(f, ": breakpoint")?,
+                    Some(TrapType::SingleStep) => write!(f, ": single step")?,
+                    Some(TrapType::HardwareBreak) => write!(f, ": hardware breakpoint")?,
+                    Some(TrapType::Watchpoint) => write!(f, ": watchpoint")?,
+                    Some(TrapType::SyscallEntry) => write!(f, ": syscall entry")?,
+                    Some(TrapType::SyscallExit) => write!(f, ": syscall exit")?,
+                    Some(TrapType::Unknown) | None => {}
+                }
+                write!(f, ")")
+            }
+        }
+    }
 }
 
 /// Collection of all known breakpoints.
@@ -79,6 +190,15 @@ impl StoppointSites {
             .iter()
             .any(|(_, b)| b.address() == *address && b.is_enabled())
     }
+
+    /// Whether there's an enabled trap instruction at the address (hardware
+    /// breakpoints trap before the instruction executes, so don't need the PC
+    /// rewinding).
+    fn contains_enabled_software_vaddr(&self, address: &VirtualAddress) -> bool {
+        self.0
+            .iter()
+            .any(|(_, b)| b.address() == *address && b.is_enabled() && !b.is_hardware())
+    }
 }
 
 /// The primary struct containing information about the process being debugged.
@@ -89,10 +209,13 @@ pub struct Process {
     /// The inferior being debugged. Will be `None` if the process has not executed
     /// or has exited.
     inferior_process: Option<Inferior>,
-    /// Snapshot of the inferior's register values. Maintained independently
-    /// of the `inferior_process` such that the regisrters can be inspected
-    /// after the inferior exits.
-    registers: Option<RegisterSnapshot>,
+    /// The inferior's threads, with a snapshot of each one's register values.
+    /// Maintained independently of the `inferior_process` such that the
+    /// regisrters can be inspected after the inferior exits.
+    threads: BTreeMap<Pid, Thread>,
+    /// The thread whose registers are shown, and which is stepped. It's set to
+    /// the thread that stopped, and changed with `thread <tid>`.
+    current_thread: Option<Pid>,
     /// Captured stdout/stderr from the inferior process.
     ///
     /// The reason the inferior output is stored here, rather than in
@@ -107,10 +230,52 @@ pub struct Process {
     shutdown_rx: Receiver<()>,
     logging_thread: Option<JoinHandle<()>>,
 
+    /// Why the inferior last stopped, exited, or was terminated.
+    last_stop: Option<StopReason>,
+
     /// Collection of all enabled and disabled breakpoints that this debugger
     /// process has tracked. Newly spawned inferior processes will have any enabled
     /// breakpoints set in them.
     breakpoint_sites: StoppointSites,
+
+    /// The user-visible breakpoints, each owning one or more of the
+    /// `breakpoint_sites`.
+    breakpoints: BTreeMap<BreakpointId, Breakpoint>,
+
+    /// Watchpoints, numbered along with the `breakpoints`.
+    watchpoints: BTreeMap<BreakpointId, Watchpoint>,
+    /// The watchpoint that triggered the last stop, if any.
+    watchpoint_hit: Option<BreakpointId>,
+    /// The breakpoint the last stop was at, if any.
+    breakpoint_hit: Option<BreakpointId>,
+    /// Syscall catchpoints, numbered along with the `breakpoints`.
+    catchpoints: BTreeMap<BreakpointId, Catchpoint>,
+    /// The catchpoint that caught the last stop's syscall, if any.
+    catchpoint_hit: Option<BreakpointId>,
+    /// The syscall the last stop was on the way into or out of.
+    last_syscall: Option<SyscallStop>,
+
+    /// The executable and shared objects loaded into the inferior. `None` if
+    /// the executable couldn't be parsed.
+    objects: Option<LoadedObjects>,
+
+    /// Which side of a fork to follow, and what to do with the other.
+    fork_settings: ForkSettings,
+    /// Processes on the other side of a fork, held stopped (with
+    /// `detach-on-fork off`) until we're done debugging.
+    held_processes: Vec<HeldProcess>,
+    /// A `vfork` whose child hasn't exec'd or exited yet.
+    pending_vfork: Option<PendingVfork>,
+    /// New threads and processes whose first stop was reported before the
+    /// clone or fork event announcing them.
+    early_stops: HashSet<Pid>,
+    /// Which signals stop the inferior, and which are passed on to it.
+    signals: SignalTable,
+
+    /// Bumped whenever anything the UI shows about the inferior may have
+    /// changed: it stopped or ran, a register was written, another thread was
+    /// selected, or a stoppoint was changed.
+    generation: u64,
 }
 
 impl Process {
@@ -119,22 +284,45 @@ impl Process {
         inferior_tx: Sender<String>,
         shutdown_rx: Receiver<()>,
     ) -> Self {
-        // Note: this is slightly borked for PID-based launches :shrug:
         Process {
             cli_options,
             state: ProcessState::Unknown,
             inferior_process: None,
             inferior_output: Vec::new(),
-            registers: None,
+            threads: Default::default(),
+            current_thread: None,
             inferior_tx,
             shutdown_rx,
             logging_thread: None,
+            last_stop: None,
             breakpoint_sites: Default::default(),
+            breakpoints: Default::default(),
+            watchpoints: Default::default(),
+            watchpoint_hit: None,
+            breakpoint_hit: None,
+            catchpoints: Default::default(),
+            catchpoint_hit: None,
+            last_syscall: None,
+            objects: None,
+            fork_settings: Default::default(),
+            held_processes: Vec::new(),
+            pending_vfork: None,
+            early_stops: HashSet::new(),
+            signals: Default::default(),
+            generation: 0,
         }
     }
 
+    /// Changes whenever anything the UI shows about the inferior may have, so
+    /// views built from the process only need rebuilding when it does.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
     /// Attach to the process by spawning a new process for the configured executable.
     pub fn attach(&mut self, args: Vec<String>) -> Result<()> {
+        self.ensure_not_debugging()?;
+        self.release_held_processes()?;
         trace!(
             "Spawning inferior process {:?}",
             self.cli_options.executable
@@ -144,30 +332,184 @@ impl Process {
             launch_executable(self.cli_options.executable.as_path(), args, Aslr::Enabled)?
                 .expect("Should receive inferior process info");
 
-        let fd_clone = inferior.reader_fd.try_clone()?;
+        let pty = inferior.pty.as_ref().expect("spawned inferior has a PTY");
+        let fd_clone = pty.reader_fd.try_clone()?;
         let inferior_tx_clone = self.inferior_tx.clone();
         let shutdown_rx_clone = self.shutdown_rx.clone();
 
         // start inferior reader
-        let logging_thread = thread::spawn(move || {
+        let logging_thread = std::thread::spawn(move || {
             read_inferior_logging(fd_clone, inferior_tx_clone, shutdown_rx_clone);
         });
         self.logging_thread = Some(logging_thread);
-        self.inferior_process = Some(inferior);
+        self.set_inferior(inferior);
+        self.load_executable(&self.cli_options.executable.clone());
 
         // TODO: not sure about setting the state here to Running ...
         self.state = ProcessState::Running;
         self.wait_on_signal()?;
+        self.set_trace_options()?;
 
         // now that the inferior is ready, set any enabled breakpoints.
         // TODO: check WaitStatus is good before trying to set the breakpoints.
-        let inferior = self.inferior_process.as_mut().expect("just created");
+        self.enable_breakpoint_sites()
+    }
+
+    /// Attach to an already-running process with `PTRACE_ATTACH`.
+    ///
+    /// The executable is resolved from `/proc/<pid>/exe`, and any enabled
+    /// breakpoints are inserted once the process has stopped. Unlike a spawned
+    /// inferior, the process is detached (rather than killed) when we're done.
+    pub fn attach_to_pid(&mut self, pid: Pid) -> Result<()> {
+        self.ensure_not_debugging()?;
+        self.release_held_processes()?;
+        let executable = executable_for_pid(pid)?;
+        trace!(?pid, ?executable, "Attaching to running process");
+
+        // PTRACE_ATTACH sends a SIGSTOP to the process, which we wait for below.
+        ptrace::attach(pid)?;
+
+        self.load_executable(&executable);
+        self.cli_options.executable = executable;
+        self.cli_options.pid = Some(pid);
+        self.inferior_output.clear();
+        self.set_inferior(Inferior::attached(pid));
+
+        self.state = ProcessState::Running;
+        self.wait_on_signal()?;
+        self.attach_threads()?;
+        self.set_trace_options()?;
+
+        self.enable_breakpoint_sites()
+    }
+
+    /// Start debugging a new inferior, whose only known thread is its (not yet
+    /// stopped) main thread.
+    fn set_inferior(&mut self, inferior: Inferior) {
+        let pid = inferior.pid();
+        let main_thread = Thread::new(pid, pid, ThreadState::Running);
+        self.threads = BTreeMap::from([(pid, main_thread)]);
+        self.current_thread = Some(pid);
+        self.inferior_process = Some(inferior);
+    }
+
+    /// Attach to the threads of an already-running process besides its main
+    /// thread (`PTRACE_ATTACH` only attaches to a single thread). The process
+    /// may be creating threads as we go, so repeat until no new ones turn up.
+    fn attach_threads(&mut self) -> Result<()> {
+        if !matches!(self.state, ProcessState::Stopped) {
+            return Ok(());
+        }
+        let pid = self.expect_pid();
+        loop {
+            let new_threads: Vec<Pid> = list_threads(pid)?
+                .into_iter()
+                .filter(|tid| !self.threads.contains_key(tid))
+                .collect();
+            if new_threads.is_empty() {
+                return Ok(());
+            }
+            for tid in new_threads {
+                match ptrace::attach(tid) {
+                    Ok(()) => {}
+                    // it exited in the meantime
+                    Err(Errno::ESRCH) => continue,
+                    Err(e) => return Err(e.into()),
+                }
+                // attaching stops the thread with a SIGSTOP
+                if let WaitStatus::Stopped(..) = waitpid(tid, Some(WaitPidFlag::__WALL))? {
+                    trace!(?tid, "Attached to thread");
+                    self.add_stopped_thread(tid)?;
+                }
+            }
+        }
+    }
+
+    /// Have the kernel stop a thread when it creates another thread or
+    /// process, or execs a new program, and trace new threads and processes
+    /// from the start. They inherit the options. Syscall stops are told
+    /// apart from `SIGTRAP`s, for catchpoints.
+    fn set_trace_options(&self) -> Result<()> {
+        if !matches!(self.state, ProcessState::Stopped) {
+            return Ok(());
+        }
+        let options = ptrace::Options::PTRACE_O_TRACECLONE
+            | ptrace::Options::PTRACE_O_TRACEFORK
+            | ptrace::Options::PTRACE_O_TRACEVFORK
+            | ptrace::Options::PTRACE_O_TRACEVFORKDONE
+            | ptrace::Options::PTRACE_O_TRACEEXEC
+            | ptrace::Options::PTRACE_O_TRACESYSGOOD;
+        for tid in self.threads.keys() {
+            ptrace::setoptions(*tid, options)?;
+        }
+        Ok(())
+    }
+
+    /// Parse the executable's ELF file. Load biases and shared objects are
+    /// filled in once the inferior stops.
+    fn load_executable(&mut self, executable: &Path) {
+        self.unresolve_symbolic_breakpoints();
+        self.objects = match Elf::open(executable) {
+            Ok(elf) => Some(LoadedObjects::new(elf)),
+            Err(e) => {
+                warn!("Cannot parse executable, symbols will not be available: {e}");
+                None
+            }
+        };
+    }
+
+    /// The executable and shared objects loaded into the inferior.
+    pub fn objects(&self) -> Option<&LoadedObjects> {
+        self.objects.as_ref()
+    }
+
+    fn ensure_not_debugging(&self) -> Result<()> {
+        if matches!(self.state, ProcessState::Stopped | ProcessState::Running) {
+            return Err(anyhow!(
+                "Already debugging process {:?}; quit or detach first",
+                self.expect_pid()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Insert all the enabled breakpoints and watchpoints into the (newly
+    /// stopped) inferior.
+    fn enable_breakpoint_sites(&mut self) -> Result<()> {
+        let Some(inferior) = self.inferior_process.as_mut() else {
+            return Ok(());
+        };
         for b in self.breakpoint_sites.values() {
             if b.is_enabled() {
                 inferior.enable_breakpoint_site(b)?;
             }
         }
+        for w in self.watchpoints.values() {
+            if w.is_enabled() {
+                inferior.enable_hardware_stoppoint(w.stoppoint_id(), w.stoppoint())?;
+            }
+        }
+        // start watching from the values in this inferior
+        let mut watchpoints = std::mem::take(&mut self.watchpoints);
+        for w in watchpoints.values_mut() {
+            w.update_value(self.read_watched_value(w.stoppoint()));
+        }
+        self.watchpoints = watchpoints;
+        Ok(())
+    }
 
+    /// Restore the original instructions for every breakpoint we've inserted
+    /// into the inferior.
+    fn disable_breakpoint_sites(&mut self) -> Result<()> {
+        let Some(inferior) = self.inferior_process.as_mut() else {
+            return Ok(());
+        };
+        for b in self.breakpoint_sites.values() {
+            inferior.disable_breakpoint_site(b)?;
+        }
+        for w in self.watchpoints.values() {
+            inferior.disable_hardware_stoppoint(w.stoppoint_id())?;
+        }
         Ok(())
     }
 
@@ -178,60 +520,267 @@ impl Process {
         None
     }
 
+    pub fn state(&self) -> &ProcessState {
+        &self.state
+    }
+
     pub fn expect_pid(&self) -> Pid {
         self.pid().expect("Should have PID at this point")
     }
 
     /// Continue (resume) debugging the inferior process.
     ///
-    /// Essentially does `PTRACE_CONT`.
+    /// Essentially does `PTRACE_CONT` on every thread (or `PTRACE_SYSCALL`,
+    /// while there are catchpoints), delivering the signal each stopped for if
+    /// it's passed to the program. If the thread that stopped is on an enabled
+    /// breakpoint, we first transparently step it over the breakpoint so the
+    /// original instruction (and not the trap) is executed.
     pub fn resume(&mut self) -> Result<()> {
         if !matches!(self.state, ProcessState::Stopped | ProcessState::Running) {
             return Err(anyhow!("Inferior process not being debugged"));
         }
 
-        let pc = self.get_pc()?;
+        if matches!(self.state, ProcessState::Stopped) {
+            // only the thread that reported the stop has executed its trap;
+            // any other thread at a breakpoint should trap when it's resumed
+            if let Some(StopReason::Stopped { tid, trap, .. }) = self.last_stop
+                // a syscall stop is before the instruction at the PC
+                && !matches!(trap, Some(TrapType::SyscallEntry | TrapType::SyscallExit))
+                && let Some(pc) = self.thread_pc(tid)
+                && self.breakpoint_sites.contiains_enabled_vaddr(&pc)
+            {
+                self.step_thread(tid)?;
+                // the single step may have ended the inferior (or stopped on
+                // some other signal, or a watchpoint), in which case there's
+                // nothing to continue.
+                if !matches!(self.state, ProcessState::Stopped)
+                    || (self.watchpoint_hit.is_some() && self.stop_requested())
+                {
+                    return Ok(());
+                }
+            }
+        }
 
-        let pid = self.expect_pid();
-        ptrace::cont(pid, None)?;
+        let stopped: Vec<(Pid, Option<Signal>)> = self
+            .threads
+            .values()
+            .filter(|thread| thread.state() == ThreadState::Stopped)
+            .map(|thread| (thread.tid(), self.signals.signal_to_pass(thread)))
+            .collect();
+        for (tid, signal) in stopped {
+            self.resume_thread(tid, None, signal)?;
+        }
         self.state = ProcessState::Running;
+        self.generation += 1;
 
         Ok(())
     }
 
+    /// Execute a single instruction in the current thread of the inferior
+    /// (`PTRACE_SINGLESTEP`), and wait for it to stop again.
+    ///
+    /// If there's an enabled breakpoint at the current PC, the original
+    /// instruction is temporarily restored for the step, and the trap
+    /// re-inserted afterwards.
+    pub fn step_instruction(&mut self) -> Result<WaitStatus> {
+        if !matches!(self.state, ProcessState::Stopped) {
+            return Err(anyhow!("Inferior process is not stopped"));
+        }
+        let tid = self.current_thread.expect("stopped inferior has a thread");
+        self.step_thread(tid)
+    }
+
+    /// Single step one thread, leaving the rest stopped. The signal it stopped
+    /// for is delivered if it's passed, so the step may end in a handler.
+    fn step_thread(&mut self, tid: Pid) -> Result<WaitStatus> {
+        let pc = self
+            .thread_pc(tid)
+            .ok_or_else(|| anyhow!("No registers yet"))?;
+        let site = self
+            .breakpoint_sites
+            .values()
+            .find(|b| b.at_address(&pc) && b.is_enabled())
+            .cloned();
+
+        let inferior = self.inferior_process.as_mut().expect("stopped inferior");
+        if let Some(ref site) = site {
+            inferior.disable_breakpoint_site(site)?;
+        }
+
+        let signal = self
+            .threads
+            .get(&tid)
+            .and_then(|thread| self.signals.signal_to_pass(thread));
+        ptrace::step(tid, signal)?;
+        if let Some(thread) = self.threads.get_mut(&tid) {
+            thread.set_running();
+        }
+        self.state = ProcessState::Running;
+        let wait_status = self.wait_for_stop(Some(tid))?;
+
+        // (after an exec, the site may belong to the old program)
+        if let Some(ref site) = site
+            && matches!(self.state, ProcessState::Stopped)
+            && self.breakpoint_sites.contains_key(&site.id())
+            && let Some(inferior) = self.inferior_process.as_mut()
+        {
+            inferior.enable_breakpoint_site(site)?;
+        }
+
+        Ok(wait_status)
+    }
+
+    /// The program counter of the inferior, if it's stopped.
+    pub fn pc(&self) -> Option<VirtualAddress> {
+        match self.state {
+            ProcessState::Stopped => self.get_pc().ok(),
+            _ => None,
+        }
+    }
+
     fn get_pc(&self) -> Result<VirtualAddress> {
-        let Some(registers) = self.registers.as_ref() else {
+        let Some(registers) = self.registers() else {
             return Err(anyhow!("No registers yet"));
         };
         registers.get_pc()
     }
 
+    /// The registers of the current thread.
+    fn registers(&self) -> Option<&RegisterSnapshot> {
+        self.threads.get(&self.current_thread?)?.registers()
+    }
+
+    /// The program counter of a thread, as of its last stop.
+    pub fn thread_pc(&self, tid: Pid) -> Option<VirtualAddress> {
+        self.threads.get(&tid)?.registers()?.get_pc().ok()
+    }
+
+    /// The inferior's threads, ordered by thread ID.
+    pub fn threads(&self) -> impl Iterator<Item = &Thread> {
+        self.threads.values()
+    }
+
+    /// The thread whose registers are shown, and which is stepped.
+    pub fn current_thread(&self) -> Option<&Thread> {
+        self.threads.get(&self.current_thread?)
+    }
+
+    /// Make another thread the current one.
+    pub fn select_thread(&mut self, tid: Pid) -> Result<()> {
+        if !matches!(self.state, ProcessState::Stopped) {
+            return Err(anyhow!("Inferior process is not stopped"));
+        }
+        if !self.threads.contains_key(&tid) {
+            return Err(anyhow!("No thread {tid}"));
+        }
+        self.current_thread = Some(tid);
+        self.generation += 1;
+        Ok(())
+    }
+
     /// Wait for the inferior to change it's status (i.e. hit a breakpoint
     /// or exit/terminate).
+    ///
+    /// Breakpoints and watchpoints whose condition is false, or with an
+    /// ignore count, are silently continued through.
     pub fn wait_on_signal(&mut self) -> Result<WaitStatus> {
-        let wait_status = waitpid(self.expect_pid(), None)?;
+        loop {
+            let wait_status = self.wait_for_stop(None)?;
+            if self.stop_requested() {
+                return Ok(wait_status);
+            }
+            self.resume()?;
+            // stepping over the breakpoint may itself have stopped
+            if !matches!(self.state, ProcessState::Running) {
+                return Ok(wait_status);
+            }
+        }
+    }
+
+    /// Wait for the next stop (or exit) of the inferior, and record why it
+    /// happened. When a thread stops, the rest are stopped too (all-stop
+    /// mode), so nothing changes under the user while they look around.
+    ///
+    /// `stepping` is the thread being single stepped, if any; it's the only
+    /// one running.
+    fn wait_for_stop(&mut self, stepping: Option<Pid>) -> Result<WaitStatus> {
+        let wait_status = self.wait_for_thread_event(stepping)?;
         trace!("signal received: {:?}", &wait_status);
+        self.generation += 1;
+        self.watchpoint_hit = None;
+        self.breakpoint_hit = None;
+        self.catchpoint_hit = None;
+        self.last_syscall = None;
 
         // TODO: if exited/terminated, send shutdown signal to inferior reader
         match wait_status {
-            WaitStatus::Exited(_, _) => {
+            WaitStatus::Exited(tid, _) | WaitStatus::Signaled(tid, _, _)
+                if tid != self.expect_pid() =>
+            {
+                // the thread being stepped exited, and the rest are stopped
+                self.state = ProcessState::Stopped;
+            }
+            WaitStatus::Exited(_, code) => {
+                self.release_vfork_parent()?;
                 self.state = ProcessState::Exited;
+                self.last_stop = Some(StopReason::Exited(code));
+                self.threads.values_mut().for_each(Thread::set_exited);
             }
-            WaitStatus::Signaled(_, _, _) => {
+            WaitStatus::Signaled(_, signal, _) => {
+                self.release_vfork_parent()?;
                 self.state = ProcessState::Terminated;
+                self.last_stop = Some(StopReason::Terminated(signal));
+                self.threads.values_mut().for_each(Thread::set_exited);
             }
-            WaitStatus::Stopped(_, signal) => {
-                let mut registers = read_all_registers(self.expect_pid())?;
-                if matches!(signal, Signal::SIGTRAP) {
-                    // set the PC back one, to where the breakpoint currently is
-                    let cur_pc = registers.get_pc()?;
-                    let instr_begin = VirtualAddress::from(cur_pc.address - 1_u64);
-                    if self.breakpoint_sites.contiains_enabled_vaddr(&instr_begin) {
-                        registers.set_pc(instr_begin)?;
+            WaitStatus::Stopped(tid, signal) => {
+                let (registers, trap) = match self.read_stopped_registers(tid, signal) {
+                    Ok(stopped) => stopped,
+                    // it's exiting, which waitpid will tell us
+                    Err(e) if is_gone(&e) => return self.wait_for_stop(stepping),
+                    Err(e) => return Err(e),
+                };
+                // a watchpoint may trigger during a single step too
+                if matches!(trap, Some(TrapType::HardwareBreak | TrapType::SingleStep)) {
+                    self.check_watchpoint_hit(tid)?;
+                }
+                let trap = match trap {
+                    Some(TrapType::HardwareBreak) if self.watchpoint_hit.is_some() => {
+                        Some(TrapType::Watchpoint)
                     }
+                    trap => trap,
+                };
+                let pc = registers.get_pc()?;
+                if matches!(
+                    trap,
+                    Some(TrapType::SoftwareBreak | TrapType::HardwareBreak)
+                ) && let Some(site) = self
+                    .breakpoint_sites
+                    .values_mut()
+                    .find(|site| site.at_address(&pc) && site.is_enabled())
+                {
+                    self.breakpoint_hit = Some(site.breakpoint());
                 }
-                self.registers = Some(registers);
-                self.state = ProcessState::Stopped
+                self.record_stop(tid, signal, trap, registers)?;
+            }
+            WaitStatus::PtraceSyscall(tid) => {
+                let stopped = self
+                    .syscall_stop_info(tid)
+                    .and_then(|info| Ok((info, read_all_registers(tid)?)));
+                let (info, registers) = match stopped {
+                    Ok(stopped) => stopped,
+                    Err(e) if is_gone(&e) => return self.wait_for_stop(stepping),
+                    Err(e) => return Err(e),
+                };
+                let (entry, returned) = info
+                    .ok_or_else(|| anyhow!("Cannot tell which syscall thread {tid} stopped in"))?;
+                let syscall = self.decode_syscall_stop(entry, returned);
+                let trap = match syscall.is_entry() {
+                    true => TrapType::SyscallEntry,
+                    false => TrapType::SyscallExit,
+                };
+                self.catchpoint_hit = self.catchpoint_for(syscall.number);
+                self.last_syscall = Some(syscall);
+                self.record_stop(tid, Signal::SIGTRAP, Some(trap), registers)?;
             }
             _ => {}
         };
@@ -239,22 +788,897 @@ impl Process {
         Ok(wait_status)
     }
 
+    /// Record why a thread stopped, and make it the current thread. The other
+    /// threads are stopped too, and any shared objects loaded since the last
+    /// stop are picked up.
+    fn record_stop(
+        &mut self,
+        tid: Pid,
+        signal: Signal,
+        trap: Option<TrapType>,
+        registers: RegisterSnapshot,
+    ) -> Result<()> {
+        let pc = registers.get_pc()?;
+        let reason = StopReason::Stopped {
+            tid,
+            signal,
+            trap,
+            pc,
+        };
+        if let Some(thread) = self.threads.get_mut(&tid) {
+            thread.set_stopped(registers, reason.clone());
+        }
+        self.last_stop = Some(reason);
+        self.current_thread = Some(tid);
+        self.state = ProcessState::Stopped;
+        self.stop_running_threads()?;
+
+        let pid = self.expect_pid();
+        if let Some(objects) = self.objects.as_mut()
+            && let Err(e) = objects.refresh(pid)
+        {
+            warn!(?pid, "Cannot refresh loaded objects: {e}");
+        }
+        self.resolve_symbolic_breakpoints()
+    }
+
+    /// Wait for the next event from the inferior's threads that the debugger
+    /// needs to act on: a thread stopping, or the whole inferior exiting.
+    /// Along the way, new threads are added, exited threads are forgotten, and
+    /// the `SIGSTOP`s we sent to stop threads are swallowed. Forks and execs
+    /// are dealt with too, which may change the process being debugged, and
+    /// signals the user doesn't stop for are passed on (or not) there and then.
+    /// Syscalls no catchpoint is for are continued through.
+    fn wait_for_thread_event(&mut self, stepping: Option<Pid>) -> Result<WaitStatus> {
+        loop {
+            let wait_status = self.wait_for_any_thread()?;
+            match wait_status {
+                WaitStatus::PtraceEvent(tid, _, libc::PTRACE_EVENT_CLONE) => {
+                    let new_thread = self.add_cloned_thread(tid)?;
+                    // while stepping, only the stepped thread runs
+                    if stepping.is_none()
+                        && let Some(new_tid) = new_thread
+                    {
+                        self.resume_thread(new_tid, None, None)?;
+                    }
+                    self.resume_thread(tid, stepping, None)?;
+                }
+                WaitStatus::PtraceEvent(
+                    tid,
+                    _,
+                    event @ (libc::PTRACE_EVENT_FORK | libc::PTRACE_EVENT_VFORK),
+                ) => match self.handle_fork(tid, event == libc::PTRACE_EVENT_VFORK)? {
+                    // a step ends in the child, on its return from the fork
+                    Some(child) if stepping.is_some() => {
+                        return Ok(WaitStatus::Stopped(child, Signal::SIGSTOP));
+                    }
+                    Some(child) => self.resume_thread(child, None, None)?,
+                    None => self.resume_thread(tid, stepping, None)?,
+                },
+                WaitStatus::PtraceEvent(tid, _, libc::PTRACE_EVENT_VFORK_DONE) => {
+                    self.finish_vfork()?;
+                    self.resume_thread(tid, stepping, None)?;
+                }
+                WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_EXEC) => {
+                    self.handle_exec()?;
+                    let pid = self.expect_pid();
+                    // a step ends at the start of the new program
+                    if stepping.is_some() {
+                        return Ok(WaitStatus::Stopped(pid, Signal::SIGTRAP));
+                    }
+                    self.resume_thread(pid, None, None)?;
+                }
+                WaitStatus::Stopped(tid, Signal::SIGSTOP)
+                    if self.threads.get(&tid).is_some_and(Thread::pending_sigstop) =>
+                {
+                    trace!(?tid, "Swallowing our SIGSTOP");
+                    if let Some(thread) = self.threads.get_mut(&tid) {
+                        thread.set_pending_sigstop(false);
+                    }
+                    self.resume_thread(tid, stepping, None)?;
+                }
+                WaitStatus::Stopped(tid, signal) if is_group_stop(tid, signal) => {
+                    trace!(?tid, %signal, "Resuming from group-stop");
+                    self.resume_thread(tid, stepping, None)?;
+                }
+                WaitStatus::Stopped(tid, signal) if !self.signals.get(signal).stop => {
+                    let pass = self.note_ignored_signal(tid, signal);
+                    self.resume_thread(tid, stepping, pass)?;
+                }
+                WaitStatus::PtraceSyscall(tid) => {
+                    let info = match self.syscall_stop_info(tid) {
+                        Ok(info) => info,
+                        // it's exiting, which waitpid will tell us
+                        Err(e) if is_gone(&e) => continue,
+                        Err(e) => return Err(e),
+                    };
+                    // an exit without an entry is from before we caught syscalls
+                    if let Some((entry, _)) = info
+                        && self.catchpoint_for(entry.number).is_some()
+                    {
+                        return Ok(wait_status);
+                    }
+                    self.resume_thread(tid, stepping, None)?;
+                }
+                WaitStatus::Exited(tid, _) | WaitStatus::Signaled(tid, _, _)
+                    if tid != self.expect_pid() =>
+                {
+                    trace!(?tid, "Thread exited");
+                    self.remove_thread(tid);
+                    if stepping == Some(tid) {
+                        return Ok(wait_status);
+                    }
+                }
+                _ => return Ok(wait_status),
+            }
+        }
+    }
+
+    /// Wait for any of the inferior's threads to change state. Events of the
+    /// processes held after a fork are dealt with here, and the first stop of
+    /// a new thread or process is kept for `wait_for_new_task` if it comes
+    /// before the event announcing it.
+    fn wait_for_any_thread(&mut self) -> Result<WaitStatus> {
+        loop {
+            // only our own tracees: the debugger's other threads (or, in the
+            // tests, other debuggers) may have children of their own
+            let wait_status = waitpid(None, Some(WaitPidFlag::__WALL | WaitPidFlag::__WNOTHREAD))?;
+            let Some(tid) = wait_status.pid() else {
+                return Ok(wait_status);
+            };
+            // the main thread's ID may be taken over by a thread that exec'd
+            if tid == self.expect_pid() || self.threads.contains_key(&tid) {
+                return Ok(wait_status);
+            }
+            if self.is_held_thread(tid) {
+                self.held_thread_event(tid, wait_status);
+                continue;
+            }
+            match wait_status {
+                WaitStatus::Stopped(..) | WaitStatus::PtraceEvent(..) => {
+                    trace!(?tid, ?wait_status, "New thread or process stopped early");
+                    self.early_stops.insert(tid);
+                }
+                _ => trace!(?tid, ?wait_status, "Event of a thread we no longer track"),
+            }
+        }
+    }
+
+    /// Whether a thread belongs to a process on the other side of a fork.
+    fn is_held_thread(&self, tid: Pid) -> bool {
+        let vfork_parent = match &self.pending_vfork {
+            Some(PendingVfork::Parent { parent, .. }) => Some(parent),
+            _ => None,
+        };
+        self.held_processes
+            .iter()
+            .chain(vfork_parent)
+            .any(|process| process.threads.contains(&tid))
+    }
+
+    /// A thread of a held process changed state. They're kept stopped, so this
+    /// is it being killed, and it's forgotten.
+    fn held_thread_event(&mut self, tid: Pid, wait_status: WaitStatus) {
+        if !matches!(
+            wait_status,
+            WaitStatus::Exited(..) | WaitStatus::Signaled(..)
+        ) {
+            trace!(?tid, ?wait_status, "Held thread changed state");
+            return;
+        }
+        trace!(?tid, ?wait_status, "Held thread exited");
+        for process in &mut self.held_processes {
+            process.threads.retain(|held| *held != tid);
+        }
+        self.held_processes
+            .retain(|process| process.pid != tid && !process.threads.is_empty());
+    }
+
+    /// Continue (or single step) a thread, delivering `signal`, e.g. after it
+    /// stopped for the debugger's own reasons, like telling us about a new
+    /// thread. While there are catchpoints, it's continued to its next syscall
+    /// entry or exit.
+    fn resume_thread(
+        &mut self,
+        tid: Pid,
+        stepping: Option<Pid>,
+        signal: Option<Signal>,
+    ) -> Result<()> {
+        match stepping == Some(tid) {
+            true => ptrace::step(tid, signal)?,
+            false if self.catching_syscalls() => ptrace::syscall(tid, signal)?,
+            false => ptrace::cont(tid, signal)?,
+        }
+        if let Some(thread) = self.threads.get_mut(&tid) {
+            thread.set_running();
+        }
+        Ok(())
+    }
+
+    /// Log a signal we aren't stopping for, if the user wants to know about it.
+    /// Returns the signal to resume the thread with, if it's passed on.
+    fn note_ignored_signal(&self, tid: Pid, signal: Signal) -> Option<Signal> {
+        let disposition = self.signals.get(signal);
+        if disposition.print {
+            info!(%tid, %signal, pass = disposition.pass, "Program received signal");
+        }
+        disposition.pass.then_some(signal)
+    }
+
+    /// Stop all of the threads that are still running, once one of them has
+    /// stopped. A thread may stop for another reason before our `SIGSTOP`
+    /// arrives; if it hit a breakpoint, it's rewound to hit it again when it's
+    /// resumed.
+    fn stop_running_threads(&mut self) -> Result<()> {
+        let pid = self.expect_pid();
+        let running: Vec<Pid> = self
+            .threads
+            .values()
+            .filter(|thread| thread.state() == ThreadState::Running)
+            .map(Thread::tid)
+            .collect();
+        for tid in running {
+            let pending_sigstop = self.threads[&tid].pending_sigstop();
+            if !pending_sigstop && let Err(e) = tgkill(pid, tid, Signal::SIGSTOP) {
+                // it's exiting, which waitpid will tell us
+                trace!(?tid, "Cannot stop thread: {e}");
+            }
+            loop {
+                let wait_status = waitpid(tid, Some(WaitPidFlag::__WALL))?;
+                match wait_status {
+                    // our SIGSTOP is still to come after these
+                    WaitStatus::Stopped(_, signal) if is_group_stop(tid, signal) => {
+                        ptrace::cont(tid, None)?;
+                    }
+                    WaitStatus::Stopped(_, signal)
+                        if signal != Signal::SIGSTOP && !self.signals.get(signal).stop =>
+                    {
+                        let pass = self.note_ignored_signal(tid, signal);
+                        ptrace::cont(tid, pass)?;
+                    }
+                    WaitStatus::Stopped(_, signal) => {
+                        let (registers, trap) = match self.read_stopped_registers(tid, signal) {
+                            Ok(stopped) => stopped,
+                            // killed since it stopped; its exit is reaped with
+                            // the rest of the process's events
+                            Err(e) if is_gone(&e) => {
+                                trace!(?tid, "Thread is gone");
+                                self.remove_thread(tid);
+                                break;
+                            }
+                            Err(e) => return Err(e),
+                        };
+                        let mut trap = trap;
+                        if matches!(trap, Some(TrapType::HardwareBreak | TrapType::SingleStep))
+                            && let Some(inferior) = self.inferior_process.as_mut()
+                        {
+                            // a watchpoint hit is lost; clear the sticky DR6
+                            let hit = inferior.take_hardware_stoppoint_hit(tid)?;
+                            if trap == Some(TrapType::HardwareBreak)
+                                && hit.is_some_and(|hit| {
+                                    self.watchpoints.values().any(|w| w.stoppoint_id() == hit)
+                                })
+                            {
+                                trap = Some(TrapType::Watchpoint);
+                            }
+                        }
+                        let pc = registers.get_pc()?;
+                        let thread = self.threads.get_mut(&tid).expect("running thread");
+                        thread.set_pending_sigstop(signal != Signal::SIGSTOP);
+                        let reason = StopReason::Stopped {
+                            tid,
+                            signal,
+                            trap,
+                            pc,
+                        };
+                        thread.set_stopped(registers, reason);
+                        break;
+                    }
+                    WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_CLONE) => {
+                        // the new thread is left stopped
+                        self.add_cloned_thread(tid)?;
+                        ptrace::cont(tid, None)?;
+                    }
+                    WaitStatus::PtraceEvent(
+                        _,
+                        _,
+                        event @ (libc::PTRACE_EVENT_FORK | libc::PTRACE_EVENT_VFORK),
+                    ) => {
+                        // another thread's stop is being reported, so it's
+                        // too late to follow the child
+                        if let Some(child) = self.wait_for_new_task(tid)? {
+                            self.leave_child(child, event == libc::PTRACE_EVENT_VFORK)?;
+                        }
+                        ptrace::cont(tid, None)?;
+                    }
+                    WaitStatus::PtraceEvent(_, _, libc::PTRACE_EVENT_VFORK_DONE) => {
+                        self.finish_vfork()?;
+                        ptrace::cont(tid, None)?;
+                    }
+                    // another thread's stop is being reported, so the syscall
+                    // isn't caught
+                    WaitStatus::PtraceSyscall(_) => {
+                        ptrace::cont(tid, None)?;
+                    }
+                    WaitStatus::Exited(..) | WaitStatus::Signaled(..) => {
+                        self.remove_thread(tid);
+                        break;
+                    }
+                    _ => {
+                        warn!(?tid, ?wait_status, "Unexpected status stopping thread");
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the registers of a thread that has just stopped, and work out why
+    /// a `SIGTRAP` was raised. After a software breakpoint, the PC is set back
+    /// to the breakpoint.
+    fn read_stopped_registers(
+        &self,
+        tid: Pid,
+        signal: Signal,
+    ) -> Result<(RegisterSnapshot, Option<TrapType>)> {
+        let mut registers = read_all_registers(tid)?;
+        if !matches!(signal, Signal::SIGTRAP) {
+            return Ok((registers, None));
+        }
+        let trap_type = TrapType::from_siginfo(&ptrace::getsiginfo(tid)?);
+        if matches!(trap_type, TrapType::SoftwareBreak) {
+            // on some arches (x86) the PC is reported past the trap
+            // instruction; set it back to where the breakpoint is
+            let cur_pc = registers.get_pc()?;
+            let instr_begin = HostArch::breakpoint_address(cur_pc);
+            if instr_begin != cur_pc
+                && self
+                    .breakpoint_sites
+                    .contains_enabled_software_vaddr(&instr_begin)
+            {
+                registers.set_pc(instr_begin)?;
+            }
+        }
+        Ok((registers, Some(trap_type)))
+    }
+
+    /// Start tracking the thread announced by a `PTRACE_EVENT_CLONE` stop of
+    /// its parent. The new thread is left stopped.
+    fn add_cloned_thread(&mut self, parent: Pid) -> Result<Option<Pid>> {
+        let Some(tid) = self.wait_for_new_task(parent)? else {
+            return Ok(None);
+        };
+        trace!(?parent, ?tid, "New thread");
+        match self.add_stopped_thread(tid) {
+            Ok(()) => Ok(Some(tid)),
+            Err(e) if is_gone(&e) => {
+                trace!(?tid, "New thread is already gone");
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Wait for the new thread or process announced by a clone or fork event
+    /// stop of `parent` to stop, which it does with a `SIGSTOP` as soon as
+    /// it starts (and may have done already, before the event was reported).
+    /// `None` if it's gone already.
+    fn wait_for_new_task(&mut self, parent: Pid) -> Result<Option<Pid>> {
+        let tid = Pid::from_raw(ptrace::getevent(parent)? as i32);
+        if self.early_stops.remove(&tid) {
+            return Ok(Some(tid));
+        }
+        let wait_status = waitpid(tid, Some(WaitPidFlag::__WALL))?;
+        if !matches!(wait_status, WaitStatus::Stopped(..)) {
+            trace!(?tid, ?wait_status, "New thread or process is already gone");
+            return Ok(None);
+        }
+        Ok(Some(tid))
+    }
+
+    /// Add a thread that's stopped (with a `SIGSTOP`) to the thread table.
+    fn add_stopped_thread(&mut self, tid: Pid) -> Result<()> {
+        let registers = read_all_registers(tid)?;
+        if let Some(inferior) = self.inferior_process.as_mut() {
+            inferior.add_thread(tid)?;
+        }
+        let reason = StopReason::Stopped {
+            tid,
+            signal: Signal::SIGSTOP,
+            trap: None,
+            pc: registers.get_pc()?,
+        };
+        let mut thread = Thread::new(self.expect_pid(), tid, ThreadState::Stopped);
+        thread.set_stopped(registers, reason);
+        self.threads.insert(tid, thread);
+        Ok(())
+    }
+
+    /// Forget a thread that has exited.
+    fn remove_thread(&mut self, tid: Pid) {
+        self.threads.remove(&tid);
+        if let Some(inferior) = self.inferior_process.as_mut() {
+            inferior.remove_thread(tid);
+        }
+        if self.current_thread == Some(tid) {
+            self.current_thread = self.threads.keys().next().copied();
+        }
+    }
+
+    /// Deal with a process forked by one of the inferior's threads, according
+    /// to the fork settings. Returns the child if we're now following it, in
+    /// which case it's left stopped.
+    fn handle_fork(&mut self, tid: Pid, vfork: bool) -> Result<Option<Pid>> {
+        let Some(child) = self.wait_for_new_task(tid)? else {
+            return Ok(None);
+        };
+        match self.fork_settings.follow_fork_mode {
+            FollowForkMode::Parent => {
+                self.leave_child(child, vfork)?;
+                Ok(None)
+            }
+            FollowForkMode::Child => {
+                info!(parent = %self.expect_pid(), %child, "Following the child process");
+                self.follow_child(tid, child, vfork)?;
+                Ok(Some(child))
+            }
+        }
+    }
+
+    /// Detach from (or hold) the child of a fork we're not following. After a
+    /// `vfork`, the parent can't run until the child execs or exits, so the
+    /// child is always detached.
+    fn leave_child(&mut self, child: Pid, vfork: bool) -> Result<()> {
+        let child = HeldProcess {
+            pid: child,
+            threads: vec![child],
+        };
+        if !self.fork_settings.detach_on_fork && !vfork {
+            info!(pid = %child.pid, "Holding the child process");
+            self.held_processes.push(child);
+            return Ok(());
+        }
+        // after a vfork, this takes the traps out of the parent too
+        self.detach_process(&child)?;
+        if vfork {
+            self.pending_vfork = Some(PendingVfork::TrapsRemoved);
+        }
+        Ok(())
+    }
+
+    /// Switch to debugging the (stopped) child of a fork. The rest of the
+    /// parent is stopped, to be detached from (or held) once it no longer
+    /// shares memory with the child.
+    fn follow_child(&mut self, tid: Pid, child: Pid, vfork: bool) -> Result<()> {
+        // the forking thread is already stopped, to tell us about the child
+        let registers = read_all_registers(tid)?;
+        let reason = StopReason::Stopped {
+            tid,
+            signal: Signal::SIGTRAP,
+            trap: None,
+            pc: registers.get_pc()?,
+        };
+        if let Some(thread) = self.threads.get_mut(&tid) {
+            thread.set_stopped(registers, reason);
+        }
+        self.stop_running_threads()?;
+        self.take_pending_sigstops()?;
+
+        let parent = HeldProcess {
+            pid: self.expect_pid(),
+            threads: self.threads.keys().copied().collect(),
+        };
+        let detach = self.fork_settings.detach_on_fork;
+        self.inferior_process
+            .as_mut()
+            .expect("forking inferior")
+            .follow(child)?;
+        match vfork {
+            true => self.pending_vfork = Some(PendingVfork::Parent { parent, detach }),
+            false => self.leave_parent(parent, detach)?,
+        }
+
+        self.threads.clear();
+        self.current_thread = Some(child);
+        self.add_stopped_thread(child)
+    }
+
+    /// Detach from (or hold) the parent of a fork whose child we're following.
+    fn leave_parent(&mut self, parent: HeldProcess, detach: bool) -> Result<()> {
+        match detach {
+            true => self.detach_process(&parent),
+            false => {
+                info!(pid = %parent.pid, "Holding the parent process");
+                self.held_processes.push(parent);
+                Ok(())
+            }
+        }
+    }
+
+    /// Detach from a process on the other side of a fork, with our
+    /// breakpoints taken out of it first.
+    fn detach_process(&self, process: &HeldProcess) -> Result<()> {
+        let inferior = self.inferior_process.as_ref().expect("forked inferior");
+        inferior.remove_stoppoints_from(process.pid, &process.threads)?;
+        for tid in &process.threads {
+            ptrace::detach(*tid, None)?;
+        }
+        trace!(pid = ?process.pid, "Detached from process");
+        Ok(())
+    }
+
+    /// The parent of a `vfork` has its memory to itself again, so put back the
+    /// traps we took out to detach the child.
+    fn finish_vfork(&mut self) -> Result<()> {
+        if matches!(self.pending_vfork, Some(PendingVfork::TrapsRemoved)) {
+            self.pending_vfork = None;
+            if let Some(inferior) = self.inferior_process.as_ref() {
+                inferior.reinsert_traps()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The `vfork` child we're following has exec'd or exited, so its parent
+    /// has its memory to itself again, and can be let go.
+    fn release_vfork_parent(&mut self) -> Result<()> {
+        match self.pending_vfork.take() {
+            Some(PendingVfork::Parent { parent, detach }) => self.leave_parent(parent, detach),
+            pending => {
+                self.pending_vfork = pending;
+                Ok(())
+            }
+        }
+    }
+
+    /// The inferior has exec'd a new program. Our traps and debug registers
+    /// went with the old image, as did any other threads, so load the new
+    /// executable's symbols, then resolve and insert the breakpoints again.
+    fn handle_exec(&mut self) -> Result<()> {
+        let pid = self.expect_pid();
+        self.release_vfork_parent()?;
+        // whichever thread exec'd now has the process' ID
+        self.threads = BTreeMap::from([(pid, Thread::new(pid, pid, ThreadState::Running))]);
+        self.current_thread = Some(pid);
+        self.inferior_process
+            .as_mut()
+            .expect("exec'd inferior")
+            .reset_for_exec();
+
+        let executable = executable_for_pid(pid)?;
+        info!(%pid, ?executable, "Process is executing a new program");
+        self.load_executable(&executable);
+        if let Some(objects) = self.objects.as_mut()
+            && let Err(e) = objects.refresh(pid)
+        {
+            warn!(?pid, "Cannot refresh loaded objects: {e}");
+        }
+        self.resolve_symbolic_breakpoints()?;
+
+        // address breakpoints and watchpoints keep their addresses, which may
+        // not be mapped in the new program
+        let inferior = self.inferior_process.as_mut().expect("exec'd inferior");
+        for site in self.breakpoint_sites.values().filter(|b| b.is_enabled()) {
+            if let Err(e) = inferior.enable_breakpoint_site(site) {
+                warn!(address = %site.address(), "Cannot insert breakpoint: {e}");
+            }
+        }
+        for w in self.watchpoints.values().filter(|w| w.is_enabled()) {
+            if let Err(e) = inferior.enable_hardware_stoppoint(w.stoppoint_id(), w.stoppoint()) {
+                warn!(id = %w.id(), "Cannot insert watchpoint: {e}");
+            }
+        }
+        let mut watchpoints = std::mem::take(&mut self.watchpoints);
+        for w in watchpoints.values_mut() {
+            w.update_value(self.read_watched_value(w.stoppoint()));
+        }
+        self.watchpoints = watchpoints;
+        Ok(())
+    }
+
+    /// Let go of the processes held on the other side of forks: they're
+    /// killed if we spawned the inferior, and otherwise detached from.
+    fn release_held_processes(&mut self) -> Result<()> {
+        let mut held = std::mem::take(&mut self.held_processes);
+        if let Some(PendingVfork::Parent { parent, .. }) = self.pending_vfork.take() {
+            held.push(parent);
+        }
+        let Some(inferior) = self.inferior_process.as_ref() else {
+            return Ok(());
+        };
+        let attached = inferior.is_attached();
+        for process in held {
+            if attached {
+                self.detach_process(&process)?;
+                continue;
+            }
+            kill(process.pid, Some(Signal::SIGKILL))?;
+            // reap it, as we're tracing it
+            for tid in process.threads {
+                let _ = waitpid(tid, Some(WaitPidFlag::__WALL));
+            }
+        }
+        Ok(())
+    }
+
+    /// What happens when the inferior forks.
+    pub fn fork_settings(&self) -> ForkSettings {
+        self.fork_settings
+    }
+
+    pub fn set_fork_settings(&mut self, settings: ForkSettings) {
+        self.fork_settings = settings;
+    }
+
+    /// How each signal is handled when the inferior receives it.
+    pub fn signals(&self) -> &SignalTable {
+        &self.signals
+    }
+
+    /// Change how a signal is handled. This takes effect straight away, so a
+    /// signal the inferior is stopped for is only delivered when it's resumed
+    /// if it's still passed.
+    pub fn set_signal_disposition(
+        &mut self,
+        signal: Signal,
+        disposition: SignalDisposition,
+    ) -> Result<()> {
+        self.signals.set(signal, disposition)
+    }
+
+    /// Work out whether a watchpoint caused the thread's `SIGTRAP`, and if so
+    /// record its new value.
+    fn check_watchpoint_hit(&mut self, tid: Pid) -> Result<()> {
+        let Some(inferior) = self.inferior_process.as_mut() else {
+            return Ok(());
+        };
+        let Some(stoppoint_id) = inferior.take_hardware_stoppoint_hit(tid)? else {
+            return Ok(());
+        };
+        let Some(id) = self
+            .watchpoints
+            .values()
+            .find(|w| w.stoppoint_id() == stoppoint_id)
+            .map(|w| w.id())
+        else {
+            return Ok(());
+        };
+        let watchpoint = &self.watchpoints[&id];
+        // the process isn't marked as stopped yet, so read via the inferior
+        let value = read_watched_value(inferior, watchpoint.stoppoint());
+        self.watchpoints
+            .get_mut(&id)
+            .expect("found above")
+            .update_value(value);
+        self.watchpoint_hit = Some(id);
+        Ok(())
+    }
+
+    /// Whether the last stop should be reported: a stop at a breakpoint,
+    /// watchpoint or catchpoint only counts (as a hit) if its condition holds,
+    /// and then only stops once its ignore count has run out.
+    fn stop_requested(&mut self) -> bool {
+        let Some(id) = self.stoppoint_hit() else {
+            return true;
+        };
+        let policy = self.stop_policy(id).expect("hit stoppoint exists");
+        if !self.condition_holds(id, policy.condition()) {
+            return false;
+        }
+        // a breakpoint's hits are counted by the site that was hit, so each of
+        // its locations has its own count
+        let breakpoint = self.breakpoint_hit == Some(id);
+        if breakpoint
+            && let Some(pc) = self.pc()
+            && let Some(site) = self
+                .breakpoint_sites
+                .values_mut()
+                .find(|site| site.at_address(&pc) && site.is_enabled())
+        {
+            site.record_hit();
+        }
+        let (_, policy) = self.stop_policy_mut(id).expect("hit stoppoint exists");
+        if !breakpoint {
+            policy.record_hit();
+        }
+        !policy.take_ignored_hit()
+    }
+
+    /// Evaluate a breakpoint's condition. If it can't be evaluated (e.g. it
+    /// reads unmapped memory), stop, so the user can see what's wrong.
+    fn condition_holds(&self, id: BreakpointId, condition: Option<&Expression>) -> bool {
+        let Some(condition) = condition else {
+            return true;
+        };
+        match condition.evaluate(self) {
+            Ok(value) => value != 0,
+            Err(e) => {
+                warn!(%id, "Cannot evaluate condition `{condition}`: {e}");
+                true
+            }
+        }
+    }
+
+    /// The catchpoint, watchpoint or breakpoint the last stop was for, if any.
+    pub fn stoppoint_hit(&self) -> Option<BreakpointId> {
+        self.catchpoint_hit
+            .or(self.watchpoint_hit)
+            .or(self.breakpoint_hit)
+    }
+
+    /// The stop policy of the breakpoint, watchpoint or catchpoint with the
+    /// given number.
+    pub fn stop_policy(&self, id: BreakpointId) -> Option<&StopPolicy> {
+        if let Some(watchpoint) = self.watchpoints.get(&id) {
+            Some(watchpoint.policy())
+        } else if let Some(catchpoint) = self.catchpoints.get(&id) {
+            Some(catchpoint.policy())
+        } else {
+            self.breakpoints.get(&id).map(Breakpoint::policy)
+        }
+    }
+
+    /// The stop policy of the breakpoint, watchpoint or catchpoint with the
+    /// given number, and which of them it is.
+    fn stop_policy_mut(&mut self, id: BreakpointId) -> Result<(&'static str, &mut StopPolicy)> {
+        if let Some(watchpoint) = self.watchpoints.get_mut(&id) {
+            Ok(("watchpoint", watchpoint.policy_mut()))
+        } else if let Some(catchpoint) = self.catchpoints.get_mut(&id) {
+            Ok(("catchpoint", catchpoint.policy_mut()))
+        } else if let Some(breakpoint) = self.breakpoints.get_mut(&id) {
+            Ok(("breakpoint", breakpoint.policy_mut()))
+        } else {
+            Err(anyhow!("No breakpoint number {id}"))
+        }
+    }
+
+    /// The breakpoint the last stop was at, if any.
+    pub fn breakpoint_hit(&self) -> Option<&Breakpoint> {
+        self.breakpoints.get(&self.breakpoint_hit?)
+    }
+
+    /// The watchpoint that triggered the last stop, if any.
+    pub fn watchpoint_hit(&self) -> Option<&Watchpoint> {
+        self.watchpoints.get(&self.watchpoint_hit?)
+    }
+
+    /// All watchpoints, ordered by ID.
+    pub fn watchpoints(&self) -> impl Iterator<Item = &Watchpoint> {
+        self.watchpoints.values()
+    }
+
+    /// The catchpoint that caught the last stop's syscall, if any.
+    pub fn catchpoint_hit(&self) -> Option<&Catchpoint> {
+        self.catchpoints.get(&self.catchpoint_hit?)
+    }
+
+    /// The syscall a catchpoint last stopped the inferior on the way into or
+    /// out of, if that's why it stopped.
+    pub fn last_syscall(&self) -> Option<&SyscallStop> {
+        self.last_syscall.as_ref()
+    }
+
+    /// All catchpoints, ordered by ID.
+    pub fn catchpoints(&self) -> impl Iterator<Item = &Catchpoint> {
+        self.catchpoints.values()
+    }
+
+    /// Whether threads are continued with `PTRACE_SYSCALL`, for catchpoints.
+    fn catching_syscalls(&self) -> bool {
+        self.catchpoints.values().any(Catchpoint::is_enabled)
+    }
+
+    /// The first enabled catchpoint for a syscall.
+    fn catchpoint_for(&self, number: u64) -> Option<BreakpointId> {
+        self.catchpoints
+            .values()
+            .find(|c| c.is_enabled() && c.catches(number))
+            .map(Catchpoint::id)
+    }
+
+    /// The syscall a thread is stopped on the way into, or out of along with
+    /// its return value. Only the return value is reported at the exit, so the
+    /// entry is kept with the thread until then. `None` for an exit whose entry
+    /// we didn't see.
+    fn syscall_stop_info(&mut self, tid: Pid) -> Result<Option<(SyscallEntry, Option<u64>)>> {
+        let info = ptrace::syscall_info(tid)?;
+        let thread = self.threads.get_mut(&tid);
+        match info.op {
+            libc::PTRACE_SYSCALL_INFO_ENTRY => {
+                // the op says which member of the union the kernel filled in
+                let entry = unsafe { info.u.entry };
+                let entry = SyscallEntry {
+                    number: entry.nr,
+                    args: entry.args,
+                };
+                if let Some(thread) = thread {
+                    thread.set_syscall_entry(entry);
+                }
+                Ok(Some((entry, None)))
+            }
+            libc::PTRACE_SYSCALL_INFO_EXIT => {
+                let returned = unsafe { info.u.exit }.sval as u64;
+                Ok(thread
+                    .and_then(|thread| thread.syscall_entry())
+                    .map(|entry| (entry, Some(returned))))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Decode the syscall a thread is stopped on the way into or out of, with
+    /// the arguments it was made with.
+    fn decode_syscall_stop(&self, entry: SyscallEntry, returned: Option<u64>) -> SyscallStop {
+        // the process isn't marked as stopped yet, so read via the inferior
+        let inferior = self.inferior_process.as_ref().expect("stopped inferior");
+        SyscallStop::decode(entry.number, entry.args, returned, |address, len| {
+            inferior.read_memory_without_traps(address, len)
+        })
+    }
+
+    fn read_watched_value(&self, stoppoint: &HardwareStoppoint) -> Option<u64> {
+        read_watched_value(self.expect_inferior().ok()?, stoppoint)
+    }
+
+    /// Number of debug registers needed by the enabled hardware stoppoints.
+    fn hardware_slots_in_use(&self) -> usize {
+        let watchpoints = self.watchpoints.values().filter(|w| w.is_enabled());
+        let sites = self
+            .breakpoint_sites
+            .values()
+            .filter(|b| b.is_hardware() && b.is_enabled());
+        watchpoints.count() + sites.count()
+    }
+
+    fn ensure_hardware_slots_available(&self, needed: usize) -> Result<()> {
+        if self.hardware_slots_in_use() + needed > HARDWARE_SLOTS {
+            return Err(anyhow!(
+                "All {HARDWARE_SLOTS} hardware debug registers are in use; delete or disable a hardware breakpoint or watchpoint first"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Why the inferior last stopped (or exited), if it has.
+    pub fn last_stop(&self) -> Option<&StopReason> {
+        self.last_stop.as_ref()
+    }
+
+    /// Stop debugging the inferior. A process we spawned is killed, while a
+    /// process we attached to is detached from (with our breakpoints removed),
+    /// and left running.
     pub fn destroy(&mut self) -> Result<()> {
-        if !matches!(self.state, ProcessState::Running) {
+        self.release_held_processes()?;
+        self.generation += 1;
+        if !matches!(self.state, ProcessState::Stopped | ProcessState::Running) {
             return Ok(());
         }
 
         let pid = self.expect_pid();
 
         // tell the inferior to STOP and wait for it
-        kill(pid, Some(Signal::SIGSTOP))?;
-        waitpid(pid, None)?;
+        if matches!(self.state, ProcessState::Running) {
+            self.stop_running_threads()?;
+        }
 
-        // let the inferior know we are done tracing it
-        ptrace::detach(pid, None)?;
-        kill(pid, Some(Signal::SIGCONT))?;
+        let attached = self
+            .inferior_process
+            .as_ref()
+            .is_some_and(|inferior| inferior.is_attached());
+        if attached {
+            // don't leave any int3s behind in a process that keeps running
+            self.disable_breakpoint_sites()?;
+            self.detach_threads()?;
+            trace!(?pid, "Detached from inferior");
+            self.inferior_process = None;
+            self.state = ProcessState::Detached;
+            return Ok(());
+        }
 
-        // we launched the inferior process, so we should reap it here
+        // we launched the inferior process (or its parent), so we should reap
+        // it here. We keep tracing it, as after following a fork it isn't
+        // our child, and waiting for it relies on that.
         kill(pid, Some(Signal::SIGKILL))?;
         self.wait_on_signal()?;
 
@@ -265,6 +1689,31 @@ impl Process {
         Ok(())
     }
 
+    /// Detach from every thread of the inferior, delivering any signal it
+    /// stopped for that's passed to the program.
+    fn detach_threads(&mut self) -> Result<()> {
+        self.take_pending_sigstops()?;
+        for thread in self.threads.values() {
+            if thread.state() != ThreadState::Exited {
+                ptrace::detach(thread.tid(), self.signals.signal_to_pass(thread))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Take the `SIGSTOP`s we sent that are still pending, which would stop
+    /// the process once we're gone.
+    fn take_pending_sigstops(&mut self) -> Result<()> {
+        for thread in self.threads.values_mut() {
+            if thread.state() != ThreadState::Exited && thread.pending_sigstop() {
+                ptrace::cont(thread.tid(), None)?;
+                waitpid(thread.tid(), Some(WaitPidFlag::__WALL))?;
+                thread.set_pending_sigstop(false);
+            }
+        }
+        Ok(())
+    }
+
     pub fn receive_inferior_logging(&mut self, output: String) {
         output.lines().for_each(|l| {
             if !l.is_empty() {
@@ -282,61 +1731,540 @@ impl Process {
         // TODO: maybe add check to ensure target process is indeed running/being debugged,
         // but perhaps having self.registers may be sufficient
 
-        self.registers
-            .as_ref()
-            .map(|snapshot| snapshot.read(&register))
+        self.registers().map(|snapshot| snapshot.read(&register))
+    }
+
+    /// Write a register of the stopped inferior.
+    pub fn write_register(&mut self, register: Register, value: RegisterValue) -> Result<()> {
+        if !matches!(self.state, ProcessState::Stopped) {
+            return Err(anyhow!("Inferior process is not stopped"));
+        }
+        let Some(registers) = self
+            .current_thread
+            .and_then(|tid| self.threads.get_mut(&tid))
+            .and_then(Thread::registers_mut)
+        else {
+            return Err(anyhow!("No registers yet"));
+        };
+        registers.write(register, value)?;
+        self.generation += 1;
+        Ok(())
+    }
+
+    /// Evaluate an address expression against the inferior's current state.
+    pub fn evaluate_address(&self, expression: &Expression) -> Result<VirtualAddress> {
+        expression.evaluate(self).map(VirtualAddress::from)
     }
 
-    /// React to a breakpoint command the user has issued.
-    pub fn breakpoint_command(&mut self, command: BreakpointCommand) -> Result<()> {
+    /// Read `len` bytes of the inferior's memory, with any of our breakpoint
+    /// instructions masked out.
+    pub fn read_memory(&self, address: VirtualAddress, len: usize) -> Result<Vec<u8>> {
+        self.expect_inferior()?
+            .read_memory_without_traps(address, len)
+    }
+
+    /// Disassemble up to `count` instructions starting at `address`, as they
+    /// are without our breakpoints.
+    pub fn disassemble(&self, address: VirtualAddress, count: usize) -> Result<Vec<Instruction>> {
+        let len = count * MAX_INSTRUCTION_LEN;
+        // the range may run into an unmapped page, so fall back to reading up
+        // to the end of the current one
+        let code = match self.read_memory(address, len) {
+            Ok(code) => code,
+            Err(_) => {
+                let to_page_end = PAGE_SIZE - (address.addr() % PAGE_SIZE);
+                self.read_memory(address, len.min(to_page_end as usize))?
+            }
+        };
+        disassemble(&code, address, count)
+    }
+
+    /// Disassemble the instructions starting in `[start, end)`.
+    pub fn disassemble_range(
+        &self,
+        start: VirtualAddress,
+        end: VirtualAddress,
+    ) -> Result<Vec<Instruction>> {
+        let len = end.addr().saturating_sub(start.addr());
+        if len == 0 || len > MAX_DISASSEMBLY_RANGE {
+            return Err(anyhow!(
+                "Invalid range {start}..{end}, it must be non-empty and at most {MAX_DISASSEMBLY_RANGE} bytes"
+            ));
+        }
+        // an instruction may straddle the end of the range, unless that's the
+        // end of the mapping
+        let code = match self.read_memory(start, len as usize + MAX_INSTRUCTION_LEN - 1) {
+            Ok(code) => code,
+            Err(_) => self.read_memory(start, len as usize)?,
+        };
+        let mut instructions = disassemble(&code, start, usize::MAX)?;
+        instructions.retain(|inst| inst.address < end);
+        Ok(instructions)
+    }
+
+    /// Write `data` into the inferior's memory at `address`.
+    pub fn write_memory(&mut self, address: VirtualAddress, data: &[u8]) -> Result<()> {
+        self.expect_inferior()?;
+        self.inferior_process
+            .as_mut()
+            .expect("checked above")
+            .write_memory(address, data)
+    }
+
+    fn expect_inferior(&self) -> Result<&Inferior> {
+        match (&self.state, self.inferior_process.as_ref()) {
+            (ProcessState::Stopped, Some(inferior)) => Ok(inferior),
+            (_, Some(_)) => Err(anyhow!("Inferior process is not stopped")),
+            (_, None) => Err(anyhow!("Inferior process not being debugged")),
+        }
+    }
+
+    /// React to a breakpoint command the user has issued, returning a
+    /// description of what was done.
+    pub fn breakpoint_command(&mut self, command: BreakpointCommand) -> Result<String> {
+        self.generation += 1;
         match command {
-            BreakpointCommand::Create(address) => {
-                if self.breakpoint_sites.contiains_vaddr(&address) {
-                    // either silently ignore (and return existing value) or return error?
+            BreakpointCommand::Create(location) => {
+                self.create_breakpoint(Breakpoint::new(location))
+            }
+            BreakpointCommand::CreateHardware(location) => {
+                self.create_breakpoint(Breakpoint::new_hardware(location))
+            }
+            BreakpointCommand::Watch(stoppoint) => {
+                if self
+                    .watchpoints
+                    .values()
+                    .any(|w| w.stoppoint().address == stoppoint.address)
+                {
                     return Err(anyhow!(
-                        "Breakpoint site already exists for address {:?}",
-                        address
+                        "Watchpoint already exists for address {}",
+                        stoppoint.address
                     ));
                 }
-
-                let b = BreakpointSite::new(address);
-                self.breakpoint_sites.insert(b.id(), b.clone());
-
+                self.ensure_hardware_slots_available(1)?;
+                let mut watchpoint = Watchpoint::new(stoppoint);
+                if let Some(inferior) = self.inferior_process.as_mut() {
+                    inferior.enable_hardware_stoppoint(watchpoint.stoppoint_id(), &stoppoint)?;
+                }
+                watchpoint.enable();
+                watchpoint.update_value(self.read_watched_value(&stoppoint));
+                let message = watchpoint.to_string();
+                self.watchpoints.insert(watchpoint.id(), watchpoint);
+                Ok(message)
+            }
+            BreakpointCommand::Catch(syscalls) => {
+                let catchpoint = Catchpoint::new(syscalls);
+                let message = catchpoint.to_string();
+                self.catchpoints.insert(catchpoint.id(), catchpoint);
+                Ok(message)
+            }
+            BreakpointCommand::Ignore { id, count } => {
+                let (kind, policy) = self.stop_policy_mut(id)?;
+                policy.set_ignore_count(count);
+                Ok(match count {
+                    0 => format!("Will stop next time {kind} {id} is reached"),
+                    1 => format!("Will ignore next crossing of {kind} {id}"),
+                    _ => format!("Will ignore next {count} crossings of {kind} {id}"),
+                })
+            }
+            BreakpointCommand::Condition { id, condition } => {
+                let (kind, policy) = self.stop_policy_mut(id)?;
+                policy.set_condition(condition.clone());
+                let what = format!("{}{} {id}", kind[..1].to_uppercase(), &kind[1..]);
+                Ok(match condition {
+                    Some(condition) => format!("{what} now stops only if {condition}"),
+                    None => format!("{what} is now unconditional"),
+                })
+            }
+            BreakpointCommand::Commands { id, commands } => {
+                let count = commands.len();
+                let (kind, policy) = self.stop_policy_mut(id)?;
+                policy.set_commands(commands);
+                Ok(match count {
+                    0 => format!("Removed the commands from {kind} {id}"),
+                    1 => format!("Will run 1 command when {kind} {id} is hit"),
+                    _ => format!("Will run {count} commands when {kind} {id} is hit"),
+                })
+            }
+            BreakpointCommand::Delete(id) if self.watchpoints.contains_key(&id) => {
+                let watchpoint = self.watchpoints.remove(&id).expect("checked above");
+                if let Some(inferior) = self.inferior_process.as_mut() {
+                    inferior.disable_hardware_stoppoint(watchpoint.stoppoint_id())?;
+                }
+                Ok(format!("Deleted watchpoint {id}"))
+            }
+            BreakpointCommand::Enable(id) if self.watchpoints.contains_key(&id) => {
+                if !self.watchpoints[&id].is_enabled() {
+                    self.ensure_hardware_slots_available(1)?;
+                }
+                let watchpoint = self.watchpoints.get_mut(&id).expect("checked above");
                 if let Some(inferior) = self.inferior_process.as_mut() {
-                    inferior.enable_breakpoint_site(&b)?;
+                    inferior.enable_hardware_stoppoint(
+                        watchpoint.stoppoint_id(),
+                        watchpoint.stoppoint(),
+                    )?;
                 }
+                watchpoint.enable();
+                Ok(format!("Enabled watchpoint {id}"))
+            }
+            BreakpointCommand::Disable(id) if self.watchpoints.contains_key(&id) => {
+                let watchpoint = self.watchpoints.get_mut(&id).expect("checked above");
+                if let Some(inferior) = self.inferior_process.as_mut() {
+                    inferior.disable_hardware_stoppoint(watchpoint.stoppoint_id())?;
+                }
+                watchpoint.disable();
+                Ok(format!("Disabled watchpoint {id}"))
+            }
+            BreakpointCommand::Delete(id) if self.catchpoints.contains_key(&id) => {
+                self.catchpoints.remove(&id);
+                Ok(format!("Deleted catchpoint {id}"))
+            }
+            BreakpointCommand::Enable(id) if self.catchpoints.contains_key(&id) => {
+                let catchpoint = self.catchpoints.get_mut(&id).expect("checked above");
+                catchpoint.enable();
+                Ok(format!("Enabled catchpoint {id}"))
+            }
+            BreakpointCommand::Disable(id) if self.catchpoints.contains_key(&id) => {
+                let catchpoint = self.catchpoints.get_mut(&id).expect("checked above");
+                catchpoint.disable();
+                Ok(format!("Disabled catchpoint {id}"))
             }
             BreakpointCommand::Delete(id) => {
-                let b = match self.breakpoint_sites.remove(&id) {
-                    Some(b) => b,
-                    None => {
-                        return Err(anyhow!("Cannot find breakpoitn by id {:?}", id));
-                    }
+                let Some(breakpoint) = self.breakpoints.remove(&id) else {
+                    return Err(anyhow!("No breakpoint number {id}"));
                 };
-
-                if let Some(inferior) = self.inferior_process.as_mut() {
-                    inferior.disable_breakpoint_site(&b)?
+                for site_id in breakpoint.sites() {
+                    let Some(site) = self.breakpoint_sites.remove(site_id) else {
+                        continue;
+                    };
+                    if let Some(inferior) = self.inferior_process.as_mut() {
+                        inferior.disable_breakpoint_site(&site)?
+                    }
                 }
+                Ok(format!("Deleted breakpoint {id}"))
             }
             BreakpointCommand::Enable(id) => {
-                if let Some(b) = self.breakpoint_sites.get_mut(&id) {
-                    if let Some(inferior) = self.inferior_process.as_mut() {
-                        inferior.enable_breakpoint_site(b)?;
+                let Some(breakpoint) = self.breakpoints.get(&id) else {
+                    return Err(anyhow!("No breakpoint number {id}"));
+                };
+                if breakpoint.is_hardware() && !breakpoint.is_enabled() {
+                    self.ensure_hardware_slots_available(breakpoint.sites().len())?;
+                }
+                let breakpoint = self.breakpoints.get_mut(&id).expect("checked above");
+                breakpoint.enable();
+                for site_id in breakpoint.sites() {
+                    if let Some(b) = self.breakpoint_sites.get_mut(site_id) {
+                        if let Some(inferior) = self.inferior_process.as_mut() {
+                            inferior.enable_breakpoint_site(b)?;
+                        }
+                        b.enable();
                     }
-                    b.enable();
                 }
+                Ok(format!("Enabled breakpoint {id}"))
             }
             BreakpointCommand::Disable(id) => {
-                if let Some(b) = self.breakpoint_sites.get_mut(&id) {
-                    if let Some(inferior) = self.inferior_process.as_mut() {
-                        inferior.disable_breakpoint_site(b)?;
+                let Some(breakpoint) = self.breakpoints.get_mut(&id) else {
+                    return Err(anyhow!("No breakpoint number {id}"));
+                };
+                breakpoint.disable();
+                for site_id in breakpoint.sites() {
+                    if let Some(b) = self.breakpoint_sites.get_mut(site_id) {
+                        if let Some(inferior) = self.inferior_process.as_mut() {
+                            inferior.disable_breakpoint_site(b)?;
+                        }
+                        b.disable();
                     }
-                    b.disable();
                 }
+                Ok(format!("Disabled breakpoint {id}"))
             }
         }
+    }
+
+    /// Resolve a new breakpoint's location and add its sites, skipping any
+    /// addresses that already have one.
+    fn create_breakpoint(&mut self, mut breakpoint: Breakpoint) -> Result<String> {
+        let location = breakpoint.location().clone();
+        if let BreakpointLocation::Address(address) = location
+            && self.breakpoint_sites.contiains_vaddr(&address)
+        {
+            // either silently ignore (and return existing value) or return error?
+            return Err(anyhow!(
+                "Breakpoint site already exists for address {:?}",
+                address
+            ));
+        }
+
+        let resolved = self.resolve_location(&location)?;
+        let addresses: Vec<VirtualAddress> = resolved
+            .iter()
+            .copied()
+            .filter(|address| !self.breakpoint_sites.contiains_vaddr(address))
+            .collect();
+        if addresses.is_empty() && !resolved.is_empty() {
+            return Err(anyhow!(
+                "Breakpoints already exist at every location of {location}"
+            ));
+        }
+        if breakpoint.is_hardware() {
+            self.ensure_hardware_slots_available(addresses.len())?;
+        }
+
+        breakpoint.enable();
+        for address in addresses {
+            self.add_breakpoint_site(&mut breakpoint, address)?;
+        }
+        let message = self.describe_breakpoint(&breakpoint);
+        self.breakpoints.insert(breakpoint.id(), breakpoint);
+        Ok(message)
+    }
+
+    /// All breakpoint sites (the addresses breakpoints have resolved to).
+    pub fn breakpoint_sites(&self) -> impl Iterator<Item = &BreakpointSite> {
+        self.breakpoint_sites.values()
+    }
+
+    pub fn breakpoint_site(&self, id: &StoppointId) -> Option<&BreakpointSite> {
+        self.breakpoint_sites.get(id)
+    }
+
+    /// All user-visible breakpoints, ordered by ID.
+    pub fn breakpoints(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.breakpoints.values()
+    }
+
+    /// The breakpoint with an enabled site at the given address.
+    pub fn breakpoint_at(&self, address: VirtualAddress) -> Option<&Breakpoint> {
+        self.breakpoint_sites
+            .values()
+            .find(|site| site.at_address(&address) && site.is_enabled())
+            .and_then(|site| self.breakpoints.get(&site.breakpoint()))
+    }
+
+    /// Describe an address relative to the symbol containing it, e.g. `main+4`.
+    pub fn symbolize(&self, address: VirtualAddress) -> Option<String> {
+        let (elf, symbol) = self.objects.as_ref()?.symbol_containing(address)?;
+        let offset = elf.to_file_address(address) - symbol.value;
+        match offset {
+            0 => Some(symbol.display_name().to_string()),
+            _ => Some(format!("{}+{offset}", symbol.display_name())),
+        }
+    }
+
+    /// The `[start, end)` addresses of the function containing `address`.
+    pub fn function_bounds(
+        &self,
+        address: VirtualAddress,
+    ) -> Option<(VirtualAddress, VirtualAddress)> {
+        let (elf, symbol) = self.objects.as_ref()?.symbol_containing(address)?;
+        let start = elf.to_virtual(symbol.value);
+        Some((start, start.offset(symbol.size as i64)))
+    }
+
+    /// The source file and line of an address, from the DWARF line table.
+    pub fn source_location(&self, address: VirtualAddress) -> Option<SourceLocation> {
+        self.objects.as_ref()?.source_location(address)
+    }
+
+    /// The addresses a breakpoint location currently resolves to. Empty if the
+    /// location can't be resolved yet, because the inferior hasn't started.
+    fn resolve_location(&self, location: &BreakpointLocation) -> Result<Vec<VirtualAddress>> {
+        match location {
+            BreakpointLocation::Address(address) => Ok(vec![*address]),
+            BreakpointLocation::Function(_) | BreakpointLocation::Line { .. } => {
+                let Some(objects) = self.objects.as_ref().filter(|_| self.pid().is_some()) else {
+                    return Ok(Vec::new());
+                };
+                let addresses = lookup_location(objects, location);
+                if addresses.is_empty() {
+                    return Err(match location {
+                        BreakpointLocation::Line { file, line } => {
+                            anyhow!("No code for line {line} in `{}`", file.display())
+                        }
+                        _ => anyhow!("No function named `{location}`"),
+                    });
+                }
+                Ok(addresses)
+            }
+        }
+    }
+
+    /// Create a site for the breakpoint at `address`, inserting it into the
+    /// inferior if the breakpoint is enabled.
+    fn add_breakpoint_site(
+        &mut self,
+        breakpoint: &mut Breakpoint,
+        address: VirtualAddress,
+    ) -> Result<()> {
+        // sites share the inferior's memory, so there can only be one per address
+        if self.breakpoint_sites.contiains_vaddr(&address) {
+            return Err(anyhow!(
+                "Breakpoint site already exists for address {address}"
+            ));
+        }
+        let mut site = if breakpoint.is_hardware() {
+            BreakpointSite::new_hardware(breakpoint.id(), address)
+        } else {
+            BreakpointSite::new(breakpoint.id(), address)
+        };
+        if breakpoint.is_enabled() {
+            site.enable();
+            if let Some(inferior) = self.inferior_process.as_mut() {
+                inferior.enable_breakpoint_site(&site)?;
+            }
+        }
+        breakpoint.add_site(site.id());
+        self.breakpoint_sites.insert(site.id(), site);
         Ok(())
     }
+
+    /// Resolve function and line breakpoints against the currently loaded
+    /// objects, adding sites for any new matches (e.g. in a newly loaded shared
+    /// object).
+    fn resolve_symbolic_breakpoints(&mut self) -> Result<()> {
+        let mut breakpoints = std::mem::take(&mut self.breakpoints);
+        let mut res = Ok(());
+        for breakpoint in breakpoints.values_mut() {
+            if !breakpoint.location().is_symbolic() {
+                continue;
+            }
+            let Some(objects) = self.objects.as_ref() else {
+                break;
+            };
+            for address in lookup_location(objects, breakpoint.location()) {
+                // already resolved, or another breakpoint is at the same address
+                if self.breakpoint_sites.contiains_vaddr(&address) {
+                    continue;
+                }
+                trace!(id = %breakpoint.id(), %address, "Resolved breakpoint");
+                if breakpoint.is_hardware()
+                    && breakpoint.is_enabled()
+                    && let Err(e) = self.ensure_hardware_slots_available(1)
+                {
+                    warn!(id = %breakpoint.id(), %address, "Cannot resolve hardware breakpoint: {e}");
+                    continue;
+                }
+                res = self.add_breakpoint_site(breakpoint, address);
+                if res.is_err() {
+                    break;
+                }
+            }
+        }
+        self.breakpoints = breakpoints;
+        res
+    }
+
+    /// Drop the resolved sites of function and line breakpoints, which need
+    /// resolving again in a new inferior (where the load addresses may differ).
+    fn unresolve_symbolic_breakpoints(&mut self) {
+        for breakpoint in self.breakpoints.values_mut() {
+            if breakpoint.location().is_symbolic() {
+                for site in breakpoint.clear_sites() {
+                    self.breakpoint_sites.remove(&site);
+                }
+            }
+        }
+    }
+
+    fn describe_breakpoint(&self, breakpoint: &Breakpoint) -> String {
+        let id = breakpoint.id();
+        let sites: Vec<VirtualAddress> = breakpoint
+            .sites()
+            .iter()
+            .filter_map(|site| self.breakpoint_sites.get(site))
+            .map(|site| site.address())
+            .collect();
+        let kind = if breakpoint.is_hardware() {
+            "Hardware breakpoint"
+        } else {
+            "Breakpoint"
+        };
+        match sites.as_slice() {
+            [] => format!("{kind} {id} ({}) pending", breakpoint.location()),
+            [address] => {
+                let mut message = format!("{kind} {id} at {address}");
+                if let Some(symbol) = self.symbolize(*address) {
+                    message.push_str(&format!(" <{symbol}>"));
+                }
+                if let Some(location) = self.source_location(*address) {
+                    message.push_str(&format!(": {location}"));
+                }
+                message
+            }
+            _ => format!(
+                "{kind} {id} ({}) at {} locations",
+                breakpoint.location(),
+                sites.len()
+            ),
+        }
+    }
+}
+
+/// Read the memory a watchpoint covers, as an unsigned little endian value.
+fn read_watched_value(inferior: &Inferior, stoppoint: &HardwareStoppoint) -> Option<u64> {
+    let data = inferior
+        .read_memory(stoppoint.address, stoppoint.size)
+        .ok()?;
+    let mut buf = [0u8; 8];
+    buf[..data.len()].copy_from_slice(&data);
+    Some(u64::from_le_bytes(buf))
+}
+
+/// Look up the addresses of a function or line breakpoint in the loaded objects.
+fn lookup_location(objects: &LoadedObjects, location: &BreakpointLocation) -> Vec<VirtualAddress> {
+    match location {
+        BreakpointLocation::Address(address) => vec![*address],
+        BreakpointLocation::Function(name) => objects.find_functions(name),
+        BreakpointLocation::Line { file, line } => objects.find_line(file, *line),
+    }
+}
+
+/// Whether a ptrace request failed because the thread is gone: it was killed
+/// after it stopped, e.g. by another thread calling `exit_group`.
+fn is_gone(error: &anyhow::Error) -> bool {
+    error.downcast_ref::<Errno>() == Some(&Errno::ESRCH)
+}
+
+/// Read an integer register by name, zero extended to 64 bits.
+fn register_u64(registers: &RegisterSnapshot, name: &str) -> Result<u64> {
+    let info = register_info_by_name(name).ok_or_else(|| anyhow!("unknown register"))?;
+    let value = i64::try_from(registers.read(&info.register))?;
+    // the conversion sign extends signed values; mask back down to the register width
+    match info.size {
+        1..=7 => Ok(value as u64 & ((1u64 << (info.size * 8)) - 1)),
+        _ => Ok(value as u64),
+    }
+}
+
+impl EvalContext for Process {
+    fn register_value(&self, name: &str) -> Result<u64> {
+        let Some(registers) = self.registers() else {
+            return Err(anyhow!(
+                "registers are not available, the process is not stopped"
+            ));
+        };
+        if name == "pc" {
+            return registers.get_pc().map(|pc| pc.addr());
+        }
+        register_u64(registers, name)
+    }
+
+    fn symbol_address(&self, name: &str) -> Result<u64> {
+        let objects = self
+            .objects
+            .as_ref()
+            .ok_or_else(|| anyhow!("no symbol table loaded"))?;
+        objects
+            .symbol_address(name)
+            .map(|address| address.addr())
+            .ok_or_else(|| anyhow!("no symbol named `{name}`"))
+    }
+
+    fn read_memory(&self, address: u64, size: usize) -> Result<u64> {
+        let data = Process::read_memory(self, VirtualAddress::from(address), size)?;
+        let mut bytes = [0u8; 8];
+        bytes[..data.len()].copy_from_slice(&data);
+        Ok(u64::from_le_bytes(bytes))
+    }
 }
 
 /// Fork an inferior process and start the executable in the child.
@@ -354,6 +2282,9 @@ fn launch_executable(
         }),
         None,
     )?;
+    // keep the slave from leaking into any other process we spawn; otherwise the
+    // master never sees the hang-up when this inferior exits.
+    fcntl(&pty.slave, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC))?;
     match unsafe { fork()? } {
         ForkResult::Parent { child } => {
             // Parent keeps master; close slave
@@ -367,10 +2298,15 @@ fn launch_executable(
 
             Ok(Some(Inferior {
                 pid: child,
-                _master_fd: pty.master.as_raw_fd(),
-                reader_fd: rfd.try_clone()?,
-                _writer: writer,
+                threads: vec![child],
+                launch_mode: LaunchMode::Spawned,
+                pty: Some(InferiorPty {
+                    _master_fd: pty.master.as_raw_fd(),
+                    reader_fd: rfd.try_clone()?,
+                    _writer: writer,
+                }),
                 breakpoint_sites: Default::default(),
+                hardware_slots: Default::default(),
             }))
         }
         ForkResult::Child => {
@@ -1,12 +1,14 @@
-//! Centralized declaration of all supported CPU registers for riscv64.
+//! Centralized declaration of all supported CPU registers for aarch64.
 
-use crate::process::register_info::{RegisterFormat, RegisterInfo, RegisterType, RegisterWidth};
+#[cfg(target_arch = "aarch64")]
+use crate::process::register_info::RegisterInfo;
+use crate::process::register_info::{RegisterFormat, RegisterType, RegisterWidth};
 
 /// All registers supported by the debugger for aarch64.
 ///
 /// The 32-bit general purpose registers are just the lower 32 bits
 /// of that same 64-bit hardware register. The 64-bit registers are prefixed
-/// with 'V', and the 32-bit registers with 'W'.
+/// with 'X', and the 32-bit registers with 'W'.
 ///
 /// The NEON SIMD registers are used for SIMD as well as floating point operations.
 /// They are 128-bit wide in hardware, but we reference smaller sizes (lower n-bits)
@@ -313,8 +315,72 @@ pub enum Register {
     B31,
 }
 
-/// Declarative metadata describing how to locate and format a register.
+/// Where a register is stored in the aarch64 register sets.
 #[derive(Copy, Clone, Debug)]
+pub enum Location {
+    /// `regs[n]` in `libc::user_regs_struct`, i.e. x0 - x30.
+    Regs(usize),
+    Sp,
+    Pc,
+    Pstate,
+    /// `vregs[n]` in `libc::user_fpsimd_struct`.
+    Vregs(usize),
+    Fpsr,
+    Fpcr,
+}
+
+impl Location {
+    /// Returns the byte offset of the register within its register set: the
+    /// general purpose registers are in `libc::user_regs_struct`, and the
+    /// SIMD/floating point registers in `libc::user_fpsimd_struct`.
+    ///
+    /// Unlike x86_64 there's no `user` struct to index into, as arm64 doesn't
+    /// support `PTRACE_PEEKUSER`/`PTRACE_POKEUSER`.
+    ///
+    /// The offsets are spelled out, rather than taken from the libc structs,
+    /// so the table can be checked on other hosts too.
+    const fn offset(self, width: RegisterWidth) -> usize {
+        let base = match self {
+            Location::Regs(index) => index * 8,
+            Location::Sp => 31 * 8,
+            Location::Pc => 32 * 8,
+            Location::Pstate => 33 * 8,
+            Location::Vregs(index) => index * 16,
+            Location::Fpsr => 32 * 16,
+            Location::Fpcr => 32 * 16 + 4,
+        };
+
+        // the narrower views (w0, d0, s0, ...) are the low bytes of the register
+        base + width.sub_offset()
+    }
+}
+
+// the spelled out offsets match the register sets we're built against
+#[cfg(target_arch = "aarch64")]
+const _: () = {
+    use memoffset::offset_of;
+    assert!(
+        offset_of!(libc::user_regs_struct, regs) == Location::Regs(0).offset(RegisterWidth::W64)
+    );
+    assert!(offset_of!(libc::user_regs_struct, sp) == Location::Sp.offset(RegisterWidth::W64));
+    assert!(offset_of!(libc::user_regs_struct, pc) == Location::Pc.offset(RegisterWidth::W64));
+    assert!(
+        offset_of!(libc::user_regs_struct, pstate) == Location::Pstate.offset(RegisterWidth::W64)
+    );
+    assert!(
+        offset_of!(libc::user_fpsimd_struct, vregs)
+            == Location::Vregs(0).offset(RegisterWidth::W128)
+    );
+    assert!(
+        offset_of!(libc::user_fpsimd_struct, fpsr) == Location::Fpsr.offset(RegisterWidth::W32)
+    );
+    assert!(
+        offset_of!(libc::user_fpsimd_struct, fpcr) == Location::Fpcr.offset(RegisterWidth::W32)
+    );
+};
+
+/// Declarative metadata describing how to locate and format a register.
+#[derive(Clone, Debug)]
 struct RegisterDecl {
     pub register: Register,
     pub name: &'static str,
@@ -322,29 +388,18 @@ struct RegisterDecl {
     pub width: RegisterWidth,
     pub reg_type: RegisterType,
     pub format: RegisterFormat,
+    pub loc: Location,
 }
 
-impl RegisterDecl {
-    /// Derive the struct offset for a given register.
-    ///
-    /// slightly janky, assumes all decl widths are the same (which they are
-    /// for general purpose and floating point regs), and that the DWARF ID
-    /// is an incremental value from 0-31 within the target struct (which is also
-    /// true for the _currently supported_ riscv structs/registers)
-    fn offset(&self) -> usize {
-        (self.dwarf % 32) as usize * self.width.bytes()
-    }
-}
-
+#[cfg(target_arch = "aarch64")]
 impl From<&RegisterDecl> for RegisterInfo {
     fn from(decl: &RegisterDecl) -> Self {
         Self {
             register: decl.register,
             name: decl.name,
             dwarf_id: decl.dwarf,
-            offset: decl.offset(),
+            offset: decl.loc.offset(decl.width),
             size: decl.width.bytes(),
-            width: decl.width,
             register_type: decl.reg_type,
             format: decl.format,
         }
@@ -360,9 +415,2361 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(0),
     },
-];
-
-pub fn registers_info_iter() -> impl Iterator<Item = RegisterInfo> {
-    REGISTER_DECLS.iter().map(RegisterInfo::from)
+    RegisterDecl {
+        register: Register::X1,
+        name: "x1",
+        dwarf: 1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(1),
+    },
+    RegisterDecl {
+        register: Register::X2,
+        name: "x2",
+        dwarf: 2,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(2),
+    },
+    RegisterDecl {
+        register: Register::X3,
+        name: "x3",
+        dwarf: 3,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(3),
+    },
+    RegisterDecl {
+        register: Register::X4,
+        name: "x4",
+        dwarf: 4,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(4),
+    },
+    RegisterDecl {
+        register: Register::X5,
+        name: "x5",
+        dwarf: 5,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(5),
+    },
+    RegisterDecl {
+        register: Register::X6,
+        name: "x6",
+        dwarf: 6,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(6),
+    },
+    RegisterDecl {
+        register: Register::X7,
+        name: "x7",
+        dwarf: 7,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(7),
+    },
+    RegisterDecl {
+        register: Register::X8,
+        name: "x8",
+        dwarf: 8,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(8),
+    },
+    RegisterDecl {
+        register: Register::X9,
+        name: "x9",
+        dwarf: 9,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(9),
+    },
+    RegisterDecl {
+        register: Register::X10,
+        name: "x10",
+        dwarf: 10,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(10),
+    },
+    RegisterDecl {
+        register: Register::X11,
+        name: "x11",
+        dwarf: 11,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(11),
+    },
+    RegisterDecl {
+        register: Register::X12,
+        name: "x12",
+        dwarf: 12,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(12),
+    },
+    RegisterDecl {
+        register: Register::X13,
+        name: "x13",
+        dwarf: 13,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(13),
+    },
+    RegisterDecl {
+        register: Register::X14,
+        name: "x14",
+        dwarf: 14,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(14),
+    },
+    RegisterDecl {
+        register: Register::X15,
+        name: "x15",
+        dwarf: 15,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(15),
+    },
+    RegisterDecl {
+        register: Register::X16,
+        name: "x16",
+        dwarf: 16,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(16),
+    },
+    RegisterDecl {
+        register: Register::X17,
+        name: "x17",
+        dwarf: 17,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(17),
+    },
+    RegisterDecl {
+        register: Register::X18,
+        name: "x18",
+        dwarf: 18,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(18),
+    },
+    RegisterDecl {
+        register: Register::X19,
+        name: "x19",
+        dwarf: 19,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(19),
+    },
+    RegisterDecl {
+        register: Register::X20,
+        name: "x20",
+        dwarf: 20,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(20),
+    },
+    RegisterDecl {
+        register: Register::X21,
+        name: "x21",
+        dwarf: 21,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(21),
+    },
+    RegisterDecl {
+        register: Register::X22,
+        name: "x22",
+        dwarf: 22,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(22),
+    },
+    RegisterDecl {
+        register: Register::X23,
+        name: "x23",
+        dwarf: 23,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(23),
+    },
+    RegisterDecl {
+        register: Register::X24,
+        name: "x24",
+        dwarf: 24,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(24),
+    },
+    RegisterDecl {
+        register: Register::X25,
+        name: "x25",
+        dwarf: 25,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(25),
+    },
+    RegisterDecl {
+        register: Register::X26,
+        name: "x26",
+        dwarf: 26,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(26),
+    },
+    RegisterDecl {
+        register: Register::X27,
+        name: "x27",
+        dwarf: 27,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(27),
+    },
+    RegisterDecl {
+        register: Register::X28,
+        name: "x28",
+        dwarf: 28,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(28),
+    },
+    RegisterDecl {
+        register: Register::X29,
+        name: "x29",
+        dwarf: 29,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(29),
+    },
+    RegisterDecl {
+        register: Register::X30,
+        name: "x30",
+        dwarf: 30,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(30),
+    },
+    RegisterDecl {
+        register: Register::SP,
+        name: "sp",
+        dwarf: 31,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Sp,
+    },
+    RegisterDecl {
+        register: Register::PC,
+        name: "pc",
+        dwarf: 32,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Pc,
+    },
+    RegisterDecl {
+        register: Register::PSTATE,
+        name: "pstate",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Pstate,
+    },
+    // 32-bit registers
+    RegisterDecl {
+        register: Register::W0,
+        name: "w0",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(0),
+    },
+    RegisterDecl {
+        register: Register::W1,
+        name: "w1",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(1),
+    },
+    RegisterDecl {
+        register: Register::W2,
+        name: "w2",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(2),
+    },
+    RegisterDecl {
+        register: Register::W3,
+        name: "w3",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(3),
+    },
+    RegisterDecl {
+        register: Register::W4,
+        name: "w4",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(4),
+    },
+    RegisterDecl {
+        register: Register::W5,
+        name: "w5",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(5),
+    },
+    RegisterDecl {
+        register: Register::W6,
+        name: "w6",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(6),
+    },
+    RegisterDecl {
+        register: Register::W7,
+        name: "w7",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(7),
+    },
+    RegisterDecl {
+        register: Register::W8,
+        name: "w8",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(8),
+    },
+    RegisterDecl {
+        register: Register::W9,
+        name: "w9",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(9),
+    },
+    RegisterDecl {
+        register: Register::W10,
+        name: "w10",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(10),
+    },
+    RegisterDecl {
+        register: Register::W11,
+        name: "w11",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(11),
+    },
+    RegisterDecl {
+        register: Register::W12,
+        name: "w12",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(12),
+    },
+    RegisterDecl {
+        register: Register::W13,
+        name: "w13",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(13),
+    },
+    RegisterDecl {
+        register: Register::W14,
+        name: "w14",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(14),
+    },
+    RegisterDecl {
+        register: Register::W15,
+        name: "w15",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(15),
+    },
+    RegisterDecl {
+        register: Register::W16,
+        name: "w16",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(16),
+    },
+    RegisterDecl {
+        register: Register::W17,
+        name: "w17",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(17),
+    },
+    RegisterDecl {
+        register: Register::W18,
+        name: "w18",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(18),
+    },
+    RegisterDecl {
+        register: Register::W19,
+        name: "w19",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(19),
+    },
+    RegisterDecl {
+        register: Register::W20,
+        name: "w20",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(20),
+    },
+    RegisterDecl {
+        register: Register::W21,
+        name: "w21",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(21),
+    },
+    RegisterDecl {
+        register: Register::W22,
+        name: "w22",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(22),
+    },
+    RegisterDecl {
+        register: Register::W23,
+        name: "w23",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(23),
+    },
+    RegisterDecl {
+        register: Register::W24,
+        name: "w24",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(24),
+    },
+    RegisterDecl {
+        register: Register::W25,
+        name: "w25",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(25),
+    },
+    RegisterDecl {
+        register: Register::W26,
+        name: "w26",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(26),
+    },
+    RegisterDecl {
+        register: Register::W27,
+        name: "w27",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(27),
+    },
+    RegisterDecl {
+        register: Register::W28,
+        name: "w28",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(28),
+    },
+    RegisterDecl {
+        register: Register::W29,
+        name: "w29",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(29),
+    },
+    RegisterDecl {
+        register: Register::W30,
+        name: "w30",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Regs(30),
+    },
+    RegisterDecl {
+        register: Register::CSPR,
+        name: "cpsr",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::SubGeneralPurpose,
+        format: RegisterFormat::Uint32,
+        loc: Location::Pstate,
+    },
+    // NOTE: tpidr/tpidr2 live in their own register set (NT_ARM_TLS), which
+    // we don't read yet.
+    // floating point status and control
+    RegisterDecl {
+        register: Register::FPSR,
+        name: "fpsr",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint32,
+        loc: Location::Fpsr,
+    },
+    RegisterDecl {
+        register: Register::FPCR,
+        name: "fpcr",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint32,
+        loc: Location::Fpcr,
+    },
+    // NEON (SIMD) registers. 128-bit
+    RegisterDecl {
+        register: Register::V0,
+        name: "v0",
+        dwarf: 64,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(0),
+    },
+    RegisterDecl {
+        register: Register::V1,
+        name: "v1",
+        dwarf: 65,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(1),
+    },
+    RegisterDecl {
+        register: Register::V2,
+        name: "v2",
+        dwarf: 66,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(2),
+    },
+    RegisterDecl {
+        register: Register::V3,
+        name: "v3",
+        dwarf: 67,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(3),
+    },
+    RegisterDecl {
+        register: Register::V4,
+        name: "v4",
+        dwarf: 68,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(4),
+    },
+    RegisterDecl {
+        register: Register::V5,
+        name: "v5",
+        dwarf: 69,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(5),
+    },
+    RegisterDecl {
+        register: Register::V6,
+        name: "v6",
+        dwarf: 70,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(6),
+    },
+    RegisterDecl {
+        register: Register::V7,
+        name: "v7",
+        dwarf: 71,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(7),
+    },
+    RegisterDecl {
+        register: Register::V8,
+        name: "v8",
+        dwarf: 72,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(8),
+    },
+    RegisterDecl {
+        register: Register::V9,
+        name: "v9",
+        dwarf: 73,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(9),
+    },
+    RegisterDecl {
+        register: Register::V10,
+        name: "v10",
+        dwarf: 74,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(10),
+    },
+    RegisterDecl {
+        register: Register::V11,
+        name: "v11",
+        dwarf: 75,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(11),
+    },
+    RegisterDecl {
+        register: Register::V12,
+        name: "v12",
+        dwarf: 76,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(12),
+    },
+    RegisterDecl {
+        register: Register::V13,
+        name: "v13",
+        dwarf: 77,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(13),
+    },
+    RegisterDecl {
+        register: Register::V14,
+        name: "v14",
+        dwarf: 78,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(14),
+    },
+    RegisterDecl {
+        register: Register::V15,
+        name: "v15",
+        dwarf: 79,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(15),
+    },
+    RegisterDecl {
+        register: Register::V16,
+        name: "v16",
+        dwarf: 80,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(16),
+    },
+    RegisterDecl {
+        register: Register::V17,
+        name: "v17",
+        dwarf: 81,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(17),
+    },
+    RegisterDecl {
+        register: Register::V18,
+        name: "v18",
+        dwarf: 82,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(18),
+    },
+    RegisterDecl {
+        register: Register::V19,
+        name: "v19",
+        dwarf: 83,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(19),
+    },
+    RegisterDecl {
+        register: Register::V20,
+        name: "v20",
+        dwarf: 84,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(20),
+    },
+    RegisterDecl {
+        register: Register::V21,
+        name: "v21",
+        dwarf: 85,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(21),
+    },
+    RegisterDecl {
+        register: Register::V22,
+        name: "v22",
+        dwarf: 86,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(22),
+    },
+    RegisterDecl {
+        register: Register::V23,
+        name: "v23",
+        dwarf: 87,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(23),
+    },
+    RegisterDecl {
+        register: Register::V24,
+        name: "v24",
+        dwarf: 88,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(24),
+    },
+    RegisterDecl {
+        register: Register::V25,
+        name: "v25",
+        dwarf: 89,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(25),
+    },
+    RegisterDecl {
+        register: Register::V26,
+        name: "v26",
+        dwarf: 90,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(26),
+    },
+    RegisterDecl {
+        register: Register::V27,
+        name: "v27",
+        dwarf: 91,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(27),
+    },
+    RegisterDecl {
+        register: Register::V28,
+        name: "v28",
+        dwarf: 92,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(28),
+    },
+    RegisterDecl {
+        register: Register::V29,
+        name: "v29",
+        dwarf: 93,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(29),
+    },
+    RegisterDecl {
+        register: Register::V30,
+        name: "v30",
+        dwarf: 94,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(30),
+    },
+    RegisterDecl {
+        register: Register::V31,
+        name: "v31",
+        dwarf: 95,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(31),
+    },
+    // NEON (SIMD) registers. 128-bit ('quad-word' alias)
+    RegisterDecl {
+        register: Register::Q0,
+        name: "q0",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(0),
+    },
+    RegisterDecl {
+        register: Register::Q1,
+        name: "q1",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(1),
+    },
+    RegisterDecl {
+        register: Register::Q2,
+        name: "q2",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(2),
+    },
+    RegisterDecl {
+        register: Register::Q3,
+        name: "q3",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(3),
+    },
+    RegisterDecl {
+        register: Register::Q4,
+        name: "q4",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(4),
+    },
+    RegisterDecl {
+        register: Register::Q5,
+        name: "q5",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(5),
+    },
+    RegisterDecl {
+        register: Register::Q6,
+        name: "q6",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(6),
+    },
+    RegisterDecl {
+        register: Register::Q7,
+        name: "q7",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(7),
+    },
+    RegisterDecl {
+        register: Register::Q8,
+        name: "q8",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(8),
+    },
+    RegisterDecl {
+        register: Register::Q9,
+        name: "q9",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(9),
+    },
+    RegisterDecl {
+        register: Register::Q10,
+        name: "q10",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(10),
+    },
+    RegisterDecl {
+        register: Register::Q11,
+        name: "q11",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(11),
+    },
+    RegisterDecl {
+        register: Register::Q12,
+        name: "q12",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(12),
+    },
+    RegisterDecl {
+        register: Register::Q13,
+        name: "q13",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(13),
+    },
+    RegisterDecl {
+        register: Register::Q14,
+        name: "q14",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(14),
+    },
+    RegisterDecl {
+        register: Register::Q15,
+        name: "q15",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(15),
+    },
+    RegisterDecl {
+        register: Register::Q16,
+        name: "q16",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(16),
+    },
+    RegisterDecl {
+        register: Register::Q17,
+        name: "q17",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(17),
+    },
+    RegisterDecl {
+        register: Register::Q18,
+        name: "q18",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(18),
+    },
+    RegisterDecl {
+        register: Register::Q19,
+        name: "q19",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(19),
+    },
+    RegisterDecl {
+        register: Register::Q20,
+        name: "q20",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(20),
+    },
+    RegisterDecl {
+        register: Register::Q21,
+        name: "q21",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(21),
+    },
+    RegisterDecl {
+        register: Register::Q22,
+        name: "q22",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(22),
+    },
+    RegisterDecl {
+        register: Register::Q23,
+        name: "q23",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(23),
+    },
+    RegisterDecl {
+        register: Register::Q24,
+        name: "q24",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(24),
+    },
+    RegisterDecl {
+        register: Register::Q25,
+        name: "q25",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(25),
+    },
+    RegisterDecl {
+        register: Register::Q26,
+        name: "q26",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(26),
+    },
+    RegisterDecl {
+        register: Register::Q27,
+        name: "q27",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(27),
+    },
+    RegisterDecl {
+        register: Register::Q28,
+        name: "q28",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(28),
+    },
+    RegisterDecl {
+        register: Register::Q29,
+        name: "q29",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(29),
+    },
+    RegisterDecl {
+        register: Register::Q30,
+        name: "q30",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(30),
+    },
+    RegisterDecl {
+        register: Register::Q31,
+        name: "q31",
+        dwarf: -1,
+        width: RegisterWidth::W128,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Byte128,
+        loc: Location::Vregs(31),
+    },
+    // NEON (SIMD) registers. 64-bit
+    RegisterDecl {
+        register: Register::D0,
+        name: "d0",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(0),
+    },
+    RegisterDecl {
+        register: Register::D1,
+        name: "d1",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(1),
+    },
+    RegisterDecl {
+        register: Register::D2,
+        name: "d2",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(2),
+    },
+    RegisterDecl {
+        register: Register::D3,
+        name: "d3",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(3),
+    },
+    RegisterDecl {
+        register: Register::D4,
+        name: "d4",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(4),
+    },
+    RegisterDecl {
+        register: Register::D5,
+        name: "d5",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(5),
+    },
+    RegisterDecl {
+        register: Register::D6,
+        name: "d6",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(6),
+    },
+    RegisterDecl {
+        register: Register::D7,
+        name: "d7",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(7),
+    },
+    RegisterDecl {
+        register: Register::D8,
+        name: "d8",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(8),
+    },
+    RegisterDecl {
+        register: Register::D9,
+        name: "d9",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(9),
+    },
+    RegisterDecl {
+        register: Register::D10,
+        name: "d10",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(10),
+    },
+    RegisterDecl {
+        register: Register::D11,
+        name: "d11",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(11),
+    },
+    RegisterDecl {
+        register: Register::D12,
+        name: "d12",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(12),
+    },
+    RegisterDecl {
+        register: Register::D13,
+        name: "d13",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(13),
+    },
+    RegisterDecl {
+        register: Register::D14,
+        name: "d14",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(14),
+    },
+    RegisterDecl {
+        register: Register::D15,
+        name: "d15",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(15),
+    },
+    RegisterDecl {
+        register: Register::D16,
+        name: "d16",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(16),
+    },
+    RegisterDecl {
+        register: Register::D17,
+        name: "d17",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(17),
+    },
+    RegisterDecl {
+        register: Register::D18,
+        name: "d18",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(18),
+    },
+    RegisterDecl {
+        register: Register::D19,
+        name: "d19",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(19),
+    },
+    RegisterDecl {
+        register: Register::D20,
+        name: "d20",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(20),
+    },
+    RegisterDecl {
+        register: Register::D21,
+        name: "d21",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(21),
+    },
+    RegisterDecl {
+        register: Register::D22,
+        name: "d22",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(22),
+    },
+    RegisterDecl {
+        register: Register::D23,
+        name: "d23",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(23),
+    },
+    RegisterDecl {
+        register: Register::D24,
+        name: "d24",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(24),
+    },
+    RegisterDecl {
+        register: Register::D25,
+        name: "d25",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(25),
+    },
+    RegisterDecl {
+        register: Register::D26,
+        name: "d26",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(26),
+    },
+    RegisterDecl {
+        register: Register::D27,
+        name: "d27",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(27),
+    },
+    RegisterDecl {
+        register: Register::D28,
+        name: "d28",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(28),
+    },
+    RegisterDecl {
+        register: Register::D29,
+        name: "d29",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(29),
+    },
+    RegisterDecl {
+        register: Register::D30,
+        name: "d30",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(30),
+    },
+    RegisterDecl {
+        register: Register::D31,
+        name: "d31",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Double,
+        loc: Location::Vregs(31),
+    },
+    // NEON (SIMD) registers. 32-bit
+    RegisterDecl {
+        register: Register::S0,
+        name: "s0",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(0),
+    },
+    RegisterDecl {
+        register: Register::S1,
+        name: "s1",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(1),
+    },
+    RegisterDecl {
+        register: Register::S2,
+        name: "s2",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(2),
+    },
+    RegisterDecl {
+        register: Register::S3,
+        name: "s3",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(3),
+    },
+    RegisterDecl {
+        register: Register::S4,
+        name: "s4",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(4),
+    },
+    RegisterDecl {
+        register: Register::S5,
+        name: "s5",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(5),
+    },
+    RegisterDecl {
+        register: Register::S6,
+        name: "s6",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(6),
+    },
+    RegisterDecl {
+        register: Register::S7,
+        name: "s7",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(7),
+    },
+    RegisterDecl {
+        register: Register::S8,
+        name: "s8",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(8),
+    },
+    RegisterDecl {
+        register: Register::S9,
+        name: "s9",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(9),
+    },
+    RegisterDecl {
+        register: Register::S10,
+        name: "s10",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(10),
+    },
+    RegisterDecl {
+        register: Register::S11,
+        name: "s11",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(11),
+    },
+    RegisterDecl {
+        register: Register::S12,
+        name: "s12",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(12),
+    },
+    RegisterDecl {
+        register: Register::S13,
+        name: "s13",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(13),
+    },
+    RegisterDecl {
+        register: Register::S14,
+        name: "s14",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(14),
+    },
+    RegisterDecl {
+        register: Register::S15,
+        name: "s15",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(15),
+    },
+    RegisterDecl {
+        register: Register::S16,
+        name: "s16",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(16),
+    },
+    RegisterDecl {
+        register: Register::S17,
+        name: "s17",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(17),
+    },
+    RegisterDecl {
+        register: Register::S18,
+        name: "s18",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(18),
+    },
+    RegisterDecl {
+        register: Register::S19,
+        name: "s19",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(19),
+    },
+    RegisterDecl {
+        register: Register::S20,
+        name: "s20",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(20),
+    },
+    RegisterDecl {
+        register: Register::S21,
+        name: "s21",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(21),
+    },
+    RegisterDecl {
+        register: Register::S22,
+        name: "s22",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(22),
+    },
+    RegisterDecl {
+        register: Register::S23,
+        name: "s23",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(23),
+    },
+    RegisterDecl {
+        register: Register::S24,
+        name: "s24",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(24),
+    },
+    RegisterDecl {
+        register: Register::S25,
+        name: "s25",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(25),
+    },
+    RegisterDecl {
+        register: Register::S26,
+        name: "s26",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(26),
+    },
+    RegisterDecl {
+        register: Register::S27,
+        name: "s27",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(27),
+    },
+    RegisterDecl {
+        register: Register::S28,
+        name: "s28",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(28),
+    },
+    RegisterDecl {
+        register: Register::S29,
+        name: "s29",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(29),
+    },
+    RegisterDecl {
+        register: Register::S30,
+        name: "s30",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(30),
+    },
+    RegisterDecl {
+        register: Register::S31,
+        name: "s31",
+        dwarf: -1,
+        width: RegisterWidth::W32,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Float,
+        loc: Location::Vregs(31),
+    },
+    // NEON (SIMD) registers. 16-bit
+    RegisterDecl {
+        register: Register::H0,
+        name: "h0",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(0),
+    },
+    RegisterDecl {
+        register: Register::H1,
+        name: "h1",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(1),
+    },
+    RegisterDecl {
+        register: Register::H2,
+        name: "h2",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(2),
+    },
+    RegisterDecl {
+        register: Register::H3,
+        name: "h3",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(3),
+    },
+    RegisterDecl {
+        register: Register::H4,
+        name: "h4",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(4),
+    },
+    RegisterDecl {
+        register: Register::H5,
+        name: "h5",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(5),
+    },
+    RegisterDecl {
+        register: Register::H6,
+        name: "h6",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(6),
+    },
+    RegisterDecl {
+        register: Register::H7,
+        name: "h7",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(7),
+    },
+    RegisterDecl {
+        register: Register::H8,
+        name: "h8",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(8),
+    },
+    RegisterDecl {
+        register: Register::H9,
+        name: "h9",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(9),
+    },
+    RegisterDecl {
+        register: Register::H10,
+        name: "h10",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(10),
+    },
+    RegisterDecl {
+        register: Register::H11,
+        name: "h11",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(11),
+    },
+    RegisterDecl {
+        register: Register::H12,
+        name: "h12",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(12),
+    },
+    RegisterDecl {
+        register: Register::H13,
+        name: "h13",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(13),
+    },
+    RegisterDecl {
+        register: Register::H14,
+        name: "h14",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(14),
+    },
+    RegisterDecl {
+        register: Register::H15,
+        name: "h15",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(15),
+    },
+    RegisterDecl {
+        register: Register::H16,
+        name: "h16",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(16),
+    },
+    RegisterDecl {
+        register: Register::H17,
+        name: "h17",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(17),
+    },
+    RegisterDecl {
+        register: Register::H18,
+        name: "h18",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(18),
+    },
+    RegisterDecl {
+        register: Register::H19,
+        name: "h19",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(19),
+    },
+    RegisterDecl {
+        register: Register::H20,
+        name: "h20",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(20),
+    },
+    RegisterDecl {
+        register: Register::H21,
+        name: "h21",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(21),
+    },
+    RegisterDecl {
+        register: Register::H22,
+        name: "h22",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(22),
+    },
+    RegisterDecl {
+        register: Register::H23,
+        name: "h23",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(23),
+    },
+    RegisterDecl {
+        register: Register::H24,
+        name: "h24",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(24),
+    },
+    RegisterDecl {
+        register: Register::H25,
+        name: "h25",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(25),
+    },
+    RegisterDecl {
+        register: Register::H26,
+        name: "h26",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(26),
+    },
+    RegisterDecl {
+        register: Register::H27,
+        name: "h27",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(27),
+    },
+    RegisterDecl {
+        register: Register::H28,
+        name: "h28",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(28),
+    },
+    RegisterDecl {
+        register: Register::H29,
+        name: "h29",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(29),
+    },
+    RegisterDecl {
+        register: Register::H30,
+        name: "h30",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(30),
+    },
+    RegisterDecl {
+        register: Register::H31,
+        name: "h31",
+        dwarf: -1,
+        width: RegisterWidth::W16,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint16,
+        loc: Location::Vregs(31),
+    },
+    // NEON (SIMD) registers. 8-bit
+    RegisterDecl {
+        register: Register::B0,
+        name: "b0",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(0),
+    },
+    RegisterDecl {
+        register: Register::B1,
+        name: "b1",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(1),
+    },
+    RegisterDecl {
+        register: Register::B2,
+        name: "b2",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(2),
+    },
+    RegisterDecl {
+        register: Register::B3,
+        name: "b3",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(3),
+    },
+    RegisterDecl {
+        register: Register::B4,
+        name: "b4",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(4),
+    },
+    RegisterDecl {
+        register: Register::B5,
+        name: "b5",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(5),
+    },
+    RegisterDecl {
+        register: Register::B6,
+        name: "b6",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(6),
+    },
+    RegisterDecl {
+        register: Register::B7,
+        name: "b7",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(7),
+    },
+    RegisterDecl {
+        register: Register::B8,
+        name: "b8",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(8),
+    },
+    RegisterDecl {
+        register: Register::B9,
+        name: "b9",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(9),
+    },
+    RegisterDecl {
+        register: Register::B10,
+        name: "b10",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(10),
+    },
+    RegisterDecl {
+        register: Register::B11,
+        name: "b11",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(11),
+    },
+    RegisterDecl {
+        register: Register::B12,
+        name: "b12",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(12),
+    },
+    RegisterDecl {
+        register: Register::B13,
+        name: "b13",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(13),
+    },
+    RegisterDecl {
+        register: Register::B14,
+        name: "b14",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(14),
+    },
+    RegisterDecl {
+        register: Register::B15,
+        name: "b15",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(15),
+    },
+    RegisterDecl {
+        register: Register::B16,
+        name: "b16",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(16),
+    },
+    RegisterDecl {
+        register: Register::B17,
+        name: "b17",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(17),
+    },
+    RegisterDecl {
+        register: Register::B18,
+        name: "b18",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(18),
+    },
+    RegisterDecl {
+        register: Register::B19,
+        name: "b19",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(19),
+    },
+    RegisterDecl {
+        register: Register::B20,
+        name: "b20",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(20),
+    },
+    RegisterDecl {
+        register: Register::B21,
+        name: "b21",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(21),
+    },
+    RegisterDecl {
+        register: Register::B22,
+        name: "b22",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(22),
+    },
+    RegisterDecl {
+        register: Register::B23,
+        name: "b23",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(23),
+    },
+    RegisterDecl {
+        register: Register::B24,
+        name: "b24",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(24),
+    },
+    RegisterDecl {
+        register: Register::B25,
+        name: "b25",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(25),
+    },
+    RegisterDecl {
+        register: Register::B26,
+        name: "b26",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(26),
+    },
+    RegisterDecl {
+        register: Register::B27,
+        name: "b27",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(27),
+    },
+    RegisterDecl {
+        register: Register::B28,
+        name: "b28",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(28),
+    },
+    RegisterDecl {
+        register: Register::B29,
+        name: "b29",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(29),
+    },
+    RegisterDecl {
+        register: Register::B30,
+        name: "b30",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(30),
+    },
+    RegisterDecl {
+        register: Register::B31,
+        name: "b31",
+        dwarf: -1,
+        width: RegisterWidth::W8L,
+        reg_type: RegisterType::FloatingPoint,
+        format: RegisterFormat::Uint8,
+        loc: Location::Vregs(31),
+    },
+];
+
+#[cfg(target_arch = "aarch64")]
+pub fn registers_info_iter() -> impl Iterator<Item = RegisterInfo> {
+    REGISTER_DECLS.iter().map(RegisterInfo::from)
+}
+
+/// The name, offset and size of each register in the table, which (unlike
+/// `registers_info_iter`) is available on any host, for testing.
+#[cfg(test)]
+pub(crate) fn register_offsets() -> impl Iterator<Item = (&'static str, usize, usize)> {
+    REGISTER_DECLS
+        .iter()
+        .map(|decl| (decl.name, decl.loc.offset(decl.width), decl.width.bytes()))
 }
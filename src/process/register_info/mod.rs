@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 use anyhow::{Result, anyhow};
+use std::fmt;
 use strum::EnumDiscriminants;
 
 #[cfg(target_arch = "x86_64")]
@@ -7,13 +8,14 @@ mod x86_64;
 #[cfg(target_arch = "x86_64")]
 pub use x86_64::*;
 
-#[cfg(target_arch = "aarch64")]
-mod aarch64;
+// the other tables are built for the tests too, to check their offsets
+#[cfg(any(target_arch = "aarch64", test))]
+pub(crate) mod aarch64;
 #[cfg(target_arch = "aarch64")]
 pub use aarch64::*;
 
-#[cfg(target_arch = "riscv64")]
-mod riscv64;
+#[cfg(any(target_arch = "riscv64", test))]
+pub(crate) mod riscv64;
 #[cfg(target_arch = "riscv64")]
 pub use riscv64::*;
 
@@ -28,8 +30,10 @@ pub struct RegisterInfo {
     // TODO: consider using Option<i32> and a helper function that returns
     // -1 when None.
     pub dwarf_id: i32,
-    /// The byte offset into the `user` struct of this register.
-    /// Primarily used for `read_user()` and `write_user()`.
+    /// The byte offset of this register. On x86_64 this is into the `user`
+    /// struct, primarily used for `read_user()` and `write_user()`. On aarch64
+    /// and riscv64, it's into the register set (general purpose or floating
+    /// point) the register belongs to.
     pub offset: usize,
     /// Size, in bytes, of the register's value.
     pub size: usize,
@@ -38,8 +42,13 @@ pub struct RegisterInfo {
     pub format: RegisterFormat,
 }
 
+/// Look up a register by its name, as it appears in the linux structs.
+pub fn register_info_by_name(name: &str) -> Option<RegisterInfo> {
+    registers_info_iter().find(|info| info.name == name)
+}
+
 /// Broad grouping for registers, used for display and filtering.
-#[derive(Clone, Copy, Debug, Hash)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum RegisterType {
     /// 64-bit instructions
     GeneralPurpose,
@@ -107,6 +116,99 @@ impl TryFrom<RegisterValue> for i64 {
     }
 }
 
+impl fmt::Display for RegisterValue {
+    /// Integers in hex, padded to the register's width; floating point values
+    /// in decimal; and vectors as their 32-bit lanes, lowest first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use RegisterValue::*;
+        match self {
+            Uint8(v) => write!(f, "{v:#04x}"),
+            Uint16(v) => write!(f, "{v:#06x}"),
+            Uint32(v) => write!(f, "{v:#010x}"),
+            Uint64(v) => write!(f, "{v:#018x}"),
+            Int8(v) => write!(f, "{:#04x}", *v as u8),
+            Int16(v) => write!(f, "{:#06x}", *v as u16),
+            Int32(v) => write!(f, "{:#010x}", *v as u32),
+            Int64(v) => write!(f, "{:#018x}", *v as u64),
+            Float(v) => write!(f, "{v}"),
+            Double(v) => write!(f, "{v}"),
+            LongDouble(bytes) => write!(f, "{}", long_double_to_f64(bytes)),
+            Byte64(bytes) => write_lanes(f, bytes),
+            Byte128(bytes) => write_lanes(f, bytes),
+        }
+    }
+}
+
+fn write_lanes(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    let lanes: Vec<String> = bytes
+        .chunks_exact(4)
+        .map(|lane| format!("{:#010x}", u32::from_le_bytes(lane.try_into().unwrap())))
+        .collect();
+    write!(f, "{{{}}}", lanes.join(" "))
+}
+
+// x87 extended precision: a 64-bit mantissa with an explicit integer bit,
+// then the sign and a 15-bit exponent.
+const LONG_DOUBLE_BIAS: i32 = 16383;
+const LONG_DOUBLE_MAX_EXPONENT: u16 = 0x7fff;
+
+/// Decode an 80-bit x87 long double. Precision beyond a `f64` is lost.
+pub fn long_double_to_f64(bytes: &[u8; 10]) -> f64 {
+    let mantissa = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+    let sign_exponent = u16::from_le_bytes([bytes[8], bytes[9]]);
+    let sign = if sign_exponent & 0x8000 != 0 {
+        -1.0
+    } else {
+        1.0
+    };
+    let exponent = sign_exponent & LONG_DOUBLE_MAX_EXPONENT;
+
+    let value = match exponent {
+        0 if mantissa == 0 => 0.0,
+        // the integer bit is ignored for infinities and NaNs
+        LONG_DOUBLE_MAX_EXPONENT if mantissa << 1 == 0 => f64::INFINITY,
+        LONG_DOUBLE_MAX_EXPONENT => f64::NAN,
+        // denormals use the smallest exponent
+        _ => {
+            let exponent = (exponent.max(1) as i32) - LONG_DOUBLE_BIAS;
+            // scale in two halves, as 2^exponent alone may not fit in a f64
+            // even when the result does (e.g. a f64 denormal)
+            let half = exponent / 2;
+            (mantissa as f64 / 2f64.powi(63)) * 2f64.powi(half) * 2f64.powi(exponent - half)
+        }
+    };
+    sign * value
+}
+
+/// Encode a value as an 80-bit x87 long double.
+pub fn f64_to_long_double(value: f64) -> [u8; 10] {
+    let bits = value.to_bits();
+    let sign = ((bits >> 63) as u16) << 15;
+    let exponent = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = bits & ((1 << 52) - 1);
+
+    let (exponent, mantissa) = match exponent {
+        0 if fraction == 0 => (0, 0),
+        0x7ff => (LONG_DOUBLE_MAX_EXPONENT, (1 << 63) | (fraction << 11)),
+        // a double denormal is a normal long double: shift the top bit up to
+        // the integer bit
+        0 => {
+            let shift = fraction.leading_zeros() as i32;
+            let exponent = -1011 - shift + LONG_DOUBLE_BIAS;
+            (exponent as u16, fraction << shift)
+        }
+        _ => (
+            (exponent - 1023 + LONG_DOUBLE_BIAS) as u16,
+            (1 << 63) | (fraction << 11),
+        ),
+    };
+
+    let mut bytes = [0; 10];
+    bytes[..8].copy_from_slice(&mantissa.to_le_bytes());
+    bytes[8..].copy_from_slice(&(sign | exponent).to_le_bytes());
+    bytes
+}
+
 /// Canonical width for a register or subregister.
 ///
 /// Note: variants are prefixed with 'W' as rust won't allow a digit as the first char.
@@ -153,3 +255,47 @@ impl RegisterWidth {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_double_conversion() {
+        let one = [0, 0, 0, 0, 0, 0, 0, 0x80, 0xff, 0x3f];
+        assert_eq!(long_double_to_f64(&one), 1.0);
+        assert_eq!(f64_to_long_double(1.0), one);
+
+        for value in [
+            0.0,
+            -2.5,
+            1e300,
+            -1e-300,
+            f64::MIN_POSITIVE / 8.0,
+            f64::INFINITY,
+        ] {
+            assert_eq!(long_double_to_f64(&f64_to_long_double(value)), value);
+        }
+        assert!(long_double_to_f64(&f64_to_long_double(f64::NAN)).is_nan());
+    }
+
+    #[test]
+    fn display_values() {
+        assert_eq!(
+            RegisterValue::Uint64(0x1234).to_string(),
+            "0x0000000000001234"
+        );
+        assert_eq!(RegisterValue::Uint8(0xa).to_string(), "0x0a");
+        assert_eq!(RegisterValue::Int32(-1).to_string(), "0xffffffff");
+        assert_eq!(RegisterValue::Double(1.5).to_string(), "1.5");
+        assert_eq!(
+            RegisterValue::LongDouble(f64_to_long_double(-0.25)).to_string(),
+            "-0.25"
+        );
+        let xmm = u128::from_le_bytes(*b"0123456789abcdef").to_le_bytes();
+        assert_eq!(
+            RegisterValue::Byte128(xmm).to_string(),
+            "{0x33323130 0x37363534 0x62613938 0x66656463}"
+        );
+    }
+}
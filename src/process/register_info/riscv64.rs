@@ -1,6 +1,8 @@
 //! Centralized declaration of all supported CPU registers for riscv64.
 
-use crate::process::register_info::{RegisterFormat, RegisterInfo, RegisterType, RegisterWidth};
+#[cfg(target_arch = "riscv64")]
+use crate::process::register_info::RegisterInfo;
+use crate::process::register_info::{RegisterFormat, RegisterType, RegisterWidth};
 
 /// Registers for risc-v 64.
 ///
@@ -44,6 +46,9 @@ pub enum Register {
     X30, // (T5) temporaries (caller-saved)
     X31, // (T6) temporaries (caller-saved)
 
+    // program counter
+    PC,
+
     // floating-point registers
     F0,  // (FT0) FP temporaries
     F1,  // (FT1) FP temporaries
@@ -79,6 +84,29 @@ pub enum Register {
     F31, // (FT11) FP temporaries
 }
 
+/// Where a register is stored in the riscv64 register sets.
+#[derive(Copy, Clone, Debug)]
+pub enum Location {
+    /// Slot `n` of `libc::user_regs_struct`, taken as an array of 64-bit
+    /// registers. Slot 0 holds the PC: there's no need to store x0.
+    Regs(usize),
+    /// `f[n]` in `libc::__riscv_mc_d_ext_state`.
+    Fp(usize),
+    /// x0, which always reads as zero and isn't stored anywhere.
+    Zero,
+}
+
+impl Location {
+    /// Returns the byte offset into the register set struct for this register.
+    const fn offset(self) -> usize {
+        match self {
+            Location::Regs(index) => index * 8,
+            Location::Fp(index) => index * 8,
+            Location::Zero => 0,
+        }
+    }
+}
+
 /// Declarative metadata describing how to locate and format a register.
 #[derive(Clone, Debug)]
 struct RegisterDecl {
@@ -88,20 +116,16 @@ struct RegisterDecl {
     pub width: RegisterWidth,
     pub reg_type: RegisterType,
     pub format: RegisterFormat,
+    pub loc: Location,
 }
 
 impl RegisterDecl {
-    /// Derive the struct offset for a given register.
-    ///
-    /// slightly janky, assumes all decl widths are the same (which they are
-    /// for general purpose and floating point regs), and that the DWARF ID
-    /// is an incremental value from 0-31 within the target struct (which is also
-    /// true for the _currently supported_ riscv structs/registers)
-    fn offset(&self) -> usize {
-        (self.dwarf % 32) as usize * self.width.bytes()
+    const fn offset(&self) -> usize {
+        self.loc.offset() + self.width.sub_offset()
     }
 }
 
+#[cfg(target_arch = "riscv64")]
 impl From<&RegisterDecl> for RegisterInfo {
     fn from(decl: &RegisterDecl) -> Self {
         Self {
@@ -110,7 +134,6 @@ impl From<&RegisterDecl> for RegisterInfo {
             dwarf_id: decl.dwarf,
             offset: decl.offset(),
             size: decl.width.bytes(),
-            width: decl.width,
             register_type: decl.reg_type,
             format: decl.format,
         }
@@ -126,6 +149,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Zero,
     },
     RegisterDecl {
         register: Register::X1,
@@ -134,6 +158,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(1),
     },
     RegisterDecl {
         register: Register::X2,
@@ -142,6 +167,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(2),
     },
     RegisterDecl {
         register: Register::X3,
@@ -150,6 +176,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(3),
     },
     RegisterDecl {
         register: Register::X4,
@@ -158,6 +185,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(4),
     },
     RegisterDecl {
         register: Register::X5,
@@ -166,6 +194,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(5),
     },
     RegisterDecl {
         register: Register::X6,
@@ -174,6 +203,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(6),
     },
     RegisterDecl {
         register: Register::X7,
@@ -182,6 +212,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(7),
     },
     RegisterDecl {
         register: Register::X8,
@@ -190,6 +221,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(8),
     },
     RegisterDecl {
         register: Register::X9,
@@ -198,6 +230,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(9),
     },
     RegisterDecl {
         register: Register::X10,
@@ -206,6 +239,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(10),
     },
     RegisterDecl {
         register: Register::X11,
@@ -214,6 +248,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(11),
     },
     RegisterDecl {
         register: Register::X12,
@@ -222,6 +257,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(12),
     },
     RegisterDecl {
         register: Register::X13,
@@ -230,6 +266,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(13),
     },
     RegisterDecl {
         register: Register::X14,
@@ -238,6 +275,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(14),
     },
     RegisterDecl {
         register: Register::X15,
@@ -246,6 +284,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(15),
     },
     RegisterDecl {
         register: Register::X16,
@@ -254,6 +293,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(16),
     },
     RegisterDecl {
         register: Register::X17,
@@ -262,6 +302,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(17),
     },
     RegisterDecl {
         register: Register::X18,
@@ -270,6 +311,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(18),
     },
     RegisterDecl {
         register: Register::X19,
@@ -278,6 +320,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(19),
     },
     RegisterDecl {
         register: Register::X20,
@@ -286,6 +329,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(20),
     },
     RegisterDecl {
         register: Register::X21,
@@ -294,6 +338,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(21),
     },
     RegisterDecl {
         register: Register::X22,
@@ -302,6 +347,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(22),
     },
     RegisterDecl {
         register: Register::X23,
@@ -310,6 +356,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(23),
     },
     RegisterDecl {
         register: Register::X24,
@@ -318,6 +365,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(24),
     },
     RegisterDecl {
         register: Register::X25,
@@ -326,6 +374,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(25),
     },
     RegisterDecl {
         register: Register::X26,
@@ -334,6 +383,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(26),
     },
     RegisterDecl {
         register: Register::X27,
@@ -342,6 +392,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(27),
     },
     RegisterDecl {
         register: Register::X28,
@@ -350,6 +401,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(28),
     },
     RegisterDecl {
         register: Register::X29,
@@ -358,6 +410,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(29),
     },
     RegisterDecl {
         register: Register::X30,
@@ -366,6 +419,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(30),
     },
     RegisterDecl {
         register: Register::X31,
@@ -374,6 +428,16 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::GeneralPurpose,
         format: RegisterFormat::Uint64,
+        loc: Location::Regs(31),
+    },
+    RegisterDecl {
+        register: Register::PC,
+        name: "pc",
+        dwarf: -1,
+        width: RegisterWidth::W64,
+        reg_type: RegisterType::GeneralPurpose,
+        format: RegisterFormat::Uint64,
+        loc: Location::Regs(0),
     },
     // floating-point registers
     RegisterDecl {
@@ -383,6 +447,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(0),
     },
     RegisterDecl {
         register: Register::F1,
@@ -391,6 +456,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(1),
     },
     RegisterDecl {
         register: Register::F2,
@@ -399,6 +465,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(2),
     },
     RegisterDecl {
         register: Register::F3,
@@ -407,6 +474,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(3),
     },
     RegisterDecl {
         register: Register::F4,
@@ -415,6 +483,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(4),
     },
     RegisterDecl {
         register: Register::F5,
@@ -423,6 +492,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(5),
     },
     RegisterDecl {
         register: Register::F6,
@@ -431,6 +501,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(6),
     },
     RegisterDecl {
         register: Register::F7,
@@ -439,6 +510,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(7),
     },
     RegisterDecl {
         register: Register::F8,
@@ -447,6 +519,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(8),
     },
     RegisterDecl {
         register: Register::F9,
@@ -455,6 +528,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(9),
     },
     RegisterDecl {
         register: Register::F10,
@@ -463,6 +537,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(10),
     },
     RegisterDecl {
         register: Register::F11,
@@ -471,6 +546,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(11),
     },
     RegisterDecl {
         register: Register::F12,
@@ -479,6 +555,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(12),
     },
     RegisterDecl {
         register: Register::F13,
@@ -487,6 +564,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(13),
     },
     RegisterDecl {
         register: Register::F14,
@@ -495,6 +573,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(14),
     },
     RegisterDecl {
         register: Register::F15,
@@ -503,6 +582,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(15),
     },
     RegisterDecl {
         register: Register::F16,
@@ -511,6 +591,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(16),
     },
     RegisterDecl {
         register: Register::F17,
@@ -519,6 +600,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(17),
     },
     RegisterDecl {
         register: Register::F18,
@@ -527,6 +609,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(18),
     },
     RegisterDecl {
         register: Register::F19,
@@ -535,6 +618,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(19),
     },
     RegisterDecl {
         register: Register::F20,
@@ -543,6 +627,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(20),
     },
     RegisterDecl {
         register: Register::F21,
@@ -551,6 +636,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(21),
     },
     RegisterDecl {
         register: Register::F22,
@@ -559,6 +645,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(22),
     },
     RegisterDecl {
         register: Register::F23,
@@ -567,6 +654,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(23),
     },
     RegisterDecl {
         register: Register::F24,
@@ -575,6 +663,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(24),
     },
     RegisterDecl {
         register: Register::F25,
@@ -583,6 +672,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(25),
     },
     RegisterDecl {
         register: Register::F26,
@@ -591,6 +681,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(26),
     },
     RegisterDecl {
         register: Register::F27,
@@ -599,6 +690,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(27),
     },
     RegisterDecl {
         register: Register::F28,
@@ -607,6 +699,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(28),
     },
     RegisterDecl {
         register: Register::F29,
@@ -615,6 +708,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(29),
     },
     RegisterDecl {
         register: Register::F30,
@@ -623,6 +717,7 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(30),
     },
     RegisterDecl {
         register: Register::F31,
@@ -631,9 +726,20 @@ const REGISTER_DECLS: &[RegisterDecl] = &[
         width: RegisterWidth::W64,
         reg_type: RegisterType::FloatingPoint,
         format: RegisterFormat::Double,
+        loc: Location::Fp(31),
     },
 ];
 
+#[cfg(target_arch = "riscv64")]
 pub fn registers_info_iter() -> impl Iterator<Item = RegisterInfo> {
     REGISTER_DECLS.iter().map(RegisterInfo::from)
 }
+
+/// The name, offset and size of each register in the table, which (unlike
+/// `registers_info_iter`) is available on any host, for testing.
+#[cfg(test)]
+pub(crate) fn register_offsets() -> impl Iterator<Item = (&'static str, usize, usize)> {
+    REGISTER_DECLS
+        .iter()
+        .map(|decl| (decl.name, decl.offset(), decl.width.bytes()))
+}
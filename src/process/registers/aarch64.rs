@@ -1,10 +1,15 @@
-#![allow(dead_code)]
-use crate::process::{Register, RegisterValue};
 use anyhow::Result;
 use libc::{user_fpsimd_struct, user_regs_struct};
-use nix::sys::ptrace::{getregset, read_user, regset, setregset, write_user};
+use nix::sys::ptrace::{getregset, regset, setregset};
 use nix::unistd::Pid;
 
+use crate::process::register_info::{Register, RegisterType, RegisterValue};
+use crate::process::registers::bytes::{
+    struct_as_bytes, struct_as_bytes_mut, value_from_bytes, value_into_bytes,
+};
+use crate::process::registers::expect_register_info;
+use crate::process::stoppoint::VirtualAddress;
+
 /// Current state of the registers for the debugged process.
 #[derive(Clone, Debug)]
 pub struct RegisterSnapshot {
@@ -26,19 +31,66 @@ impl RegisterSnapshot {
         }
     }
 
-    pub fn read(&self, _register: &Register) -> RegisterValue {
-        todo!("impl me");
+    pub fn read(&self, register: &Register) -> RegisterValue {
+        let info = expect_register_info(register);
+        match info.register_type {
+            RegisterType::GeneralPurpose | RegisterType::SubGeneralPurpose => {
+                value_from_bytes(struct_as_bytes(&self.user_gp), info.offset, info.format)
+            }
+            RegisterType::FloatingPoint => {
+                value_from_bytes(struct_as_bytes(&self.user_fp), info.offset, info.format)
+            }
+            RegisterType::Debug => unreachable!("no debug registers on aarch64: {register:?}"),
+        }
+    }
+
+    /// Write a register in the inferior, and update the snapshot to match.
+    ///
+    /// arm64 has no `PTRACE_POKEUSER`, so the whole register set is written.
+    pub fn write(&mut self, register: Register, value: RegisterValue) -> Result<()> {
+        let info = expect_register_info(&register);
+        match info.register_type {
+            RegisterType::GeneralPurpose | RegisterType::SubGeneralPurpose => {
+                let mut gp = self.user_gp;
+                value_into_bytes(
+                    struct_as_bytes_mut(&mut gp),
+                    info.offset,
+                    info.format,
+                    value,
+                )?;
+                setregset::<regset::NT_PRSTATUS>(self.pid, gp)?;
+                self.user_gp = gp;
+            }
+            RegisterType::FloatingPoint => {
+                let mut fp = self.user_fp;
+                value_into_bytes(
+                    struct_as_bytes_mut(&mut fp),
+                    info.offset,
+                    info.format,
+                    value,
+                )?;
+                setregset::<regset::NT_PRFPREG>(self.pid, fp)?;
+                self.user_fp = fp;
+            }
+            RegisterType::Debug => unreachable!("no debug registers on aarch64: {register:?}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_pc(&self) -> Result<VirtualAddress> {
+        let reg_value = self.read(&Register::PC);
+        RegisterValue::try_into(reg_value)
     }
 
-    #[allow(dead_code)]
-    pub fn write(&mut self, _register: Register, _value: RegisterValue) -> Result<()> {
-        todo!("impl me");
+    pub fn set_pc(&mut self, address: VirtualAddress) -> Result<()> {
+        self.write(Register::PC, RegisterValue::from(address))
     }
 }
 
 pub fn read_all_registers(pid: Pid) -> Result<RegisterSnapshot> {
-    let gp_reg = getregset::<regset::NT_PRSTATUS>(pid).unwrap();
-    let fp_reg = getregset::<regset::NT_PRFPREG>(pid).unwrap();
+    let gp_reg = getregset::<regset::NT_PRSTATUS>(pid)?;
+    let fp_reg = getregset::<regset::NT_PRFPREG>(pid)?;
 
     Ok(RegisterSnapshot::new(pid, gp_reg, fp_reg))
 }
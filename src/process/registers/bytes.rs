@@ -0,0 +1,378 @@
+//! Reading and writing register values within the raw bytes of the kernel's
+//! register set structs (`user_regs_struct` and friends).
+//!
+//! This is shared by all the architectures: each one only has to say which
+//! struct a register lives in, and the register table gives the offset and
+//! format within it.
+
+use anyhow::{Result, anyhow};
+
+use crate::process::register_info::{RegisterFormat, RegisterValue};
+
+pub fn struct_as_bytes<T>(value: &T) -> &[u8] {
+    let len = std::mem::size_of::<T>();
+    // SAFETY: Only reinterpreting the provided reference as bytes.
+    unsafe { std::slice::from_raw_parts((value as *const T).cast::<u8>(), len) }
+}
+
+/// Mutable byte view of a register set struct.
+///
+/// Only used with the plain-data libc structs, where any bit pattern is valid.
+pub fn struct_as_bytes_mut<T>(value: &mut T) -> &mut [u8] {
+    let len = std::mem::size_of::<T>();
+    // SAFETY: `T` is plain data, so writing arbitrary bytes leaves it valid.
+    unsafe { std::slice::from_raw_parts_mut((value as *mut T).cast::<u8>(), len) }
+}
+
+/// Number of bytes a value of this format occupies.
+pub const fn format_size(format: RegisterFormat) -> usize {
+    match format {
+        RegisterFormat::Uint8 | RegisterFormat::Int8 => 1,
+        RegisterFormat::Uint16 | RegisterFormat::Int16 => 2,
+        RegisterFormat::Uint32 | RegisterFormat::Int32 | RegisterFormat::Float => 4,
+        RegisterFormat::Uint64
+        | RegisterFormat::Int64
+        | RegisterFormat::Double
+        | RegisterFormat::Byte64 => 8,
+        RegisterFormat::LongDouble => 10,
+        RegisterFormat::Byte128 => 16,
+    }
+}
+
+/// Decode the (little endian) value at `start`.
+pub fn value_from_bytes(bytes: &[u8], start: usize, format: RegisterFormat) -> RegisterValue {
+    let slice = &bytes[start..start + format_size(format)];
+
+    match format {
+        RegisterFormat::Uint8 => RegisterValue::Uint8(slice[0]),
+        RegisterFormat::Uint16 => RegisterValue::Uint16(u16::from_le_bytes(array(slice))),
+        RegisterFormat::Uint32 => RegisterValue::Uint32(u32::from_le_bytes(array(slice))),
+        RegisterFormat::Uint64 => RegisterValue::Uint64(u64::from_le_bytes(array(slice))),
+        RegisterFormat::Int8 => RegisterValue::Int8(slice[0] as i8),
+        RegisterFormat::Int16 => RegisterValue::Int16(i16::from_le_bytes(array(slice))),
+        RegisterFormat::Int32 => RegisterValue::Int32(i32::from_le_bytes(array(slice))),
+        RegisterFormat::Int64 => RegisterValue::Int64(i64::from_le_bytes(array(slice))),
+        RegisterFormat::Float => RegisterValue::Float(f32::from_le_bytes(array(slice))),
+        RegisterFormat::Double => RegisterValue::Double(f64::from_le_bytes(array(slice))),
+        RegisterFormat::LongDouble => RegisterValue::LongDouble(array(slice)),
+        RegisterFormat::Byte64 => RegisterValue::Byte64(array(slice)),
+        RegisterFormat::Byte128 => RegisterValue::Byte128(array(slice)),
+    }
+}
+
+/// Encode `value` as a register of the given format at `start`, leaving the
+/// surrounding bytes alone, so writing a subregister (e.g. `w0`) only changes
+/// its part of the full register.
+///
+/// Integers are truncated to the register's width, and may also be written to
+/// floating point or vector registers (converted or zero extended, respectively).
+pub fn value_into_bytes(
+    bytes: &mut [u8],
+    start: usize,
+    format: RegisterFormat,
+    value: RegisterValue,
+) -> Result<()> {
+    let encoded = encode(format, value)?;
+    bytes[start..start + encoded.len()].copy_from_slice(&encoded);
+    Ok(())
+}
+
+fn encode(format: RegisterFormat, value: RegisterValue) -> Result<Vec<u8>> {
+    let size = format_size(format);
+    let mismatch = || anyhow!("Cannot write {value:?} to a {format:?} register");
+    let integer = i64::try_from(value).ok();
+
+    let encoded = match format {
+        RegisterFormat::Uint8
+        | RegisterFormat::Uint16
+        | RegisterFormat::Uint32
+        | RegisterFormat::Uint64
+        | RegisterFormat::Int8
+        | RegisterFormat::Int16
+        | RegisterFormat::Int32
+        | RegisterFormat::Int64 => integer.ok_or_else(mismatch)?.to_le_bytes()[..size].to_vec(),
+        RegisterFormat::Float => {
+            let v = match value {
+                RegisterValue::Float(v) => v,
+                RegisterValue::Double(v) => v as f32,
+                _ => integer.ok_or_else(mismatch)? as f32,
+            };
+            v.to_le_bytes().to_vec()
+        }
+        RegisterFormat::Double => {
+            let v = match value {
+                RegisterValue::Float(v) => v as f64,
+                RegisterValue::Double(v) => v,
+                _ => integer.ok_or_else(mismatch)? as f64,
+            };
+            v.to_le_bytes().to_vec()
+        }
+        RegisterFormat::LongDouble => match value {
+            RegisterValue::LongDouble(v) => v.to_vec(),
+            _ => return Err(mismatch()),
+        },
+        RegisterFormat::Byte64 | RegisterFormat::Byte128 => {
+            let bytes = match value {
+                RegisterValue::Byte64(v) => v.to_vec(),
+                RegisterValue::Byte128(v) => v.to_vec(),
+                _ => integer.ok_or_else(mismatch)?.to_le_bytes().to_vec(),
+            };
+            if bytes.len() > size {
+                return Err(mismatch());
+            }
+            zero_extend(&bytes, size)
+        }
+    };
+
+    Ok(encoded)
+}
+
+fn zero_extend(bytes: &[u8], size: usize) -> Vec<u8> {
+    let mut extended = vec![0; size];
+    extended[..bytes.len()].copy_from_slice(bytes);
+    extended
+}
+
+fn array<const N: usize>(slice: &[u8]) -> [u8; N] {
+    let mut buf = [0u8; N];
+    buf.copy_from_slice(slice);
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process::register_info::{aarch64, riscv64};
+    use memoffset::offset_of;
+
+    // Mirrors of the aarch64 and riscv64 register sets from the kernel headers,
+    // so the conversion can be checked on whatever we're built for.
+
+    /// `struct user_pt_regs` (aarch64)
+    #[repr(C)]
+    #[derive(Default)]
+    struct Aarch64Regs {
+        regs: [u64; 31],
+        sp: u64,
+        pc: u64,
+        pstate: u64,
+    }
+
+    /// `struct user_fpsimd_state` (aarch64)
+    #[repr(C)]
+    struct Aarch64Fpsimd {
+        vregs: [u128; 32],
+        fpsr: u32,
+        fpcr: u32,
+    }
+
+    /// `struct user_regs_struct` (riscv64): the PC takes the place of x0.
+    #[repr(C)]
+    #[derive(Default)]
+    struct Riscv64Regs {
+        pc: u64,
+        ra: u64,
+        sp: u64,
+        rest: [u64; 29],
+    }
+
+    /// `struct __riscv_d_ext_state` (riscv64)
+    #[repr(C)]
+    struct Riscv64DExt {
+        f: [u64; 32],
+        fcsr: u32,
+    }
+
+    /// Split a register name like `x12` into its prefix and number.
+    fn split_name(name: &str) -> (&str, Option<usize>) {
+        let number = name.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+        (&name[..name.len() - number.len()], number.parse().ok())
+    }
+
+    #[test]
+    fn aarch64_table_matches_register_sets() {
+        let regs = offset_of!(Aarch64Regs, regs);
+        let vregs = offset_of!(Aarch64Fpsimd, vregs);
+        for (name, offset, size) in aarch64::register_offsets() {
+            let expected = match split_name(name) {
+                ("x", Some(n)) => (regs + n * 8, 8),
+                ("w", Some(n)) => (regs + n * 8, 4),
+                ("v" | "q", Some(n)) => (vregs + n * 16, 16),
+                ("d", Some(n)) => (vregs + n * 16, 8),
+                ("s", Some(n)) => (vregs + n * 16, 4),
+                ("h", Some(n)) => (vregs + n * 16, 2),
+                ("b", Some(n)) => (vregs + n * 16, 1),
+                ("sp", None) => (offset_of!(Aarch64Regs, sp), 8),
+                ("pc", None) => (offset_of!(Aarch64Regs, pc), 8),
+                ("pstate", None) => (offset_of!(Aarch64Regs, pstate), 8),
+                ("cpsr", None) => (offset_of!(Aarch64Regs, pstate), 4),
+                ("fpsr", None) => (offset_of!(Aarch64Fpsimd, fpsr), 4),
+                ("fpcr", None) => (offset_of!(Aarch64Fpsimd, fpcr), 4),
+                _ => panic!("unexpected register {name}"),
+            };
+            assert_eq!((offset, size), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn riscv64_table_matches_register_sets() {
+        for (name, offset, size) in riscv64::register_offsets() {
+            let expected = match split_name(name) {
+                // x0 isn't stored anywhere
+                ("x", Some(0)) => continue,
+                ("x", Some(n)) => (offset_of!(Riscv64Regs, ra) + (n - 1) * 8, 8),
+                ("f", Some(n)) => (offset_of!(Riscv64DExt, f) + n * 8, 8),
+                ("pc", None) => (offset_of!(Riscv64Regs, pc), 8),
+                _ => panic!("unexpected register {name}"),
+            };
+            assert_eq!((offset, size), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn aarch64_general_purpose() {
+        let mut regs = Aarch64Regs::default();
+        regs.regs[3] = 0x1122_3344_5566_7788;
+        regs.pc = 0x5555_5555_1000;
+
+        let bytes = struct_as_bytes(&regs);
+        let x3 = offset_of!(Aarch64Regs, regs) + 3 * 8;
+        assert!(matches!(
+            value_from_bytes(bytes, x3, RegisterFormat::Uint64),
+            RegisterValue::Uint64(0x1122_3344_5566_7788)
+        ));
+        // w3 is the bottom half of x3
+        assert!(matches!(
+            value_from_bytes(bytes, x3, RegisterFormat::Uint32),
+            RegisterValue::Uint32(0x5566_7788)
+        ));
+        assert!(matches!(
+            value_from_bytes(bytes, offset_of!(Aarch64Regs, pc), RegisterFormat::Uint64),
+            RegisterValue::Uint64(0x5555_5555_1000)
+        ));
+
+        // writing w3 leaves the top half of x3 alone
+        let bytes = struct_as_bytes_mut(&mut regs);
+        value_into_bytes(
+            bytes,
+            x3,
+            RegisterFormat::Uint32,
+            RegisterValue::Uint64(0xdead_beef),
+        )
+        .unwrap();
+        let pc = offset_of!(Aarch64Regs, pc);
+        value_into_bytes(
+            bytes,
+            pc,
+            RegisterFormat::Uint64,
+            RegisterValue::Uint64(0x2000),
+        )
+        .unwrap();
+        assert_eq!(regs.regs[3], 0x1122_3344_dead_beef);
+        assert_eq!(regs.pc, 0x2000);
+    }
+
+    #[test]
+    fn aarch64_vector() {
+        let mut fp = Aarch64Fpsimd {
+            vregs: [0; 32],
+            fpsr: 0,
+            fpcr: 0,
+        };
+        fp.vregs[1] = 1.5f64.to_bits() as u128 | (0xffff << 64);
+        fp.fpcr = 0x0300_0000;
+
+        let bytes = struct_as_bytes(&fp);
+        let v1 = offset_of!(Aarch64Fpsimd, vregs) + 16;
+        // d1 is the bottom 64 bits of v1
+        assert!(matches!(
+            value_from_bytes(bytes, v1, RegisterFormat::Double),
+            RegisterValue::Double(1.5)
+        ));
+        let RegisterValue::Byte128(q1) = value_from_bytes(bytes, v1, RegisterFormat::Byte128)
+        else {
+            panic!("expected a 128-bit value");
+        };
+        assert_eq!(u128::from_le_bytes(q1), fp.vregs[1]);
+        assert!(matches!(
+            value_from_bytes(
+                bytes,
+                offset_of!(Aarch64Fpsimd, fpcr),
+                RegisterFormat::Uint32
+            ),
+            RegisterValue::Uint32(0x0300_0000)
+        ));
+
+        let bytes = struct_as_bytes_mut(&mut fp);
+        value_into_bytes(bytes, v1, RegisterFormat::Float, RegisterValue::Int64(2)).unwrap();
+        value_into_bytes(
+            bytes,
+            v1 + 16,
+            RegisterFormat::Byte128,
+            RegisterValue::Uint8(7),
+        )
+        .unwrap();
+        assert_eq!(fp.vregs[1] as u32, 2.0f32.to_bits());
+        assert_eq!(fp.vregs[1] >> 64, 0xffff);
+        assert_eq!(fp.vregs[2], 7);
+    }
+
+    #[test]
+    fn riscv64_general_purpose() {
+        let mut regs = Riscv64Regs {
+            pc: 0x1_0000,
+            ra: 0x1_0400,
+            ..Default::default()
+        };
+        regs.rest[7] = u64::MAX; // x10 (a0)
+
+        // registers are laid out by number, with the PC in slot 0
+        let bytes = struct_as_bytes(&regs);
+        assert!(matches!(
+            value_from_bytes(bytes, 0, RegisterFormat::Uint64),
+            RegisterValue::Uint64(0x1_0000)
+        ));
+        assert!(matches!(
+            value_from_bytes(bytes, 8, RegisterFormat::Uint64),
+            RegisterValue::Uint64(0x1_0400)
+        ));
+        assert!(matches!(
+            value_from_bytes(bytes, 10 * 8, RegisterFormat::Int64),
+            RegisterValue::Int64(-1)
+        ));
+
+        let bytes = struct_as_bytes_mut(&mut regs);
+        value_into_bytes(
+            bytes,
+            2 * 8,
+            RegisterFormat::Uint64,
+            RegisterValue::Uint64(0x7ff0),
+        )
+        .unwrap();
+        assert_eq!(regs.sp, 0x7ff0);
+    }
+
+    #[test]
+    fn rejects_mismatched_values() {
+        let mut bytes = [0u8; 16];
+        assert!(
+            value_into_bytes(
+                &mut bytes,
+                0,
+                RegisterFormat::Uint64,
+                RegisterValue::Double(1.0)
+            )
+            .is_err()
+        );
+        assert!(
+            value_into_bytes(
+                &mut bytes,
+                0,
+                RegisterFormat::Byte64,
+                RegisterValue::Byte128([1; 16])
+            )
+            .is_err()
+        );
+        assert_eq!(bytes, [0; 16]);
+    }
+}
@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
+mod bytes;
+
 #[cfg(target_arch = "x86_64")]
 mod x86_64;
 #[cfg(target_arch = "x86_64")]
 use crate::process::register_info::{Register, RegisterInfo, registers_info_iter};
 #[cfg(target_arch = "x86_64")]
-pub use x86_64::{RegisterSnapshot, read_all_registers};
+pub use x86_64::{RegisterSnapshot, read_all_registers, read_debug_register, write_debug_register};
 
 #[cfg(target_arch = "aarch64")]
 mod aarch64;
@@ -22,6 +24,24 @@ use crate::process::register_info::{Register, RegisterInfo, registers_info_iter}
 #[cfg(target_arch = "riscv64")]
 pub use riscv64::{RegisterSnapshot, read_all_registers};
 
+#[cfg(not(target_arch = "x86_64"))]
+pub fn read_debug_register(_pid: nix::unistd::Pid, _index: usize) -> anyhow::Result<u64> {
+    Err(anyhow::anyhow!(
+        "Hardware breakpoints and watchpoints are not supported on this architecture"
+    ))
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn write_debug_register(
+    _pid: nix::unistd::Pid,
+    _index: usize,
+    _value: u64,
+) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!(
+        "Hardware breakpoints and watchpoints are not supported on this architecture"
+    ))
+}
+
 /// Map of the architecure-specific registers when executing both the debugger
 /// and the inferior.
 static REGISTERS_MAP: LazyLock<HashMap<Register, RegisterInfo>> = LazyLock::new(|| {
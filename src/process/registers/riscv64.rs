@@ -1,10 +1,15 @@
-#![allow(dead_code)]
-use crate::process::{Register, RegisterValue};
-use anyhow::Result;
-use libc::user_regs_struct;
-use nix::sys::ptrace::{getregset, read_user, regset, setregset, write_user};
+use anyhow::{Result, anyhow};
+use libc::{__riscv_mc_d_ext_state, user_regs_struct};
+use nix::sys::ptrace::{getregset, regset, setregset};
 use nix::unistd::Pid;
 
+use crate::process::register_info::{Register, RegisterType, RegisterValue};
+use crate::process::registers::bytes::{
+    struct_as_bytes, struct_as_bytes_mut, value_from_bytes, value_into_bytes,
+};
+use crate::process::registers::expect_register_info;
+use crate::process::stoppoint::VirtualAddress;
+
 /// Current state of the registers for the debugged process.
 #[derive(Clone, Debug)]
 pub struct RegisterSnapshot {
@@ -14,33 +19,84 @@ pub struct RegisterSnapshot {
     // this is probably sufficient, but i could create a rust-equivalent that pairs
     // with the RegisterInfo a bit more. we shall see if this impl becomes a burden ...
     user_gp: user_regs_struct,
-    // NOTE: leaving the floating point registers out for now. The linux riscv64
-    // headers (/usr/riscv64-linux-gnu/include/asm/ptrace.h) defines __riscv_d_ext_state /
-    // __riscv_f_ext_state / __riscv_q_ext_state, but I'm not sure how to get those from `nix`.
-    // I think they need to be added to libc first (there's some similar "signal"-related structs),
-    // and then figure out how to plumb it into nix::ptrace.
+    /// The double precision floating point state (the D extension), which
+    /// includes the F registers' single precision values.
+    user_fp: __riscv_mc_d_ext_state,
 }
 
 impl RegisterSnapshot {
-    fn new(pid: Pid, gp_regs: user_regs_struct) -> Self {
+    fn new(pid: Pid, gp_regs: user_regs_struct, fp_regs: __riscv_mc_d_ext_state) -> Self {
         Self {
             pid,
             user_gp: gp_regs,
+            user_fp: fp_regs,
+        }
+    }
+
+    pub fn read(&self, register: &Register) -> RegisterValue {
+        if *register == Register::X0 {
+            return RegisterValue::Uint64(0);
+        }
+        let info = expect_register_info(register);
+        match info.register_type {
+            RegisterType::GeneralPurpose | RegisterType::SubGeneralPurpose => {
+                value_from_bytes(struct_as_bytes(&self.user_gp), info.offset, info.format)
+            }
+            RegisterType::FloatingPoint => {
+                value_from_bytes(struct_as_bytes(&self.user_fp), info.offset, info.format)
+            }
+            RegisterType::Debug => unreachable!("no debug registers on riscv64: {register:?}"),
         }
     }
 
-    pub fn read(&self, _register: &Register) -> RegisterValue {
-        todo!("impl me");
+    /// Write a register in the inferior, and update the snapshot to match.
+    pub fn write(&mut self, register: Register, value: RegisterValue) -> Result<()> {
+        if register == Register::X0 {
+            return Err(anyhow!("x0 is hardwired to zero"));
+        }
+        let info = expect_register_info(&register);
+        match info.register_type {
+            RegisterType::GeneralPurpose | RegisterType::SubGeneralPurpose => {
+                let mut gp = self.user_gp;
+                value_into_bytes(
+                    struct_as_bytes_mut(&mut gp),
+                    info.offset,
+                    info.format,
+                    value,
+                )?;
+                setregset::<regset::NT_PRSTATUS>(self.pid, gp)?;
+                self.user_gp = gp;
+            }
+            RegisterType::FloatingPoint => {
+                let mut fp = self.user_fp;
+                value_into_bytes(
+                    struct_as_bytes_mut(&mut fp),
+                    info.offset,
+                    info.format,
+                    value,
+                )?;
+                setregset::<regset::NT_PRFPREG>(self.pid, fp)?;
+                self.user_fp = fp;
+            }
+            RegisterType::Debug => unreachable!("no debug registers on riscv64: {register:?}"),
+        }
+
+        Ok(())
+    }
+
+    pub fn get_pc(&self) -> Result<VirtualAddress> {
+        let reg_value = self.read(&Register::PC);
+        RegisterValue::try_into(reg_value)
     }
 
-    #[allow(dead_code)]
-    pub fn write(&mut self, _register: Register, _value: RegisterValue) -> Result<()> {
-        todo!("impl me");
+    pub fn set_pc(&mut self, address: VirtualAddress) -> Result<()> {
+        self.write(Register::PC, RegisterValue::from(address))
     }
 }
 
 pub fn read_all_registers(pid: Pid) -> Result<RegisterSnapshot> {
-    let gp_reg = getregset::<regset::NT_PRSTATUS>(pid).unwrap();
+    let gp_reg = getregset::<regset::NT_PRSTATUS>(pid)?;
+    let fp_reg = getregset::<regset::NT_PRFPREG>(pid)?;
 
-    Ok(RegisterSnapshot::new(pid, gp_reg))
+    Ok(RegisterSnapshot::new(pid, gp_reg, fp_reg))
 }
@@ -4,8 +4,9 @@ use memoffset::offset_of;
 use nix::sys::ptrace::{getregset, read_user, regset, setregset, write_user};
 use nix::unistd::Pid;
 
-use crate::process::register_info::{
-    Register, RegisterFormat, RegisterInfo, RegisterType, RegisterValue,
+use crate::process::register_info::{Register, RegisterType, RegisterValue};
+use crate::process::registers::bytes::{
+    struct_as_bytes, struct_as_bytes_mut, value_from_bytes, value_into_bytes,
 };
 use crate::process::registers::expect_register_info;
 use crate::process::stoppoint::VirtualAddress;
@@ -48,39 +49,59 @@ impl RegisterSnapshot {
             // component struct, so adjust by the field offset.
             RegisterType::GeneralPurpose | RegisterType::SubGeneralPurpose => {
                 let start = info.offset - offset_of!(user, regs);
-                value_from_bytes(struct_as_bytes(&self.user_gp), start, info)
+                value_from_bytes(struct_as_bytes(&self.user_gp), start, info.format)
             }
             RegisterType::FloatingPoint => {
                 let start = info.offset - offset_of!(user, i387);
-                value_from_bytes(struct_as_bytes(&self.user_fp), start, info)
+                value_from_bytes(struct_as_bytes(&self.user_fp), start, info.format)
             }
             RegisterType::Debug => {
                 // Debug registers are stored separately; use the cached array.
                 let start = info.offset - offset_of!(user, u_debugreg);
-                value_from_bytes(slice_as_bytes(&self.debug_regs), start, info)
+                value_from_bytes(slice_as_bytes(&self.debug_regs), start, info.format)
             }
         }
     }
 
+    /// Write a register in the inferior, and update the snapshot to match.
+    ///
+    /// The value is merged into the cached register set, so writing a
+    /// subregister (e.g. `eax` or `ah`) leaves the rest of the full register
+    /// alone, and then the whole set is written back.
     pub fn write(&mut self, register: Register, value: RegisterValue) -> Result<()> {
-        // TODO: there's a lot of incomplete work here ...
-        // including a problem of writing a reg value less than u64 :shrug:
-        // will fix when I hit it ... just really need to move forward for now
-
         let info = expect_register_info(&register);
-
-        // apparently PTRACE_POKEUSER does not work on the x87 area on x86
-        // (according to the Sy Brand book), so write all the x87 registers at once.
-        if matches!(info.register_type, RegisterType::FloatingPoint) {
-            let fpregs = self.user_fp;
-            // TODO: actually set the value into the struct
-            setregset::<regset::NT_PRFPREG>(self.pid, fpregs)?;
-        } else {
-            // clears out the bottom 3 bits (! is bitwise NOT), effectively round
-            // down to a multiple of 8.
-            let aligned_offset = info.offset & !0b111;
-            // TODO: i have no idea if this is correct?
-            write_user(self.pid, aligned_offset as _, value.try_into()?)?;
+        match info.register_type {
+            RegisterType::GeneralPurpose | RegisterType::SubGeneralPurpose => {
+                let start = info.offset - offset_of!(user, regs);
+                let mut gp = self.user_gp;
+                value_into_bytes(struct_as_bytes_mut(&mut gp), start, info.format, value)?;
+                setregset::<regset::NT_PRSTATUS>(self.pid, gp)?;
+                self.user_gp = gp;
+            }
+            // apparently PTRACE_POKEUSER does not work on the x87 area on x86
+            // (according to the Sy Brand book), so write all the x87 registers at once.
+            RegisterType::FloatingPoint => {
+                let start = info.offset - offset_of!(user, i387);
+                let mut fp = self.user_fp;
+                value_into_bytes(struct_as_bytes_mut(&mut fp), start, info.format, value)?;
+                setregset::<regset::NT_PRFPREG>(self.pid, fp)?;
+                self.user_fp = fp;
+            }
+            // there's no register set for the debug registers, so poke the one
+            // that changed
+            RegisterType::Debug => {
+                let start = info.offset - offset_of!(user, u_debugreg);
+                let mut debug_regs = self.debug_regs;
+                value_into_bytes(
+                    struct_as_bytes_mut(&mut debug_regs),
+                    start,
+                    info.format,
+                    value,
+                )?;
+                let index = start / 8;
+                write_debug_register(self.pid, index, debug_regs[index])?;
+                self.debug_regs = debug_regs;
+            }
         }
 
         Ok(())
@@ -96,86 +117,23 @@ impl RegisterSnapshot {
     }
 }
 
-fn struct_as_bytes<T>(value: &T) -> &[u8] {
-    let len = std::mem::size_of::<T>();
-    // SAFETY: Only reinterpreting the provided reference as bytes.
-    unsafe { std::slice::from_raw_parts((value as *const T).cast::<u8>(), len) }
+/// Read one of the debug registers (DR0–DR7).
+pub fn read_debug_register(pid: Pid, index: usize) -> Result<u64> {
+    let offset = offset_of!(user, u_debugreg) + index * 8;
+    Ok(read_user(pid, offset as _)? as u64)
 }
 
-fn slice_as_bytes<T>(slice: &[T]) -> &[u8] {
-    let len = std::mem::size_of_val(slice);
-    // SAFETY: `T` is plain data; we only read the byte view.
-    unsafe { std::slice::from_raw_parts(slice.as_ptr().cast::<u8>(), len) }
-}
-
-fn value_from_bytes(bytes: &[u8], start: usize, info: &RegisterInfo) -> RegisterValue {
-    let end = start + info.size;
-    let slice = &bytes[start..end];
-
-    match info.format {
-        RegisterFormat::Uint8 => RegisterValue::Uint8(slice[0]),
-        RegisterFormat::Uint16 => {
-            let mut buf = [0u8; 2];
-            buf.copy_from_slice(slice);
-            RegisterValue::Uint16(u16::from_le_bytes(buf))
-        }
-        RegisterFormat::Uint32 => {
-            let mut buf = [0u8; 4];
-            buf.copy_from_slice(slice);
-            RegisterValue::Uint32(u32::from_le_bytes(buf))
-        }
-        RegisterFormat::Uint64 => {
-            let mut buf = [0u8; 8];
-            buf.copy_from_slice(slice);
-            RegisterValue::Uint64(u64::from_le_bytes(buf))
-        }
-        RegisterFormat::Int8 => RegisterValue::Int8(slice[0] as i8),
-        RegisterFormat::Int16 => {
-            let mut buf = [0u8; 2];
-            buf.copy_from_slice(slice);
-            RegisterValue::Int16(i16::from_le_bytes(buf))
-        }
-        RegisterFormat::Int32 => {
-            let mut buf = [0u8; 4];
-            buf.copy_from_slice(slice);
-            RegisterValue::Int32(i32::from_le_bytes(buf))
-        }
-        RegisterFormat::Int64 => {
-            let mut buf = [0u8; 8];
-            buf.copy_from_slice(slice);
-            RegisterValue::Int64(i64::from_le_bytes(buf))
-        }
-        RegisterFormat::Float => {
-            let mut buf = [0u8; 4];
-            buf.copy_from_slice(slice);
-            RegisterValue::Float(f32::from_le_bytes(buf))
-        }
-        RegisterFormat::Double => {
-            let mut buf = [0u8; 8];
-            buf.copy_from_slice(slice);
-            RegisterValue::Double(f64::from_le_bytes(buf))
-        }
-        RegisterFormat::LongDouble => {
-            let mut buf = [0u8; 10];
-            buf.copy_from_slice(&slice[..10]);
-            RegisterValue::LongDouble(buf)
-        }
-        RegisterFormat::Byte64 => {
-            let mut buf = [0u8; 8];
-            buf.copy_from_slice(&slice[..8]);
-            RegisterValue::Byte64(buf)
-        }
-        RegisterFormat::Byte128 => {
-            let mut buf = [0u8; 16];
-            buf.copy_from_slice(&slice[..16]);
-            RegisterValue::Byte128(buf)
-        }
-    }
+/// Write one of the debug registers (DR0–DR7). The kernel validates the
+/// values, e.g. refusing kernel addresses or misaligned watchpoints.
+pub fn write_debug_register(pid: Pid, index: usize, value: u64) -> Result<()> {
+    let offset = offset_of!(user, u_debugreg) + index * 8;
+    write_user(pid, offset as _, value as _)?;
+    Ok(())
 }
 
 pub fn read_all_registers(pid: Pid) -> Result<RegisterSnapshot> {
-    let gp_reg = getregset::<regset::NT_PRSTATUS>(pid).unwrap();
-    let fp_reg = getregset::<regset::NT_PRFPREG>(pid).unwrap();
+    let gp_reg = getregset::<regset::NT_PRSTATUS>(pid)?;
+    let fp_reg = getregset::<regset::NT_PRFPREG>(pid)?;
 
     // read out the debug registers
     let mut debug_regs = [0; 8];
@@ -183,9 +141,16 @@ pub fn read_all_registers(pid: Pid) -> Result<RegisterSnapshot> {
     for (i, e) in debug_regs.iter_mut().enumerate() {
         // TODO: don't hardcode the offset
         let offset = base_regs_offset + (i * 8);
-        let reg = read_user(pid, offset as _).unwrap();
+        let reg = read_user(pid, offset as _)?;
         *e = reg as u64;
     }
 
     Ok(RegisterSnapshot::new(pid, gp_reg, fp_reg, debug_regs))
 }
+
+/// Byte view of the cached debug registers.
+fn slice_as_bytes<T>(slice: &[T]) -> &[u8] {
+    let len = std::mem::size_of_val(slice);
+    // SAFETY: `T` is plain data; we only read the byte view.
+    unsafe { std::slice::from_raw_parts(slice.as_ptr().cast::<u8>(), len) }
+}
@@ -0,0 +1,123 @@
+//! What to do when the inferior receives a signal: whether to stop for it,
+//! whether to tell the user about it, and whether the inferior gets it.
+
+use anyhow::{Result, anyhow};
+use nix::errno::Errno;
+use nix::sys::ptrace;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::process::StopReason;
+use crate::process::thread::Thread;
+
+/// How the debugger handles one signal, as set with `handle`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SignalDisposition {
+    /// Stop the inferior, and hand control to the user.
+    pub stop: bool,
+    /// Log the signal, even if we don't stop for it.
+    pub print: bool,
+    /// Deliver the signal to the inferior when it's resumed. Otherwise it's
+    /// discarded, as if it never happened.
+    pub pass: bool,
+}
+
+impl SignalDisposition {
+    const fn new(stop: bool, print: bool, pass: bool) -> Self {
+        Self { stop, print, pass }
+    }
+
+    /// The disposition gdb starts out with, except that `SIGSTOP` isn't passed
+    /// on, as it's how we stop the inferior's threads.
+    fn default_for(signal: Signal) -> Self {
+        match signal {
+            // used by the debugger itself
+            Signal::SIGTRAP | Signal::SIGSTOP | Signal::SIGINT => Self::new(true, true, false),
+            // routine, and usually handled by the program
+            Signal::SIGALRM
+            | Signal::SIGURG
+            | Signal::SIGCHLD
+            | Signal::SIGIO
+            | Signal::SIGVTALRM
+            | Signal::SIGPROF
+            | Signal::SIGWINCH => Self::new(false, false, true),
+            _ => Self::new(true, true, true),
+        }
+    }
+}
+
+/// The disposition of every signal.
+#[derive(Clone, Debug)]
+pub struct SignalTable(BTreeMap<Signal, SignalDisposition>);
+
+impl Default for SignalTable {
+    fn default() -> Self {
+        Self(
+            Signal::iterator()
+                .map(|signal| (signal, SignalDisposition::default_for(signal)))
+                .collect(),
+        )
+    }
+}
+
+impl SignalTable {
+    pub fn get(&self, signal: Signal) -> SignalDisposition {
+        self.0
+            .get(&signal)
+            .copied()
+            .unwrap_or_else(|| SignalDisposition::default_for(signal))
+    }
+
+    /// Change how a signal is handled. `SIGTRAP` and `SIGSTOP` are how the
+    /// debugger stops the inferior, so they can't be changed.
+    pub fn set(&mut self, signal: Signal, disposition: SignalDisposition) -> Result<()> {
+        if matches!(signal, Signal::SIGTRAP | Signal::SIGSTOP) {
+            return Err(anyhow!("{signal} is used by the debugger"));
+        }
+        self.0.insert(signal, disposition);
+        Ok(())
+    }
+
+    /// Every signal, in numerical order, with how it's handled.
+    pub fn iter(&self) -> impl Iterator<Item = (Signal, SignalDisposition)> + '_ {
+        self.0
+            .iter()
+            .map(|(signal, disposition)| (*signal, *disposition))
+    }
+
+    /// The signal a stopped thread should be resumed with: the one it stopped
+    /// for, if that's passed to the program.
+    pub(super) fn signal_to_pass(&self, thread: &Thread) -> Option<Signal> {
+        match thread.stop_reason() {
+            Some(StopReason::Stopped { signal, .. }) if self.get(*signal).pass => Some(*signal),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a signal name, with or without the `SIG` prefix and in any case
+/// (e.g. `SIGSEGV` or `segv`), or a signal number.
+pub fn parse_signal(s: &str) -> Result<Signal> {
+    if let Ok(number) = s.parse::<i32>() {
+        return Signal::try_from(number).map_err(|_| anyhow!("Invalid signal number {number}"));
+    }
+    let name = s.to_uppercase();
+    let name = match name.starts_with("SIG") {
+        true => name,
+        false => format!("SIG{name}"),
+    };
+    Signal::from_str(&name).map_err(|_| anyhow!("Unknown signal {s:?}"))
+}
+
+/// Whether a thread's stop is a group-stop, i.e. the process stopping (as for
+/// job control) because a stopping signal we passed on was delivered, rather
+/// than the signal itself arriving. It's told apart by there being no
+/// `siginfo` for it. Resuming the thread ends the stop.
+pub(super) fn is_group_stop(tid: Pid, signal: Signal) -> bool {
+    matches!(
+        signal,
+        Signal::SIGSTOP | Signal::SIGTSTP | Signal::SIGTTIN | Signal::SIGTTOU
+    ) && matches!(ptrace::getsiginfo(tid), Err(Errno::EINVAL))
+}
@@ -0,0 +1,177 @@
+use anyhow::{Error, anyhow};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use crate::process::stoppoint::stop_policy::StopPolicy;
+use crate::process::stoppoint::{StoppointId, StoppointState, VirtualAddress};
+
+// Separate from the site IDs, so the user sees breakpoints numbered 1, 2, 3...
+// (watchpoints share the numbering)
+static NEXT_ID: AtomicI32 = AtomicI32::new(1);
+
+/// User-visible identifier for a breakpoint, as used by `delete`, `enable`,
+/// and `disable`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct BreakpointId {
+    id: i32,
+}
+
+impl BreakpointId {
+    pub(crate) fn next() -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        BreakpointId { id }
+    }
+}
+
+impl TryFrom<Vec<String>> for BreakpointId {
+    type Error = Error;
+
+    fn try_from(v: Vec<String>) -> Result<Self, Self::Error> {
+        if v.len() != 1 {
+            return Err(anyhow!("Wrong number of arguments: {:?}", v));
+        }
+
+        let s = v.first().unwrap();
+        let id = s
+            .parse::<i32>()
+            .map_err(|e| anyhow!("Invalid breakpoint number {:?}: {e}", s))?;
+        Ok(Self { id })
+    }
+}
+
+impl fmt::Display for BreakpointId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)
+    }
+}
+
+/// What the user asked to break on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BreakpointLocation {
+    /// A single, exact address.
+    Address(VirtualAddress),
+    /// Every function with the given (raw or demangled) name, just past its
+    /// prologue.
+    Function(String),
+    /// A source line, e.g. `src/main.rs:12`. The file may be a suffix of the
+    /// path recorded in the debug info.
+    Line { file: PathBuf, line: u64 },
+}
+
+impl BreakpointLocation {
+    /// Whether this location is resolved by looking up debug info or symbols
+    /// (and so must be re-resolved when objects are loaded).
+    pub fn is_symbolic(&self) -> bool {
+        !matches!(self, BreakpointLocation::Address(_))
+    }
+}
+
+impl From<VirtualAddress> for BreakpointLocation {
+    fn from(address: VirtualAddress) -> Self {
+        BreakpointLocation::Address(address)
+    }
+}
+
+impl fmt::Display for BreakpointLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakpointLocation::Address(address) => write!(f, "{address}"),
+            BreakpointLocation::Function(name) => write!(f, "{name}"),
+            BreakpointLocation::Line { file, line } => write!(f, "{}:{line}", file.display()),
+        }
+    }
+}
+
+/// A user-visible breakpoint.
+///
+/// A breakpoint may resolve to any number of [`BreakpointSite`]s (one per
+/// address), e.g. a function name matching several functions. A breakpoint on
+/// a function that hasn't been loaded yet has no sites, and is "pending"
+/// until it's resolved.
+///
+/// [`BreakpointSite`]: crate::process::stoppoint::breakpoint_site::BreakpointSite
+#[derive(Clone, Debug)]
+pub struct Breakpoint {
+    id: BreakpointId,
+    location: BreakpointLocation,
+    state: StoppointState,
+    /// IDs of the sites this breakpoint has resolved to.
+    sites: Vec<StoppointId>,
+    /// Whether the sites use the debug registers rather than `int3`.
+    hardware: bool,
+    /// Ignore count, condition and commands. Hits are counted by the sites.
+    policy: StopPolicy,
+}
+
+impl Breakpoint {
+    pub fn new(location: BreakpointLocation) -> Self {
+        Self {
+            id: BreakpointId::next(),
+            location,
+            state: StoppointState::Disabled,
+            sites: Vec::new(),
+            hardware: false,
+            policy: StopPolicy::default(),
+        }
+    }
+
+    /// A breakpoint implemented with the debug registers, leaving the code
+    /// untouched (e.g. for checksummed or shared code).
+    pub fn new_hardware(location: BreakpointLocation) -> Self {
+        Self {
+            hardware: true,
+            ..Self::new(location)
+        }
+    }
+
+    pub fn is_hardware(&self) -> bool {
+        self.hardware
+    }
+
+    pub fn id(&self) -> BreakpointId {
+        self.id
+    }
+
+    pub fn location(&self) -> &BreakpointLocation {
+        &self.location
+    }
+
+    pub fn policy(&self) -> &StopPolicy {
+        &self.policy
+    }
+
+    pub fn policy_mut(&mut self) -> &mut StopPolicy {
+        &mut self.policy
+    }
+
+    pub fn enable(&mut self) {
+        self.state = StoppointState::Enabled;
+    }
+
+    pub fn disable(&mut self) {
+        self.state = StoppointState::Disabled
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        matches!(self.state, StoppointState::Enabled)
+    }
+
+    pub fn sites(&self) -> &[StoppointId] {
+        &self.sites
+    }
+
+    pub fn add_site(&mut self, site: StoppointId) {
+        self.sites.push(site);
+    }
+
+    /// Forget all the resolved sites, e.g. when a new inferior is started and
+    /// the addresses need resolving again.
+    pub fn clear_sites(&mut self) -> Vec<StoppointId> {
+        std::mem::take(&mut self.sites)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.sites.is_empty()
+    }
+}
@@ -1,40 +1,57 @@
-use std::sync::atomic::{AtomicI32, Ordering};
-
+use crate::process::stoppoint::breakpoint::BreakpointId;
 use crate::process::stoppoint::{StoppointId, StoppointState, VirtualAddress};
 
-// Simple global ID generator; relaxed ordering is sufficient for a monotonic counter.
-static NEXT_ID: AtomicI32 = AtomicI32::new(1);
-
-fn next_id() -> StoppointId {
-    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
-    StoppointId { id }
-}
-
-/// A software breakpoint.
+/// A breakpoint at a single address: either an `int3` patched into the code,
+/// or one of the debug registers.
 #[derive(Clone, Debug)]
 pub struct BreakpointSite {
     /// Unique identifier for the stoppoint.
     id: StoppointId,
+    /// The user-visible breakpoint this site belongs to.
+    breakpoint: BreakpointId,
     //process: Process ???
     address: VirtualAddress,
 
     /// State of the stoppoint (basically, is it enabled or disabled?).
     state: StoppointState,
+    /// Whether this uses a debug register rather than an `int3`.
+    hardware: bool,
+    /// Number of times the inferior has stopped here.
+    hit_count: u64,
 }
 
 impl BreakpointSite {
-    pub fn new(address: VirtualAddress) -> Self {
+    pub fn new(breakpoint: BreakpointId, address: VirtualAddress) -> Self {
         Self {
-            id: next_id(),
+            id: StoppointId::next(),
+            breakpoint,
             address,
             state: StoppointState::Disabled,
+            hardware: false,
+            hit_count: 0,
+        }
+    }
+
+    /// A site using one of the debug registers, in execute mode.
+    pub fn new_hardware(breakpoint: BreakpointId, address: VirtualAddress) -> Self {
+        Self {
+            hardware: true,
+            ..Self::new(breakpoint, address)
         }
     }
 
+    pub fn is_hardware(&self) -> bool {
+        self.hardware
+    }
+
     pub fn id(&self) -> StoppointId {
         self.id
     }
 
+    pub fn breakpoint(&self) -> BreakpointId {
+        self.breakpoint
+    }
+
     pub fn enable(&mut self) {
         self.state = StoppointState::Enabled;
     }
@@ -47,6 +64,14 @@ impl BreakpointSite {
         matches!(self.state, StoppointState::Enabled)
     }
 
+    pub fn hit_count(&self) -> u64 {
+        self.hit_count
+    }
+
+    pub fn record_hit(&mut self) {
+        self.hit_count += 1;
+    }
+
     pub fn address(&self) -> VirtualAddress {
         self.address
     }
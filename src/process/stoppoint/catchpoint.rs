@@ -0,0 +1,99 @@
+use std::fmt;
+
+use crate::process::stoppoint::StoppointState;
+use crate::process::stoppoint::breakpoint::BreakpointId;
+use crate::process::stoppoint::stop_policy::StopPolicy;
+use crate::process::syscalls::syscall_name;
+
+/// A user-visible syscall catchpoint: stop when the inferior makes, and
+/// again when it returns from, one of the syscalls.
+///
+/// Catchpoints are numbered along with breakpoints and watchpoints.
+#[derive(Clone, Debug)]
+pub struct Catchpoint {
+    id: BreakpointId,
+    /// The syscall numbers to stop on; empty for any syscall.
+    syscalls: Vec<u64>,
+    state: StoppointState,
+    /// Hit and ignore counts (each counting entries and exits), condition and
+    /// commands.
+    policy: StopPolicy,
+}
+
+impl Catchpoint {
+    pub fn new(syscalls: Vec<u64>) -> Self {
+        Self {
+            id: BreakpointId::next(),
+            syscalls,
+            state: StoppointState::Enabled,
+            policy: StopPolicy::default(),
+        }
+    }
+
+    pub fn id(&self) -> BreakpointId {
+        self.id
+    }
+
+    pub fn syscalls(&self) -> &[u64] {
+        &self.syscalls
+    }
+
+    /// Whether the catchpoint is for the syscall.
+    pub fn catches(&self, number: u64) -> bool {
+        self.syscalls.is_empty() || self.syscalls.contains(&number)
+    }
+
+    pub fn policy(&self) -> &StopPolicy {
+        &self.policy
+    }
+
+    pub fn policy_mut(&mut self) -> &mut StopPolicy {
+        &mut self.policy
+    }
+
+    pub fn enable(&mut self) {
+        self.state = StoppointState::Enabled;
+    }
+
+    pub fn disable(&mut self) {
+        self.state = StoppointState::Disabled
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        matches!(self.state, StoppointState::Enabled)
+    }
+
+    /// The syscalls caught, e.g. `"openat" "close"`, or `any`.
+    pub fn describe_syscalls(&self) -> String {
+        if self.syscalls.is_empty() {
+            return "any".to_string();
+        }
+        self.syscalls
+            .iter()
+            .map(|number| format!("{:?}", syscall_name(*number)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl fmt::Display for Catchpoint {
+    /// e.g. `Catchpoint 3 (syscall 'openat' [257])`, or `Catchpoint 3 (any
+    /// syscall)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let syscalls: Vec<_> = self
+            .syscalls
+            .iter()
+            .map(|number| format!("'{}' [{number}]", syscall_name(*number)))
+            .collect();
+        match syscalls.len() {
+            0 => write!(f, "Catchpoint {} (any syscall)", self.id),
+            1 => write!(f, "Catchpoint {} (syscall {})", self.id, syscalls[0]),
+            _ => write!(
+                f,
+                "Catchpoint {} (syscalls {})",
+                self.id,
+                syscalls.join(" ")
+            ),
+        }
+    }
+}
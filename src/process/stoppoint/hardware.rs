@@ -0,0 +1,148 @@
+//! Hardware breakpoints and watchpoints, using the x86 debug registers.
+//!
+//! DR0–DR3 hold up to four addresses, and DR7 controls how each of them is
+//! used: whether it's enabled, whether it traps on execution, writes, or
+//! reads and writes, and how many bytes it covers. When one fires, the low
+//! bits of DR6 say which.
+
+use anyhow::{Result, anyhow};
+use std::fmt;
+
+use crate::process::stoppoint::VirtualAddress;
+
+/// The number of debug address registers (DR0–DR3).
+pub const HARDWARE_SLOTS: usize = 4;
+
+/// What access to an address triggers a hardware stoppoint.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HardwareMode {
+    /// Executing the instruction at the address (a hardware breakpoint).
+    Execute,
+    /// Writing to the address.
+    Write,
+    /// Reading or writing the address (x86 can't trap on reads alone).
+    ReadWrite,
+}
+
+impl fmt::Display for HardwareMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HardwareMode::Execute => write!(f, "execute"),
+            HardwareMode::Write => write!(f, "write"),
+            HardwareMode::ReadWrite => write!(f, "read/write"),
+        }
+    }
+}
+
+/// An address to program into one of the debug registers.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HardwareStoppoint {
+    pub address: VirtualAddress,
+    /// Number of bytes covered: 1, 2, 4, or 8.
+    pub size: usize,
+    pub mode: HardwareMode,
+}
+
+impl HardwareStoppoint {
+    /// A hardware breakpoint on the instruction at `address`.
+    pub fn execute(address: VirtualAddress) -> Self {
+        Self {
+            address,
+            size: 1,
+            mode: HardwareMode::Execute,
+        }
+    }
+
+    /// A watchpoint on `size` bytes at `address`, which must be aligned to
+    /// the size (the hardware ignores the low bits of the address).
+    pub fn watch(address: VirtualAddress, size: usize, mode: HardwareMode) -> Result<Self> {
+        if !matches!(size, 1 | 2 | 4 | 8) {
+            return Err(anyhow!(
+                "Invalid watchpoint size {size}, it must be 1, 2, 4, or 8 bytes"
+            ));
+        }
+        if !address.addr().is_multiple_of(size as u64) {
+            return Err(anyhow!(
+                "Watchpoint address {address} must be aligned to its size ({size} bytes)"
+            ));
+        }
+        Ok(Self {
+            address,
+            size,
+            mode,
+        })
+    }
+}
+
+// DR7 layout: a local enable bit for each slot in the low byte, then 4 bits per
+// slot from bit 16: 2 for the access type (RW) and 2 for the length (LEN).
+const DR7_CONTROL_SHIFT: usize = 16;
+const DR7_CONTROL_BITS: usize = 4;
+
+/// DR7 with `slot` configured for `stoppoint`, or cleared if `None`. Other
+/// slots are left as they are.
+pub fn dr7_with_slot(dr7: u64, slot: usize, stoppoint: Option<&HardwareStoppoint>) -> u64 {
+    assert!(slot < HARDWARE_SLOTS, "invalid debug register slot {slot}");
+    let enable_bit = 1 << (slot * 2);
+    let control_shift = DR7_CONTROL_SHIFT + slot * DR7_CONTROL_BITS;
+    let cleared = dr7 & !enable_bit & !(0b1111 << control_shift);
+
+    let Some(stoppoint) = stoppoint else {
+        return cleared;
+    };
+    let rw: u64 = match stoppoint.mode {
+        HardwareMode::Execute => 0b00,
+        HardwareMode::Write => 0b01,
+        HardwareMode::ReadWrite => 0b11,
+    };
+    let len: u64 = match stoppoint.size {
+        1 => 0b00,
+        2 => 0b01,
+        8 => 0b10,
+        4 => 0b11,
+        size => unreachable!("invalid hardware stoppoint size {size}"),
+    };
+    cleared | enable_bit | ((rw | (len << 2)) << control_shift)
+}
+
+/// The slots DR6 reports as having fired.
+pub fn dr6_triggered_slots(dr6: u64) -> impl Iterator<Item = usize> {
+    (0..HARDWARE_SLOTS).filter(move |slot| dr6 & (1 << slot) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dr7_encoding() {
+        let watch = HardwareStoppoint::watch(0x1000.into(), 8, HardwareMode::Write).unwrap();
+        // L1, RW1 = 01, LEN1 = 10
+        let dr7 = dr7_with_slot(0, 1, Some(&watch));
+        assert_eq!(dr7, 0b1001 << 20 | 0b100);
+
+        let exec = HardwareStoppoint::execute(0x1234.into());
+        let dr7 = dr7_with_slot(dr7, 3, Some(&exec));
+        assert_eq!(dr7, 0b1001 << 20 | 0b100 | 0b100_0000);
+
+        // clearing a slot leaves the others alone
+        assert_eq!(dr7_with_slot(dr7, 1, None), 0b100_0000);
+
+        let rw = HardwareStoppoint::watch(0x1000.into(), 4, HardwareMode::ReadWrite).unwrap();
+        assert_eq!(dr7_with_slot(0, 0, Some(&rw)), 0b1111 << 16 | 0b1);
+    }
+
+    #[test]
+    fn watch_validation() {
+        assert!(HardwareStoppoint::watch(0x1000.into(), 3, HardwareMode::Write).is_err());
+        assert!(HardwareStoppoint::watch(0x1004.into(), 8, HardwareMode::Write).is_err());
+        assert!(HardwareStoppoint::watch(0x1004.into(), 4, HardwareMode::Write).is_ok());
+    }
+
+    #[test]
+    fn dr6_slots() {
+        let slots: Vec<_> = dr6_triggered_slots(0xffff_0ff2).collect();
+        assert_eq!(slots, [1]);
+        assert_eq!(dr6_triggered_slots(0b1001).collect::<Vec<_>>(), [0, 3]);
+    }
+}
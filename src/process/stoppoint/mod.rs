@@ -1,11 +1,15 @@
 use anyhow::{Error, anyhow};
+use std::fmt;
+use std::sync::atomic::{AtomicI32, Ordering};
 
 use crate::process::register_info::RegisterValue;
 
+pub mod breakpoint;
 pub mod breakpoint_site;
-
-/// This is the `int3` instruction, which causes the prcoess to break/signal.
-pub const INTERRUPT_INSTRUCTION: i64 = 0xCC;
+pub mod catchpoint;
+pub mod hardware;
+pub mod stop_policy;
+pub mod watchpoint;
 
 /// A simple uniqueness identifier for a stoppoint.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -13,6 +17,16 @@ pub struct StoppointId {
     id: i32,
 }
 
+// Simple global ID generator; relaxed ordering is sufficient for a monotonic counter.
+static NEXT_ID: AtomicI32 = AtomicI32::new(1);
+
+impl StoppointId {
+    pub(crate) fn next() -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        StoppointId { id }
+    }
+}
+
 impl TryFrom<Vec<String>> for StoppointId {
     type Error = Error;
 
@@ -32,20 +46,6 @@ pub struct VirtualAddress {
     pub address: u64,
 }
 
-impl TryFrom<Vec<String>> for VirtualAddress {
-    type Error = Error;
-
-    fn try_from(v: Vec<String>) -> Result<Self, Self::Error> {
-        if v.len() != 1 {
-            return Err(anyhow!("Wrong number of arguments: {:?}", v));
-        }
-
-        let s = v.first().unwrap();
-        let address = s.parse::<u64>()?;
-        Ok(Self { address })
-    }
-}
-
 impl TryFrom<RegisterValue> for VirtualAddress {
     type Error = Error;
 
@@ -69,10 +69,29 @@ impl From<VirtualAddress> for RegisterValue {
     }
 }
 
+impl fmt::Display for VirtualAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.address)
+    }
+}
+
+impl fmt::LowerHex for VirtualAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.address, f)
+    }
+}
+
 impl VirtualAddress {
     pub fn addr(&self) -> u64 {
         self.address
     }
+
+    /// The address `delta` bytes away from this one.
+    pub fn offset(&self, delta: i64) -> Self {
+        Self {
+            address: self.address.wrapping_add_signed(delta),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -0,0 +1,59 @@
+use crate::expression::Expression;
+
+/// What a hit on a breakpoint, watchpoint or catchpoint does: whether it stops
+/// the inferior, and what's run when it does.
+#[derive(Clone, Debug, Default)]
+pub struct StopPolicy {
+    /// Number of times the stoppoint was hit (with its condition holding).
+    /// Always 0 for breakpoints, whose hits are counted by their sites.
+    hit_count: u64,
+    /// Number of upcoming hits to continue through without stopping.
+    ignore_count: u64,
+    /// Only stop if this evaluates to non-zero.
+    condition: Option<Expression>,
+    /// Debugger commands to run when the inferior stops here.
+    commands: Vec<String>,
+}
+
+impl StopPolicy {
+    pub fn hit_count(&self) -> u64 {
+        self.hit_count
+    }
+
+    pub fn record_hit(&mut self) {
+        self.hit_count += 1;
+    }
+
+    pub fn ignore_count(&self) -> u64 {
+        self.ignore_count
+    }
+
+    pub fn set_ignore_count(&mut self, count: u64) {
+        self.ignore_count = count;
+    }
+
+    /// Use up one of the hits to ignore, if there are any left.
+    pub fn take_ignored_hit(&mut self) -> bool {
+        if self.ignore_count == 0 {
+            return false;
+        }
+        self.ignore_count -= 1;
+        true
+    }
+
+    pub fn condition(&self) -> Option<&Expression> {
+        self.condition.as_ref()
+    }
+
+    pub fn set_condition(&mut self, condition: Option<Expression>) {
+        self.condition = condition;
+    }
+
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    pub fn set_commands(&mut self, commands: Vec<String>) {
+        self.commands = commands;
+    }
+}
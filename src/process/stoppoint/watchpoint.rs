@@ -0,0 +1,112 @@
+use std::fmt;
+
+use crate::process::stoppoint::breakpoint::BreakpointId;
+use crate::process::stoppoint::hardware::{HardwareMode, HardwareStoppoint};
+use crate::process::stoppoint::stop_policy::StopPolicy;
+use crate::process::stoppoint::{StoppointId, StoppointState, VirtualAddress};
+
+/// A user-visible watchpoint: stop when the inferior writes (or reads) some
+/// memory.
+///
+/// Watchpoints are numbered along with breakpoints, so `delete 2` works on
+/// either.
+#[derive(Clone, Debug)]
+pub struct Watchpoint {
+    id: BreakpointId,
+    /// Identifies the watchpoint's debug register slot in the inferior.
+    stoppoint_id: StoppointId,
+    stoppoint: HardwareStoppoint,
+    state: StoppointState,
+    /// The watched value as of the last stop, and the one before, so we can
+    /// show what changed.
+    value: Option<u64>,
+    previous_value: Option<u64>,
+    /// Hit and ignore counts, condition and commands.
+    policy: StopPolicy,
+}
+
+impl Watchpoint {
+    pub fn new(stoppoint: HardwareStoppoint) -> Self {
+        Self {
+            id: BreakpointId::next(),
+            stoppoint_id: StoppointId::next(),
+            stoppoint,
+            state: StoppointState::Disabled,
+            value: None,
+            previous_value: None,
+            policy: StopPolicy::default(),
+        }
+    }
+
+    pub fn id(&self) -> BreakpointId {
+        self.id
+    }
+
+    pub fn stoppoint_id(&self) -> StoppointId {
+        self.stoppoint_id
+    }
+
+    pub fn stoppoint(&self) -> &HardwareStoppoint {
+        &self.stoppoint
+    }
+
+    pub fn address(&self) -> VirtualAddress {
+        self.stoppoint.address
+    }
+
+    pub fn size(&self) -> usize {
+        self.stoppoint.size
+    }
+
+    pub fn mode(&self) -> HardwareMode {
+        self.stoppoint.mode
+    }
+
+    pub fn policy(&self) -> &StopPolicy {
+        &self.policy
+    }
+
+    pub fn policy_mut(&mut self) -> &mut StopPolicy {
+        &mut self.policy
+    }
+
+    pub fn enable(&mut self) {
+        self.state = StoppointState::Enabled;
+    }
+
+    pub fn disable(&mut self) {
+        self.state = StoppointState::Disabled
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        matches!(self.state, StoppointState::Enabled)
+    }
+
+    pub fn value(&self) -> Option<u64> {
+        self.value
+    }
+
+    pub fn previous_value(&self) -> Option<u64> {
+        self.previous_value
+    }
+
+    /// Record the current value of the watched memory.
+    pub fn update_value(&mut self, value: Option<u64>) {
+        self.previous_value = self.value;
+        self.value = value;
+    }
+}
+
+impl fmt::Display for Watchpoint {
+    /// e.g. `Hardware watchpoint 2: 8 bytes at 0x4010 (write)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Hardware watchpoint {}: {} bytes at {} ({})",
+            self.id,
+            self.size(),
+            self.address(),
+            self.mode()
+        )
+    }
+}
@@ -0,0 +1,288 @@
+//! The inferior's system calls: their names and numbers, and how to show
+//! their arguments and return values when a catchpoint stops on one.
+
+use anyhow::{Result, anyhow};
+use nix::errno::Errno;
+use std::fmt;
+
+use crate::process::arch::{Arch, HostArch};
+use crate::process::memory::PAGE_SIZE;
+use crate::process::stoppoint::VirtualAddress;
+
+pub(crate) mod x86_64;
+
+/// The longest string argument shown; longer ones are cut off with `...`.
+const MAX_STRING_LEN: usize = 64;
+
+/// How to show a syscall argument.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArgKind {
+    /// A signed integer, e.g. a length or a PID.
+    Int,
+    /// Flags, or a pointer we don't look behind.
+    Hex,
+    /// A file mode, e.g. `0o644`.
+    Octal,
+    /// A file descriptor, or `AT_FDCWD`.
+    Fd,
+    /// A pointer to a NUL-terminated string, which is read and shown.
+    Str,
+}
+
+/// A system call of the architecture, as in the kernel's syscall table.
+#[derive(Clone, Copy, Debug)]
+pub struct Syscall {
+    pub number: u64,
+    pub name: &'static str,
+    /// How to show each argument; the syscall takes this many.
+    pub args: &'static [ArgKind],
+}
+
+/// Look up a syscall of the host architecture by number.
+pub fn syscall_by_number(number: u64) -> Option<&'static Syscall> {
+    HostArch::SYSCALLS.iter().find(|s| s.number == number)
+}
+
+/// Look up a syscall of the host architecture by name, e.g. `openat`.
+pub fn syscall_by_name(name: &str) -> Option<&'static Syscall> {
+    HostArch::SYSCALLS.iter().find(|s| s.name == name)
+}
+
+/// Parse a syscall name or number, as given to `catch syscall`.
+pub fn parse_syscall(s: &str) -> Result<u64> {
+    if let Ok(number) = s.parse::<u64>() {
+        return Ok(number);
+    }
+    syscall_by_name(s)
+        .map(|syscall| syscall.number)
+        .ok_or_else(|| anyhow!("Unknown syscall {s:?}"))
+}
+
+/// The name of a syscall, e.g. `openat`, or `syscall 999` if it isn't in the
+/// table.
+pub fn syscall_name(number: u64) -> String {
+    match syscall_by_number(number) {
+        Some(syscall) => syscall.name.to_string(),
+        None => format!("syscall {number}"),
+    }
+}
+
+/// A syscall as it was made, from the thread's syscall entry stop. By the
+/// time it returns, the argument registers may have been overwritten (on
+/// aarch64 and riscv64 the first one holds the return value).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct SyscallEntry {
+    pub number: u64,
+    pub args: [u64; 6],
+}
+
+/// A thread stopped on its way into or out of a syscall, with the arguments
+/// decoded as the syscall was made.
+#[derive(Clone, Debug)]
+pub struct SyscallStop {
+    pub number: u64,
+    /// The arguments, ready to show.
+    pub arguments: Vec<String>,
+    /// The raw return value, if the syscall is returning rather than being
+    /// made.
+    pub returned: Option<u64>,
+}
+
+impl SyscallStop {
+    /// Decode the syscall's arguments, reading any strings they point at with
+    /// `read_memory`. Syscalls missing from the table have all six argument
+    /// registers shown in hex.
+    pub fn decode(
+        number: u64,
+        args: [u64; 6],
+        returned: Option<u64>,
+        read_memory: impl Fn(VirtualAddress, usize) -> Result<Vec<u8>>,
+    ) -> Self {
+        let kinds = syscall_by_number(number).map_or(&[ArgKind::Hex; 6][..], |s| s.args);
+        let arguments = kinds
+            .iter()
+            .zip(args)
+            .map(|(kind, value)| format_argument(*kind, value, &read_memory))
+            .collect();
+        Self {
+            number,
+            arguments,
+            returned,
+        }
+    }
+
+    pub fn is_entry(&self) -> bool {
+        self.returned.is_none()
+    }
+}
+
+impl fmt::Display for SyscallStop {
+    /// e.g. `call to openat(AT_FDCWD, "/etc/jdb.conf", 0x80000, 0o0)`, or
+    /// `returned from close(3) = 0`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let call = format!(
+            "{}({})",
+            syscall_name(self.number),
+            self.arguments.join(", ")
+        );
+        match self.returned {
+            None => write!(f, "call to {call}"),
+            Some(value) => write!(f, "returned from {call} = {}", format_return(value)),
+        }
+    }
+}
+
+fn format_argument(
+    kind: ArgKind,
+    value: u64,
+    read_memory: &impl Fn(VirtualAddress, usize) -> Result<Vec<u8>>,
+) -> String {
+    match kind {
+        ArgKind::Int => (value as i64).to_string(),
+        ArgKind::Hex => format!("{value:#x}"),
+        ArgKind::Octal => format!("{value:#o}"),
+        ArgKind::Fd => match value as i32 {
+            libc::AT_FDCWD => "AT_FDCWD".to_string(),
+            fd => fd.to_string(),
+        },
+        ArgKind::Str if value == 0 => "NULL".to_string(),
+        ArgKind::Str => {
+            read_string(VirtualAddress::from(value), read_memory).unwrap_or(format!("{value:#x}"))
+        }
+    }
+}
+
+/// Read a NUL-terminated string, quoted and escaped. It's read a page at a
+/// time, so a short string at the end of a mapping can still be read.
+fn read_string(
+    address: VirtualAddress,
+    read_memory: &impl Fn(VirtualAddress, usize) -> Result<Vec<u8>>,
+) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut truncated = true;
+    while bytes.len() < MAX_STRING_LEN {
+        let start = address.offset(bytes.len() as i64);
+        let to_page_end = PAGE_SIZE - start.addr() % PAGE_SIZE;
+        let len = (MAX_STRING_LEN - bytes.len()).min(to_page_end as usize);
+        let chunk = match read_memory(start, len) {
+            Ok(chunk) => chunk,
+            // an unreadable pointer isn't a string at all
+            Err(_) if bytes.is_empty() => return None,
+            Err(_) => break,
+        };
+        if let Some(nul) = chunk.iter().position(|b| *b == 0) {
+            bytes.extend_from_slice(&chunk[..nul]);
+            truncated = false;
+            break;
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    let quoted = format!("{:?}", String::from_utf8_lossy(&bytes));
+    Some(match truncated {
+        true => format!("{quoted}..."),
+        false => quoted,
+    })
+}
+
+/// A syscall's return value: errors are shown like `-1 ENOENT (No such file
+/// or directory)`, addresses in hex, and anything else in decimal.
+fn format_return(value: u64) -> String {
+    let signed = value as i64;
+    if !(-4095..0).contains(&signed) {
+        return match u32::try_from(value) {
+            Ok(value) => value.to_string(),
+            Err(_) => format!("{value:#x}"),
+        };
+    }
+    let errno = -signed as i32;
+    // the kernel's own codes for a syscall interrupted by a signal, which
+    // only a tracer sees
+    let restart = match errno {
+        512 => Some("ERESTARTSYS"),
+        513 => Some("ERESTARTNOINTR"),
+        514 => Some("ERESTARTNOHAND"),
+        516 => Some("ERESTART_RESTARTBLOCK"),
+        _ => None,
+    };
+    if let Some(name) = restart {
+        return format!("? {name} (to be restarted)");
+    }
+    match Errno::from_raw(errno) {
+        Errno::UnknownErrno => signed.to_string(),
+        errno => format!("-1 {errno:?} ({})", errno.desc()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_memory(_: VirtualAddress, _: usize) -> Result<Vec<u8>> {
+        Err(anyhow!("unmapped"))
+    }
+
+    #[test]
+    fn x86_64_table_is_ordered() {
+        let table = x86_64::X86_64_SYSCALLS;
+        assert!(table.windows(2).all(|w| w[0].number < w[1].number));
+        assert!(table.iter().all(|s| s.args.len() <= 6));
+        let openat = table.iter().find(|s| s.name == "openat").unwrap();
+        assert_eq!(openat.number, 257);
+        assert_eq!(table.iter().find(|s| s.number == 60).unwrap().name, "exit");
+    }
+
+    #[test]
+    fn return_values() {
+        assert_eq!(format_return(0), "0");
+        assert_eq!(format_return(3), "3");
+        assert_eq!(
+            format_return(-2i64 as u64),
+            "-1 ENOENT (No such file or directory)"
+        );
+        assert_eq!(format_return(0x7f12_3456_7000), "0x7f1234567000");
+        assert_eq!(
+            format_return(-512i64 as u64),
+            "? ERESTARTSYS (to be restarted)"
+        );
+    }
+
+    #[test]
+    fn arguments() {
+        assert_eq!(
+            format_argument(ArgKind::Fd, -100i64 as u64, &no_memory),
+            "AT_FDCWD"
+        );
+        assert_eq!(format_argument(ArgKind::Fd, 3, &no_memory), "3");
+        assert_eq!(
+            format_argument(ArgKind::Int, -1i64 as u64, &no_memory),
+            "-1"
+        );
+        assert_eq!(format_argument(ArgKind::Octal, 0o644, &no_memory), "0o644");
+        assert_eq!(format_argument(ArgKind::Str, 0, &no_memory), "NULL");
+        assert_eq!(format_argument(ArgKind::Str, 0x1000, &no_memory), "0x1000");
+    }
+
+    #[test]
+    fn strings_are_read_a_page_at_a_time() {
+        // "/etc/passwd" straddling a page boundary, with nothing mapped after
+        let memory = b"/etc/passwd\0";
+        let base = PAGE_SIZE - 4;
+        let read = |address: VirtualAddress, len: usize| {
+            let offset = (address.addr() - base) as usize;
+            if address.addr() + len as u64 > 2 * PAGE_SIZE {
+                return Err(anyhow!("unmapped"));
+            }
+            let mut data = memory.get(offset..).unwrap_or_default().to_vec();
+            data.resize(len, 0);
+            Ok(data)
+        };
+        assert_eq!(
+            read_string(VirtualAddress::from(base), &read).unwrap(),
+            "\"/etc/passwd\""
+        );
+
+        let long = |_: VirtualAddress, len: usize| Ok(vec![b'a'; len]);
+        let shown = read_string(VirtualAddress::from(0x1000), &long).unwrap();
+        assert_eq!(shown, format!("\"{}\"...", "a".repeat(MAX_STRING_LEN)));
+    }
+}
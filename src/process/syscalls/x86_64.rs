@@ -0,0 +1,386 @@
+//! The x86_64 syscall table, from the kernel's `syscall_64.tbl`. The x32
+//! syscalls (numbered from 512) aren't included.
+
+use super::ArgKind::{Fd as F, Hex as X, Int as I, Octal as O, Str as S};
+use super::{ArgKind, Syscall};
+
+const fn syscall(number: u64, name: &'static str, args: &'static [ArgKind]) -> Syscall {
+    Syscall { number, name, args }
+}
+
+pub(crate) const X86_64_SYSCALLS: &[Syscall] = &[
+    syscall(0, "read", &[F, X, I]),
+    syscall(1, "write", &[F, X, I]),
+    syscall(2, "open", &[S, X, O]),
+    syscall(3, "close", &[F]),
+    syscall(4, "stat", &[S, X]),
+    syscall(5, "fstat", &[F, X]),
+    syscall(6, "lstat", &[S, X]),
+    syscall(7, "poll", &[X, I, I]),
+    syscall(8, "lseek", &[F, I, I]),
+    syscall(9, "mmap", &[X, I, X, X, F, X]),
+    syscall(10, "mprotect", &[X, I, X]),
+    syscall(11, "munmap", &[X, I]),
+    syscall(12, "brk", &[X]),
+    syscall(13, "rt_sigaction", &[I, X, X, I]),
+    syscall(14, "rt_sigprocmask", &[I, X, X, I]),
+    syscall(15, "rt_sigreturn", &[]),
+    syscall(16, "ioctl", &[F, X, X]),
+    syscall(17, "pread64", &[F, X, I, I]),
+    syscall(18, "pwrite64", &[F, X, I, I]),
+    syscall(19, "readv", &[F, X, I]),
+    syscall(20, "writev", &[F, X, I]),
+    syscall(21, "access", &[S, X]),
+    syscall(22, "pipe", &[X]),
+    syscall(23, "select", &[I, X, X, X, X]),
+    syscall(24, "sched_yield", &[]),
+    syscall(25, "mremap", &[X, I, I, X, X]),
+    syscall(26, "msync", &[X, I, X]),
+    syscall(27, "mincore", &[X, I, X]),
+    syscall(28, "madvise", &[X, I, I]),
+    syscall(29, "shmget", &[X, I, X]),
+    syscall(30, "shmat", &[I, X, X]),
+    syscall(31, "shmctl", &[I, I, X]),
+    syscall(32, "dup", &[F]),
+    syscall(33, "dup2", &[F, F]),
+    syscall(34, "pause", &[]),
+    syscall(35, "nanosleep", &[X, X]),
+    syscall(36, "getitimer", &[I, X]),
+    syscall(37, "alarm", &[I]),
+    syscall(38, "setitimer", &[I, X, X]),
+    syscall(39, "getpid", &[]),
+    syscall(40, "sendfile", &[F, F, X, I]),
+    syscall(41, "socket", &[I, X, I]),
+    syscall(42, "connect", &[F, X, I]),
+    syscall(43, "accept", &[F, X, X]),
+    syscall(44, "sendto", &[F, X, I, X, X, I]),
+    syscall(45, "recvfrom", &[F, X, I, X, X, X]),
+    syscall(46, "sendmsg", &[F, X, X]),
+    syscall(47, "recvmsg", &[F, X, X]),
+    syscall(48, "shutdown", &[F, I]),
+    syscall(49, "bind", &[F, X, I]),
+    syscall(50, "listen", &[F, I]),
+    syscall(51, "getsockname", &[F, X, X]),
+    syscall(52, "getpeername", &[F, X, X]),
+    syscall(53, "socketpair", &[I, X, I, X]),
+    syscall(54, "setsockopt", &[F, I, I, X, I]),
+    syscall(55, "getsockopt", &[F, I, I, X, X]),
+    syscall(56, "clone", &[X, X, X, X, X]),
+    syscall(57, "fork", &[]),
+    syscall(58, "vfork", &[]),
+    syscall(59, "execve", &[S, X, X]),
+    syscall(60, "exit", &[I]),
+    syscall(61, "wait4", &[I, X, X, X]),
+    syscall(62, "kill", &[I, I]),
+    syscall(63, "uname", &[X]),
+    syscall(64, "semget", &[X, I, X]),
+    syscall(65, "semop", &[I, X, I]),
+    syscall(66, "semctl", &[I, I, I, X]),
+    syscall(67, "shmdt", &[X]),
+    syscall(68, "msgget", &[X, X]),
+    syscall(69, "msgsnd", &[I, X, I, X]),
+    syscall(70, "msgrcv", &[I, X, I, I, X]),
+    syscall(71, "msgctl", &[I, I, X]),
+    syscall(72, "fcntl", &[F, I, X]),
+    syscall(73, "flock", &[F, X]),
+    syscall(74, "fsync", &[F]),
+    syscall(75, "fdatasync", &[F]),
+    syscall(76, "truncate", &[S, I]),
+    syscall(77, "ftruncate", &[F, I]),
+    syscall(78, "getdents", &[F, X, I]),
+    syscall(79, "getcwd", &[X, I]),
+    syscall(80, "chdir", &[S]),
+    syscall(81, "fchdir", &[F]),
+    syscall(82, "rename", &[S, S]),
+    syscall(83, "mkdir", &[S, O]),
+    syscall(84, "rmdir", &[S]),
+    syscall(85, "creat", &[S, O]),
+    syscall(86, "link", &[S, S]),
+    syscall(87, "unlink", &[S]),
+    syscall(88, "symlink", &[S, S]),
+    syscall(89, "readlink", &[S, X, I]),
+    syscall(90, "chmod", &[S, O]),
+    syscall(91, "fchmod", &[F, O]),
+    syscall(92, "chown", &[S, I, I]),
+    syscall(93, "fchown", &[F, I, I]),
+    syscall(94, "lchown", &[S, I, I]),
+    syscall(95, "umask", &[O]),
+    syscall(96, "gettimeofday", &[X, X]),
+    syscall(97, "getrlimit", &[I, X]),
+    syscall(98, "getrusage", &[I, X]),
+    syscall(99, "sysinfo", &[X]),
+    syscall(100, "times", &[X]),
+    syscall(101, "ptrace", &[I, I, X, X]),
+    syscall(102, "getuid", &[]),
+    syscall(103, "syslog", &[I, X, I]),
+    syscall(104, "getgid", &[]),
+    syscall(105, "setuid", &[I]),
+    syscall(106, "setgid", &[I]),
+    syscall(107, "geteuid", &[]),
+    syscall(108, "getegid", &[]),
+    syscall(109, "setpgid", &[I, I]),
+    syscall(110, "getppid", &[]),
+    syscall(111, "getpgrp", &[]),
+    syscall(112, "setsid", &[]),
+    syscall(113, "setreuid", &[I, I]),
+    syscall(114, "setregid", &[I, I]),
+    syscall(115, "getgroups", &[I, X]),
+    syscall(116, "setgroups", &[I, X]),
+    syscall(117, "setresuid", &[I, I, I]),
+    syscall(118, "getresuid", &[X, X, X]),
+    syscall(119, "setresgid", &[I, I, I]),
+    syscall(120, "getresgid", &[X, X, X]),
+    syscall(121, "getpgid", &[I]),
+    syscall(122, "setfsuid", &[I]),
+    syscall(123, "setfsgid", &[I]),
+    syscall(124, "getsid", &[I]),
+    syscall(125, "capget", &[X, X]),
+    syscall(126, "capset", &[X, X]),
+    syscall(127, "rt_sigpending", &[X, I]),
+    syscall(128, "rt_sigtimedwait", &[X, X, X, I]),
+    syscall(129, "rt_sigqueueinfo", &[I, I, X]),
+    syscall(130, "rt_sigsuspend", &[X, I]),
+    syscall(131, "sigaltstack", &[X, X]),
+    syscall(132, "utime", &[S, X]),
+    syscall(133, "mknod", &[S, O, X]),
+    syscall(134, "uselib", &[S]),
+    syscall(135, "personality", &[X]),
+    syscall(136, "ustat", &[X, X]),
+    syscall(137, "statfs", &[S, X]),
+    syscall(138, "fstatfs", &[F, X]),
+    syscall(139, "sysfs", &[I, X, X]),
+    syscall(140, "getpriority", &[I, I]),
+    syscall(141, "setpriority", &[I, I, I]),
+    syscall(142, "sched_setparam", &[I, X]),
+    syscall(143, "sched_getparam", &[I, X]),
+    syscall(144, "sched_setscheduler", &[I, I, X]),
+    syscall(145, "sched_getscheduler", &[I]),
+    syscall(146, "sched_get_priority_max", &[I]),
+    syscall(147, "sched_get_priority_min", &[I]),
+    syscall(148, "sched_rr_get_interval", &[I, X]),
+    syscall(149, "mlock", &[X, I]),
+    syscall(150, "munlock", &[X, I]),
+    syscall(151, "mlockall", &[X]),
+    syscall(152, "munlockall", &[]),
+    syscall(153, "vhangup", &[]),
+    syscall(154, "modify_ldt", &[I, X, I]),
+    syscall(155, "pivot_root", &[S, S]),
+    syscall(156, "_sysctl", &[X]),
+    syscall(157, "prctl", &[I, X, X, X, X]),
+    syscall(158, "arch_prctl", &[X, X]),
+    syscall(159, "adjtimex", &[X]),
+    syscall(160, "setrlimit", &[I, X]),
+    syscall(161, "chroot", &[S]),
+    syscall(162, "sync", &[]),
+    syscall(163, "acct", &[S]),
+    syscall(164, "settimeofday", &[X, X]),
+    syscall(165, "mount", &[S, S, S, X, X]),
+    syscall(166, "umount2", &[S, X]),
+    syscall(167, "swapon", &[S, X]),
+    syscall(168, "swapoff", &[S]),
+    syscall(169, "reboot", &[X, X, X, X]),
+    syscall(170, "sethostname", &[X, I]),
+    syscall(171, "setdomainname", &[X, I]),
+    syscall(172, "iopl", &[I]),
+    syscall(173, "ioperm", &[X, I, I]),
+    syscall(174, "create_module", &[]),
+    syscall(175, "init_module", &[X, I, S]),
+    syscall(176, "delete_module", &[S, X]),
+    syscall(177, "get_kernel_syms", &[]),
+    syscall(178, "query_module", &[]),
+    syscall(179, "quotactl", &[X, S, I, X]),
+    syscall(180, "nfsservctl", &[]),
+    syscall(181, "getpmsg", &[]),
+    syscall(182, "putpmsg", &[]),
+    syscall(183, "afs_syscall", &[]),
+    syscall(184, "tuxcall", &[]),
+    syscall(185, "security", &[]),
+    syscall(186, "gettid", &[]),
+    syscall(187, "readahead", &[F, I, I]),
+    syscall(188, "setxattr", &[S, S, X, I, X]),
+    syscall(189, "lsetxattr", &[S, S, X, I, X]),
+    syscall(190, "fsetxattr", &[F, S, X, I, X]),
+    syscall(191, "getxattr", &[S, S, X, I]),
+    syscall(192, "lgetxattr", &[S, S, X, I]),
+    syscall(193, "fgetxattr", &[F, S, X, I]),
+    syscall(194, "listxattr", &[S, X, I]),
+    syscall(195, "llistxattr", &[S, X, I]),
+    syscall(196, "flistxattr", &[F, X, I]),
+    syscall(197, "removexattr", &[S, S]),
+    syscall(198, "lremovexattr", &[S, S]),
+    syscall(199, "fremovexattr", &[F, S]),
+    syscall(200, "tkill", &[I, I]),
+    syscall(201, "time", &[X]),
+    syscall(202, "futex", &[X, I, I, X, X, I]),
+    syscall(203, "sched_setaffinity", &[I, I, X]),
+    syscall(204, "sched_getaffinity", &[I, I, X]),
+    syscall(205, "set_thread_area", &[X]),
+    syscall(206, "io_setup", &[I, X]),
+    syscall(207, "io_destroy", &[X]),
+    syscall(208, "io_getevents", &[X, I, I, X, X]),
+    syscall(209, "io_submit", &[X, I, X]),
+    syscall(210, "io_cancel", &[X, X, X]),
+    syscall(211, "get_thread_area", &[X]),
+    syscall(212, "lookup_dcookie", &[X, X, I]),
+    syscall(213, "epoll_create", &[I]),
+    syscall(214, "epoll_ctl_old", &[]),
+    syscall(215, "epoll_wait_old", &[]),
+    syscall(216, "remap_file_pages", &[X, I, X, I, X]),
+    syscall(217, "getdents64", &[F, X, I]),
+    syscall(218, "set_tid_address", &[X]),
+    syscall(219, "restart_syscall", &[]),
+    syscall(220, "semtimedop", &[I, X, I, X]),
+    syscall(221, "fadvise64", &[F, I, I, I]),
+    syscall(222, "timer_create", &[I, X, X]),
+    syscall(223, "timer_settime", &[I, X, X, X]),
+    syscall(224, "timer_gettime", &[I, X]),
+    syscall(225, "timer_getoverrun", &[I]),
+    syscall(226, "timer_delete", &[I]),
+    syscall(227, "clock_settime", &[I, X]),
+    syscall(228, "clock_gettime", &[I, X]),
+    syscall(229, "clock_getres", &[I, X]),
+    syscall(230, "clock_nanosleep", &[I, X, X, X]),
+    syscall(231, "exit_group", &[I]),
+    syscall(232, "epoll_wait", &[F, X, I, I]),
+    syscall(233, "epoll_ctl", &[F, I, F, X]),
+    syscall(234, "tgkill", &[I, I, I]),
+    syscall(235, "utimes", &[S, X]),
+    syscall(236, "vserver", &[]),
+    syscall(237, "mbind", &[X, I, I, X, I, X]),
+    syscall(238, "set_mempolicy", &[I, X, I]),
+    syscall(239, "get_mempolicy", &[X, X, I, X, X]),
+    syscall(240, "mq_open", &[S, X, O, X]),
+    syscall(241, "mq_unlink", &[S]),
+    syscall(242, "mq_timedsend", &[F, X, I, I, X]),
+    syscall(243, "mq_timedreceive", &[F, X, I, X, X]),
+    syscall(244, "mq_notify", &[F, X]),
+    syscall(245, "mq_getsetattr", &[F, X, X]),
+    syscall(246, "kexec_load", &[X, I, X, X]),
+    syscall(247, "waitid", &[I, I, X, X, X]),
+    syscall(248, "add_key", &[S, S, X, I, I]),
+    syscall(249, "request_key", &[S, S, S, I]),
+    syscall(250, "keyctl", &[I, X, X, X, X]),
+    syscall(251, "ioprio_set", &[I, I, I]),
+    syscall(252, "ioprio_get", &[I, I]),
+    syscall(253, "inotify_init", &[]),
+    syscall(254, "inotify_add_watch", &[F, S, X]),
+    syscall(255, "inotify_rm_watch", &[F, I]),
+    syscall(256, "migrate_pages", &[I, I, X, X]),
+    syscall(257, "openat", &[F, S, X, O]),
+    syscall(258, "mkdirat", &[F, S, O]),
+    syscall(259, "mknodat", &[F, S, O, X]),
+    syscall(260, "fchownat", &[F, S, I, I, X]),
+    syscall(261, "futimesat", &[F, S, X]),
+    syscall(262, "newfstatat", &[F, S, X, X]),
+    syscall(263, "unlinkat", &[F, S, X]),
+    syscall(264, "renameat", &[F, S, F, S]),
+    syscall(265, "linkat", &[F, S, F, S, X]),
+    syscall(266, "symlinkat", &[S, F, S]),
+    syscall(267, "readlinkat", &[F, S, X, I]),
+    syscall(268, "fchmodat", &[F, S, O]),
+    syscall(269, "faccessat", &[F, S, X]),
+    syscall(270, "pselect6", &[I, X, X, X, X, X]),
+    syscall(271, "ppoll", &[X, I, X, X, I]),
+    syscall(272, "unshare", &[X]),
+    syscall(273, "set_robust_list", &[X, I]),
+    syscall(274, "get_robust_list", &[I, X, X]),
+    syscall(275, "splice", &[F, X, F, X, I, X]),
+    syscall(276, "tee", &[F, F, I, X]),
+    syscall(277, "sync_file_range", &[F, I, I, X]),
+    syscall(278, "vmsplice", &[F, X, I, X]),
+    syscall(279, "move_pages", &[I, I, X, X, X, X]),
+    syscall(280, "utimensat", &[F, S, X, X]),
+    syscall(281, "epoll_pwait", &[F, X, I, I, X, I]),
+    syscall(282, "signalfd", &[F, X, I]),
+    syscall(283, "timerfd_create", &[I, X]),
+    syscall(284, "eventfd", &[I]),
+    syscall(285, "fallocate", &[F, X, I, I]),
+    syscall(286, "timerfd_settime", &[F, X, X, X]),
+    syscall(287, "timerfd_gettime", &[F, X]),
+    syscall(288, "accept4", &[F, X, X, X]),
+    syscall(289, "signalfd4", &[F, X, I, X]),
+    syscall(290, "eventfd2", &[I, X]),
+    syscall(291, "epoll_create1", &[X]),
+    syscall(292, "dup3", &[F, F, X]),
+    syscall(293, "pipe2", &[X, X]),
+    syscall(294, "inotify_init1", &[X]),
+    syscall(295, "preadv", &[F, X, I, I, I]),
+    syscall(296, "pwritev", &[F, X, I, I, I]),
+    syscall(297, "rt_tgsigqueueinfo", &[I, I, I, X]),
+    syscall(298, "perf_event_open", &[X, I, I, F, X]),
+    syscall(299, "recvmmsg", &[F, X, I, X, X]),
+    syscall(300, "fanotify_init", &[X, X]),
+    syscall(301, "fanotify_mark", &[F, X, X, F, S]),
+    syscall(302, "prlimit64", &[I, I, X, X]),
+    syscall(303, "name_to_handle_at", &[F, S, X, X, X]),
+    syscall(304, "open_by_handle_at", &[F, X, X]),
+    syscall(305, "clock_adjtime", &[I, X]),
+    syscall(306, "syncfs", &[F]),
+    syscall(307, "sendmmsg", &[F, X, I, X]),
+    syscall(308, "setns", &[F, X]),
+    syscall(309, "getcpu", &[X, X, X]),
+    syscall(310, "process_vm_readv", &[I, X, I, X, I, X]),
+    syscall(311, "process_vm_writev", &[I, X, I, X, I, X]),
+    syscall(312, "kcmp", &[I, I, I, X, X]),
+    syscall(313, "finit_module", &[F, S, X]),
+    syscall(314, "sched_setattr", &[I, X, X]),
+    syscall(315, "sched_getattr", &[I, X, I, X]),
+    syscall(316, "renameat2", &[F, S, F, S, X]),
+    syscall(317, "seccomp", &[I, X, X]),
+    syscall(318, "getrandom", &[X, I, X]),
+    syscall(319, "memfd_create", &[S, X]),
+    syscall(320, "kexec_file_load", &[F, F, I, S, X]),
+    syscall(321, "bpf", &[I, X, I]),
+    syscall(322, "execveat", &[F, S, X, X, X]),
+    syscall(323, "userfaultfd", &[X]),
+    syscall(324, "membarrier", &[I, X, I]),
+    syscall(325, "mlock2", &[X, I, X]),
+    syscall(326, "copy_file_range", &[F, X, F, X, I, X]),
+    syscall(327, "preadv2", &[F, X, I, I, I, X]),
+    syscall(328, "pwritev2", &[F, X, I, I, I, X]),
+    syscall(329, "pkey_mprotect", &[X, I, X, I]),
+    syscall(330, "pkey_alloc", &[X, X]),
+    syscall(331, "pkey_free", &[I]),
+    syscall(332, "statx", &[F, S, X, X, X]),
+    syscall(333, "io_pgetevents", &[X, I, I, X, X, X]),
+    syscall(334, "rseq", &[X, I, X, X]),
+    syscall(424, "pidfd_send_signal", &[F, I, X, X]),
+    syscall(425, "io_uring_setup", &[I, X]),
+    syscall(426, "io_uring_enter", &[F, I, I, X, X, I]),
+    syscall(427, "io_uring_register", &[F, I, X, I]),
+    syscall(428, "open_tree", &[F, S, X]),
+    syscall(429, "move_mount", &[F, S, F, S, X]),
+    syscall(430, "fsopen", &[S, X]),
+    syscall(431, "fsconfig", &[F, I, S, X, I]),
+    syscall(432, "fsmount", &[F, X, X]),
+    syscall(433, "fspick", &[F, S, X]),
+    syscall(434, "pidfd_open", &[I, X]),
+    syscall(435, "clone3", &[X, I]),
+    syscall(436, "close_range", &[I, I, X]),
+    syscall(437, "openat2", &[F, S, X, I]),
+    syscall(438, "pidfd_getfd", &[F, I, X]),
+    syscall(439, "faccessat2", &[F, S, X, X]),
+    syscall(440, "process_madvise", &[F, X, I, I, X]),
+    syscall(441, "epoll_pwait2", &[F, X, I, X, X, I]),
+    syscall(442, "mount_setattr", &[F, S, X, X, I]),
+    syscall(443, "quotactl_fd", &[F, X, I, X]),
+    syscall(444, "landlock_create_ruleset", &[X, I, X]),
+    syscall(445, "landlock_add_rule", &[F, I, X, X]),
+    syscall(446, "landlock_restrict_self", &[F, X]),
+    syscall(447, "memfd_secret", &[X]),
+    syscall(448, "process_mrelease", &[F, X]),
+    syscall(449, "futex_waitv", &[X, I, X, X, I]),
+    syscall(450, "set_mempolicy_home_node", &[X, I, I, X]),
+    syscall(451, "cachestat", &[F, X, X, X]),
+    syscall(452, "fchmodat2", &[F, S, O, X]),
+    syscall(453, "map_shadow_stack", &[X, I, X]),
+    syscall(454, "futex_wake", &[X, X, I, X]),
+    syscall(455, "futex_wait", &[X, I, X, X, X, I]),
+    syscall(456, "futex_requeue", &[X, X, I, I]),
+    syscall(457, "statmount", &[X, X, I, X]),
+    syscall(458, "listmount", &[X, X, I, X]),
+    syscall(459, "lsm_get_self_attr", &[I, X, X, X]),
+    syscall(460, "lsm_set_self_attr", &[I, X, I, X]),
+    syscall(461, "lsm_list_modules", &[X, X, X]),
+    syscall(462, "mseal", &[X, I, X]),
+];
@@ -0,0 +1,161 @@
+//! The threads of the inferior, and what the debugger knows about each.
+
+use anyhow::Result;
+use nix::errno::Errno;
+use nix::libc;
+use nix::sys::signal::Signal;
+use nix::unistd::Pid;
+use std::fmt;
+use tracing::trace;
+
+use crate::process::StopReason;
+use crate::process::registers::RegisterSnapshot;
+use crate::process::syscalls::SyscallEntry;
+
+/// What a thread of the inferior is doing, as far as the debugger knows.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ThreadState {
+    Running,
+    Stopped,
+    /// The whole inferior has exited. A thread that exits while the rest of
+    /// the inferior keeps running is simply forgotten.
+    Exited,
+}
+
+impl fmt::Display for ThreadState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThreadState::Running => write!(f, "running"),
+            ThreadState::Stopped => write!(f, "stopped"),
+            ThreadState::Exited => write!(f, "exited"),
+        }
+    }
+}
+
+/// A thread of the inferior. The thread group leader's thread ID is the
+/// inferior's PID.
+#[derive(Debug)]
+pub struct Thread {
+    /// The process the thread belongs to.
+    pid: Pid,
+    tid: Pid,
+    /// The thread's name, as of its last stop.
+    name: Option<String>,
+    state: ThreadState,
+    /// The thread's registers as of its last stop.
+    registers: Option<RegisterSnapshot>,
+    /// Why the thread last stopped.
+    stop_reason: Option<StopReason>,
+    /// We sent the thread a `SIGSTOP`, to stop it along with the others, but it
+    /// stopped for some other reason first. The `SIGSTOP` is reported once the
+    /// thread is resumed, and is swallowed then.
+    pending_sigstop: bool,
+    /// The syscall the thread last made, while catching syscalls, to decode
+    /// again when it returns.
+    syscall_entry: Option<SyscallEntry>,
+}
+
+impl Thread {
+    pub(super) fn new(pid: Pid, tid: Pid, state: ThreadState) -> Self {
+        Self {
+            pid,
+            tid,
+            name: None,
+            state,
+            registers: None,
+            stop_reason: None,
+            pending_sigstop: false,
+            syscall_entry: None,
+        }
+    }
+
+    pub fn tid(&self) -> Pid {
+        self.tid
+    }
+
+    /// The thread's name (e.g. from `pthread_setname_np`), which defaults to
+    /// the executable's name.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn state(&self) -> ThreadState {
+        self.state
+    }
+
+    pub fn stop_reason(&self) -> Option<&StopReason> {
+        self.stop_reason.as_ref()
+    }
+
+    pub(super) fn registers(&self) -> Option<&RegisterSnapshot> {
+        self.registers.as_ref()
+    }
+
+    pub(super) fn registers_mut(&mut self) -> Option<&mut RegisterSnapshot> {
+        self.registers.as_mut()
+    }
+
+    pub(super) fn set_running(&mut self) {
+        self.state = ThreadState::Running;
+    }
+
+    pub(super) fn set_exited(&mut self) {
+        self.state = ThreadState::Exited;
+    }
+
+    /// Record that the thread stopped, with its registers at the stop. Its
+    /// name may have changed since it last stopped, so that's read too.
+    pub(super) fn set_stopped(&mut self, registers: RegisterSnapshot, reason: StopReason) {
+        self.state = ThreadState::Stopped;
+        self.registers = Some(registers);
+        self.stop_reason = Some(reason);
+        let comm = format!("/proc/{}/task/{}/comm", self.pid, self.tid);
+        match std::fs::read_to_string(comm) {
+            Ok(name) => self.name = Some(name.trim_end().to_string()),
+            Err(e) => trace!(tid = ?self.tid, "Cannot read thread name: {e}"),
+        }
+    }
+
+    pub(super) fn pending_sigstop(&self) -> bool {
+        self.pending_sigstop
+    }
+
+    pub(super) fn set_pending_sigstop(&mut self, pending: bool) {
+        self.pending_sigstop = pending;
+    }
+
+    pub(super) fn syscall_entry(&self) -> Option<SyscallEntry> {
+        self.syscall_entry
+    }
+
+    pub(super) fn set_syscall_entry(&mut self, entry: SyscallEntry) {
+        self.syscall_entry = Some(entry);
+    }
+}
+
+/// Send a signal to a single thread of the process, rather than to whichever
+/// thread the kernel picks (as `kill` does).
+pub(super) fn tgkill(pid: Pid, tid: Pid, signal: Signal) -> Result<()> {
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_tgkill,
+            pid.as_raw(),
+            tid.as_raw(),
+            signal as libc::c_int,
+        )
+    };
+    Errno::result(res)?;
+    Ok(())
+}
+
+/// The IDs of the process' threads, from `/proc/<pid>/task`.
+pub(super) fn list_threads(pid: Pid) -> Result<Vec<Pid>> {
+    let mut tids = Vec::new();
+    for entry in std::fs::read_dir(format!("/proc/{pid}/task"))? {
+        if let Some(tid) = entry?.file_name().to_str().and_then(|s| s.parse().ok()) {
+            tids.push(Pid::from_raw(tid));
+        }
+    }
+    tids.sort();
+    Ok(tids)
+}
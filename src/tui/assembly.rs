@@ -0,0 +1,75 @@
+//! State for the assembly pane: the instructions around the current PC.
+
+use tracing::trace;
+
+use crate::disassemble::Instruction;
+use crate::process::Process;
+use crate::process::stoppoint::VirtualAddress;
+use crate::tui::scroll::ScrollState;
+
+/// How far before the PC we're willing to start disassembling, to show some
+/// context from the start of the function.
+const MAX_DISASSEMBLY_LEAD: u64 = 4096;
+/// Number of instructions shown past the PC.
+const DISASSEMBLY_COUNT: usize = 128;
+
+/// Disassemble from the start of the function containing `pc` (as x86 can't be
+/// decoded backwards), or from `pc` itself if that's too far back.
+pub fn disassemble_around(process: &Process, pc: VirtualAddress) -> Option<Vec<Instruction>> {
+    let mut instructions = match process.disassemble(pc, DISASSEMBLY_COUNT) {
+        Ok(instructions) => instructions,
+        Err(e) => {
+            trace!(?pc, "Cannot disassemble: {e}");
+            return None;
+        }
+    };
+
+    let Some((start, _)) = process
+        .function_bounds(pc)
+        .filter(|(start, _)| *start < pc && pc.addr() - start.addr() <= MAX_DISASSEMBLY_LEAD)
+    else {
+        return Some(instructions);
+    };
+    // if decoding from the start doesn't land on the PC (e.g. the symbol is
+    // really data) the leading instructions are nonsense, so leave them out
+    if let Ok(mut leading) = process.disassemble_range(start, pc)
+        && leading
+            .last()
+            .is_some_and(|inst| inst.address.offset(inst.bytes.len() as i64) == pc)
+    {
+        leading.append(&mut instructions);
+        instructions = leading;
+    }
+    Some(instructions)
+}
+
+#[derive(Debug, Default)]
+pub struct AssemblyPaneState {
+    /// The PC the instructions were disassembled for.
+    pc: Option<VirtualAddress>,
+    instructions: Vec<Instruction>,
+    pub scroll: ScrollState,
+}
+
+impl AssemblyPaneState {
+    /// Bring the instructions up to date with the process' current PC.
+    pub fn sync(&mut self, process: &Process) {
+        let pc = process.pc();
+        if pc == self.pc {
+            return;
+        }
+        self.pc = pc;
+        self.scroll.recenter();
+        self.instructions = pc
+            .and_then(|pc| disassemble_around(process, pc))
+            .unwrap_or_default();
+    }
+
+    pub fn pc(&self) -> Option<VirtualAddress> {
+        self.pc
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+}
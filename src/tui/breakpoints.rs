@@ -0,0 +1,63 @@
+//! State for the breakpoints pane: the breakpoint list, and the selected row.
+
+use crate::debugger::BreakpointCommand;
+use crate::debugger::info::{BreakpointRow, breakpoint_rows};
+use crate::process::Process;
+use crate::tui::scroll::ScrollState;
+
+#[derive(Debug, Default)]
+pub struct BreakpointsPaneState {
+    rows: Vec<BreakpointRow>,
+    /// The process' generation the rows were built for.
+    generation: Option<u64>,
+    /// Index of the selected row.
+    selected: usize,
+    pub scroll: ScrollState,
+}
+
+impl BreakpointsPaneState {
+    /// Bring the list up to date with the process' breakpoints.
+    pub fn sync(&mut self, process: &Process) {
+        if self.generation == Some(process.generation()) {
+            return;
+        }
+        self.generation = Some(process.generation());
+        self.rows = breakpoint_rows(process);
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn rows(&self) -> &[BreakpointRow] {
+        &self.rows
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Move the selection down (or up) a row, keeping it in view.
+    pub fn select_next(&mut self, forward: bool) {
+        self.selected = match forward {
+            true => (self.selected + 1).min(self.rows.len().saturating_sub(1)),
+            false => self.selected.saturating_sub(1),
+        };
+        self.scroll.recenter();
+    }
+
+    /// Enable or disable the breakpoint the selected row belongs to (all its
+    /// locations, for a location row).
+    pub fn toggle_selected(&self) -> Option<BreakpointCommand> {
+        let id = self.rows.get(self.selected)?.id;
+        // the breakpoint's own row comes before its locations
+        let breakpoint = self.rows.iter().find(|row| row.id == id)?;
+        match breakpoint.enabled {
+            true => Some(BreakpointCommand::Disable(id)),
+            false => Some(BreakpointCommand::Enable(id)),
+        }
+    }
+
+    /// Delete the breakpoint the selected row belongs to.
+    pub fn delete_selected(&self) -> Option<BreakpointCommand> {
+        let row = self.rows.get(self.selected)?;
+        Some(BreakpointCommand::Delete(row.id))
+    }
+}
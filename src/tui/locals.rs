@@ -0,0 +1,98 @@
+//! State for the locals pane: a tab for each group of registers, with the
+//! ones that changed since the previous stop highlighted.
+
+use nix::unistd::Pid;
+use std::collections::{HashMap, HashSet};
+use strum::IntoEnumIterator;
+
+use crate::process::Process;
+use crate::process::register_info::{RegisterType, registers_info_iter};
+use crate::process::thread::Thread;
+use crate::tui::scroll::ScrollState;
+use crate::tui::{LocalsPaneMode, next_index};
+
+/// A register as shown in the pane.
+#[derive(Debug)]
+pub struct RegisterLine<'a> {
+    pub name: &'static str,
+    pub value: &'a str,
+    /// Whether the value changed when the inferior last ran.
+    pub changed: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct LocalsPaneState {
+    mode: LocalsPaneMode,
+    /// The formatted value of each register, as of the last sync.
+    values: HashMap<&'static str, String>,
+    /// The registers whose values changed the last time any did.
+    changed: HashSet<&'static str>,
+    /// The thread whose registers these are.
+    thread: Option<Pid>,
+    /// The process' generation the values were read at.
+    generation: Option<u64>,
+    pub scroll: ScrollState,
+}
+
+impl LocalsPaneState {
+    /// Bring the register values up to date with the process.
+    pub fn sync(&mut self, process: &Process) {
+        if self.generation == Some(process.generation()) {
+            return;
+        }
+        self.generation = Some(process.generation());
+        let values: HashMap<_, _> = registers_info_iter()
+            .filter(|info| !matches!(info.register_type, RegisterType::SubGeneralPurpose))
+            .filter_map(|info| {
+                let value = process.read_register(info.register)?;
+                Some((info.name, value.to_string()))
+            })
+            .collect();
+        let thread = process.current_thread().map(Thread::tid);
+        if values == self.values && thread == self.thread {
+            return;
+        }
+
+        // nothing has changed on the first stop, there's nothing to compare to,
+        // and another thread's registers are no comparison either
+        self.changed = match self.values.is_empty() || thread != self.thread {
+            true => HashSet::new(),
+            false => values
+                .iter()
+                .filter(|(name, value)| self.values.get(*name) != Some(value))
+                .map(|(name, _)| *name)
+                .collect(),
+        };
+        self.values = values;
+        self.thread = thread;
+    }
+
+    pub fn mode(&self) -> LocalsPaneMode {
+        self.mode
+    }
+
+    /// Switch to the next (or previous) tab.
+    pub fn next_tab(&mut self, forward: bool) {
+        let idx = next_index(LocalsPaneMode::iter().count(), self.mode as usize, forward);
+        self.mode = LocalsPaneMode::from_repr(idx).unwrap_or_default();
+        self.scroll.recenter();
+    }
+
+    /// The registers on the current tab, in the order of the register table.
+    pub fn registers(&self) -> Vec<RegisterLine<'_>> {
+        let Some(register_type) = self.mode.register_type() else {
+            return Vec::new();
+        };
+        registers_info_iter()
+            .filter(|info| info.register_type == register_type)
+            .filter_map(|info| {
+                let value = self.values.get(info.name)?;
+                Some(RegisterLine {
+                    name: info.name,
+                    value,
+                    changed: self.changed.contains(info.name),
+                })
+            })
+            .collect()
+    }
+}
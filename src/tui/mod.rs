@@ -16,9 +16,26 @@ use strum::{Display, EnumIter, FromRepr};
 use tracing::{debug, error, trace};
 use tui_logger::{TuiWidgetEvent, TuiWidgetState};
 
-use crate::{JdbEvent, debugger::Debugger, process::Process, tui::render::render_screen};
+use crate::{
+    JdbEvent,
+    debugger::{
+        BreakpointCommand, Debugger, clear_interrupt, request_interrupt, thread::ThreadCommand,
+    },
+    process::{Process, register_info::RegisterType},
+    tui::{
+        assembly::AssemblyPaneState, breakpoints::BreakpointsPaneState, locals::LocalsPaneState,
+        render::render_screen, scroll::ScrollState, source::SourcePaneState,
+        threads::ThreadsPaneState,
+    },
+};
 
+mod assembly;
+mod breakpoints;
+mod locals;
 mod render;
+mod scroll;
+mod source;
+mod threads;
 
 fn next_index(len: usize, cur_idx: usize, increment: bool) -> usize {
     if increment {
@@ -39,6 +56,7 @@ pub enum DebuggerPane {
     Locals,
     Logs,
     Source,
+    Threads,
     Watchpoints,
 }
 
@@ -52,6 +70,7 @@ impl DebuggerPane {
             Locals => "locals",
             Logs => "logs",
             Source => "source",
+            Threads => "threads",
             Watchpoints => "watchpoints",
         };
         name.to_string()
@@ -68,22 +87,40 @@ pub struct DebuggerState {
     command_input: String,
     /// Last response emitted after running a command (shown in echo area).
     last_command_response: Option<String>,
+    /// What the source pane is showing.
+    source: SourcePaneState,
+    /// What the assembly pane is showing.
+    assembly: AssemblyPaneState,
+    /// The locals pane's tab, and the register values it shows.
+    locals: LocalsPaneState,
+    /// The breakpoint list, and which one is selected.
+    breakpoints: BreakpointsPaneState,
+    /// The thread list.
+    threads: ThreadsPaneState,
 }
 
 impl Default for DebuggerState {
     fn default() -> Self {
         let panes = vec![
             DebuggerPane::Source,
+            DebuggerPane::Assembly,
             DebuggerPane::Locals,
+            DebuggerPane::Threads,
             DebuggerPane::Logs,
+            DebuggerPane::Breakpoints,
             DebuggerPane::Command,
         ];
 
         DebuggerState {
             panes,
-            focus_pane_idx: 3,
+            focus_pane_idx: 6,
             command_input: String::new(),
             last_command_response: None,
+            source: Default::default(),
+            assembly: Default::default(),
+            locals: Default::default(),
+            breakpoints: Default::default(),
+            threads: Default::default(),
         }
     }
 }
@@ -147,6 +184,39 @@ impl DebuggerState {
     pub fn last_command_response(&self) -> Option<&str> {
         self.last_command_response.as_deref()
     }
+
+    fn source_state(&self) -> &SourcePaneState {
+        &self.source
+    }
+
+    fn assembly_state(&self) -> &AssemblyPaneState {
+        &self.assembly
+    }
+
+    fn locals_state(&self) -> &LocalsPaneState {
+        &self.locals
+    }
+
+    fn breakpoints_state(&self) -> &BreakpointsPaneState {
+        &self.breakpoints
+    }
+
+    fn threads_state(&self) -> &ThreadsPaneState {
+        &self.threads
+    }
+
+    /// The scroll state of the focused pane, if it scrolls.
+    fn focused_scroll_mut(&mut self) -> Option<&mut ScrollState> {
+        if self.is_focus(&DebuggerPane::Source) {
+            Some(&mut self.source.scroll)
+        } else if self.is_focus(&DebuggerPane::Assembly) {
+            Some(&mut self.assembly.scroll)
+        } else if self.is_focus(&DebuggerPane::Locals) {
+            Some(&mut self.locals.scroll)
+        } else {
+            None
+        }
+    }
 }
 
 pub struct DebuggerLogScreenState {
@@ -208,8 +278,7 @@ pub enum ScreenMode {
 }
 
 /// Enum of the panes within the Locals pane.
-#[allow(dead_code)]
-#[derive(Default, Clone, Copy, Display, FromRepr, EnumIter)]
+#[derive(Default, Clone, Copy, Debug, Display, FromRepr, EnumIter)]
 pub enum LocalsPaneMode {
     #[strum(to_string = "Variables")]
     Variables,
@@ -218,11 +287,23 @@ pub enum LocalsPaneMode {
     // TODO: default to variables, once I actually get to that point.
     GeneralPurposeRegisters,
     #[strum(to_string = "FP Regs")]
-    FloatingPoointRegisters,
+    FloatingPointRegisters,
     #[strum(to_string = "Debug Regs")]
     DebugRegisters,
 }
 
+impl LocalsPaneMode {
+    /// The registers shown on this tab, if it's a register tab.
+    fn register_type(&self) -> Option<RegisterType> {
+        match self {
+            LocalsPaneMode::Variables => None,
+            LocalsPaneMode::GeneralPurposeRegisters => Some(RegisterType::GeneralPurpose),
+            LocalsPaneMode::FloatingPointRegisters => Some(RegisterType::FloatingPoint),
+            LocalsPaneMode::DebugRegisters => Some(RegisterType::Debug),
+        }
+    }
+}
+
 /// The central nexus of state of the various screens for the TUI.
 struct TuiState {
     debugger_state: DebuggerState,
@@ -250,7 +331,13 @@ pub struct Tui {
 
 pub enum EventResult {
     Normal,
-    Editor { command: String },
+    Editor {
+        command: String,
+    },
+    /// A breakpoint was toggled or deleted from the breakpoints pane.
+    Breakpoint(BreakpointCommand),
+    /// Another thread was selected in the threads pane.
+    Thread(ThreadCommand),
     Quit,
 }
 
@@ -285,6 +372,11 @@ impl Tui {
 
     /// Render the TUI
     pub fn render(&mut self, debugger: &Debugger, process: &Process) -> Result<()> {
+        self.state.debugger_state.source.sync(process);
+        self.state.debugger_state.assembly.sync(process);
+        self.state.debugger_state.locals.sync(process);
+        self.state.debugger_state.breakpoints.sync(process);
+        self.state.debugger_state.threads.sync(process);
         match self
             .terminal
             .draw(|frame| render_screen(&self.state, debugger, process, frame))
@@ -317,6 +409,12 @@ impl Tui {
     }
 
     pub fn handle_key_press(&mut self, key: KeyEvent) -> Result<EventResult> {
+        // the terminal thread has already asked whatever was running to stop,
+        // so by now there's nothing left to interrupt
+        if is_interrupt(&key) {
+            clear_interrupt();
+            return Ok(EventResult::Normal);
+        }
         // handle Fn keys before everything as that will switch screens
         if let KeyCode::F(fkey_num) = key.code {
             return self.handle_function_key(fkey_num);
@@ -365,6 +463,14 @@ fn debugger_screen_key_press(state: &mut DebuggerState, key: KeyEvent) -> Result
                 _ => {}
             }
         }
+    } else if state.is_focus(&DebuggerPane::Breakpoints)
+        && let Some(result) = breakpoints_key_press(&mut state.breakpoints, key)
+    {
+        ret_code = result;
+    } else if state.is_focus(&DebuggerPane::Threads)
+        && let Some(result) = threads_key_press(&mut state.threads, key)
+    {
+        ret_code = result;
     } else {
         match key.code {
             KeyCode::Char(c) => match c {
@@ -377,24 +483,80 @@ fn debugger_screen_key_press(state: &mut DebuggerState, key: KeyEvent) -> Result
                 's' => {
                     state.set_focus(&DebuggerPane::Source);
                 }
+                'a' => {
+                    state.set_focus(&DebuggerPane::Assembly);
+                }
                 'l' => {
                     state.set_focus(&DebuggerPane::Locals);
                 }
                 'o' => {
                     state.set_focus(&DebuggerPane::Logs);
                 }
+                'b' => {
+                    state.set_focus(&DebuggerPane::Breakpoints);
+                }
+                't' => {
+                    state.set_focus(&DebuggerPane::Threads);
+                }
                 'q' => ret_code = EventResult::Quit,
                 _ => {}
             },
             KeyCode::Tab => state.focus_next_pane(true),
             KeyCode::BackTab => state.focus_next_pane(false),
-            _ => {}
+            KeyCode::Left | KeyCode::Right if state.is_focus(&DebuggerPane::Locals) => {
+                state.locals.next_tab(key.code == KeyCode::Right);
+            }
+            _ => {
+                if let Some(scroll) = state.focused_scroll_mut() {
+                    scroll_key_press(scroll, key);
+                }
+            }
         }
     }
 
     Ok(ret_code)
 }
 
+/// Keys for the focused breakpoints pane, if it handles them.
+fn breakpoints_key_press(state: &mut BreakpointsPaneState, key: KeyEvent) -> Option<EventResult> {
+    let command = match key.code {
+        KeyCode::Up => {
+            state.select_next(false);
+            None
+        }
+        KeyCode::Down => {
+            state.select_next(true);
+            None
+        }
+        KeyCode::Enter | KeyCode::Char(' ') => state.toggle_selected(),
+        KeyCode::Delete | KeyCode::Char('d') => state.delete_selected(),
+        _ => return None,
+    };
+    Some(command.map_or(EventResult::Normal, EventResult::Breakpoint))
+}
+
+/// Keys for the focused threads pane, if it handles them.
+fn threads_key_press(state: &mut ThreadsPaneState, key: KeyEvent) -> Option<EventResult> {
+    let command = match key.code {
+        KeyCode::Up => state.select_next(false),
+        KeyCode::Down => state.select_next(true),
+        _ => return None,
+    };
+    Some(command.map_or(EventResult::Normal, EventResult::Thread))
+}
+
+fn scroll_key_press(scroll: &mut ScrollState, key: KeyEvent) {
+    match key.code {
+        KeyCode::Up => scroll.scroll_up(1),
+        KeyCode::Down => scroll.scroll_down(1),
+        KeyCode::PageUp => scroll.page_up(),
+        KeyCode::PageDown => scroll.page_down(),
+        // back to the current line
+        KeyCode::Home => scroll.recenter(),
+        _ => {}
+    }
+}
+
 fn logging_screen_key_press(
     state: &mut DebuggerLogScreenState,
     key: KeyEvent,
@@ -427,6 +589,11 @@ fn logging_screen_key_press(
     Ok(ret_code)
 }
 
+/// `Ctrl`+`c`, which stops a running breakpoint command list.
+fn is_interrupt(key: &KeyEvent) -> bool {
+    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL)
+}
+
 fn await_event(tui_tx: Sender<JdbEvent>, shutdown_rx: Receiver<()>) {
     loop {
         match crossterm::event::poll(Duration::from_millis(100)) {
@@ -439,6 +606,12 @@ fn await_event(tui_tx: Sender<JdbEvent>, shutdown_rx: Receiver<()>) {
                                     // we only care about key presses.
                                     KeyEventKind::Release | KeyEventKind::Repeat => {}
                                     KeyEventKind::Press => {
+                                        // the main loop may be busy running
+                                        // breakpoint commands, so don't wait
+                                        // for it to see the key
+                                        if is_interrupt(&key) {
+                                            request_interrupt();
+                                        }
                                         if let Err(e) = tui_tx.send(JdbEvent::TerminalKey(key)) {
                                             error!("Error when sending to tui_tx channel: {:?}", e)
                                         }
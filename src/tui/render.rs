@@ -7,20 +7,64 @@ use ratatui::{
     text::{Line, Span, Text},
     widgets::{Block, Borders, Paragraph, Tabs, Widget},
 };
+use std::collections::HashMap;
+use std::path::Path;
+use strum::IntoEnumIterator;
 use tui_logger::{
     LogFormatter, TuiLoggerLevelOutput, TuiLoggerSmartWidget, TuiLoggerWidget, TuiWidgetState,
 };
 
 use crate::{
-    debugger::Debugger,
-    process::Process,
-    tui::{DebuggerLogScreenState, DebuggerPane, DebuggerState, ScreenMode, TuiState},
+    debugger::{Debugger, MAX_ECHO_LINES},
+    disassemble::Instruction,
+    process::{Process, stoppoint::VirtualAddress},
+    tui::{
+        DebuggerLogScreenState, DebuggerPane, DebuggerState, LocalsPaneMode, ScreenMode, TuiState,
+        scroll::ScrollState,
+        source::{SourcePaneState, SourceView},
+    },
 };
 
-/// This pane will render the local variables, and various registers.
-fn build_watchers_pane(state: &TuiState) -> impl Widget {
-    let block = build_bounding_rect(&DebuggerPane::Locals, None, &state.debugger_state);
-    Paragraph::new("x: 42").block(block)
+/// This pane will render the local variables, and various registers: a tab
+/// for each, with the registers that changed at the last stop highlighted.
+fn render_locals_pane(state: &DebuggerState, frame: &mut Frame, area: Rect) {
+    let locals = state.locals_state();
+    let block = build_bounding_rect(&DebuggerPane::Locals, None, state);
+    let [tabs_area, list_area] =
+        Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(block.inner(area));
+    frame.render_widget(block, area);
+
+    let tabs = LocalsPaneMode::iter()
+        .map(|mode| Line::from(mode.to_string()))
+        .collect::<Tabs>()
+        .style(Style::default().fg(Color::DarkGray))
+        .highlight_style(Style::default().fg(Color::Yellow))
+        .select(locals.mode() as usize);
+    frame.render_widget(tabs, tabs_area);
+
+    let registers = locals.registers();
+    let height = list_area.height as usize;
+    let top = locals.scroll.first_visible_line(0, registers.len(), height);
+    let width = registers.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    let lines: Vec<Line> = registers
+        .iter()
+        .skip(top)
+        .take(height)
+        .map(|register| {
+            let mut value_style = Style::default().fg(Color::Green);
+            if register.changed {
+                value_style = Style::default().fg(Color::Yellow).bold();
+            }
+            Line::from(vec![
+                Span::styled(
+                    format!("{:>width$} ", register.name),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(register.value, value_style),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), list_area);
 }
 
 fn build_command_pane(state: &DebuggerState) -> impl Widget {
@@ -35,7 +79,7 @@ fn build_command_pane(state: &DebuggerState) -> impl Widget {
 
 fn build_echo_pane(state: &DebuggerState) -> impl Widget {
     let message = state.last_command_response().unwrap_or("");
-    Paragraph::new(Line::from(message))
+    Paragraph::new(Text::from(message.to_string()))
 }
 
 fn build_output_pane(state: &DebuggerState, process: &Process) -> impl Widget {
@@ -55,11 +99,288 @@ fn build_output_pane(state: &DebuggerState, process: &Process) -> impl Widget {
         .block(block)
 }
 
-fn build_source_pane(state: &DebuggerState) -> impl Widget {
-    let block = build_bounding_rect(&DebuggerPane::Source, None, state);
-    Paragraph::new("println!(\"hello, world\");")
-        .style(Style::default().fg(Color::Green))
-        .block(block)
+/// The breakpoint list: number, type, address, hits and location, with
+/// hardware breakpoints, watchpoints and catchpoints marked, and the selected
+/// row highlighted when the pane is focused.
+fn build_breakpoints_pane(state: &DebuggerState, area: Rect) -> impl Widget {
+    let breakpoints = state.breakpoints_state();
+    let rows = breakpoints.rows();
+    let height = area.height.saturating_sub(2) as usize;
+    let selected = breakpoints.selected();
+    let top = breakpoints
+        .scroll
+        .first_visible_line(selected, rows.len(), height);
+    let is_focus = state.is_focus(&DebuggerPane::Breakpoints);
+    let number_width = rows.iter().map(|r| r.number.len()).max().unwrap_or(0);
+    let address_width = rows.iter().map(|r| r.address.len()).max().unwrap_or(0);
+
+    let lines: Vec<Line> = rows
+        .iter()
+        .enumerate()
+        .skip(top)
+        .take(height)
+        .map(|(idx, row)| {
+            // locations are listed under their breakpoint, which has the type
+            let kind = match (row.is_location(), row.hardware) {
+                (true, _) => Span::raw("   "),
+                _ if row.is_catchpoint() => Span::styled("sys", Style::default().fg(Color::Cyan)),
+                (false, true) => Span::styled("hw ", Style::default().fg(Color::Magenta)),
+                (false, false) => Span::raw("sw "),
+            };
+            let mut line = Line::from(vec![
+                breakpoint_marker(Some(row.enabled)),
+                Span::styled(
+                    format!(" {:<number_width$} ", row.number),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                kind,
+                Span::styled(
+                    format!("{:<address_width$} ", row.address),
+                    Style::default().fg(Color::Green),
+                ),
+                Span::styled(
+                    format!("{:>3} ", row.hits),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(row.what.as_str(), Style::default().fg(Color::White)),
+            ]);
+            if let Some(condition) = &row.condition {
+                line.push_span(Span::styled(
+                    format!(" if {condition}"),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            if !row.commands.is_empty() {
+                line.push_span(Span::styled(
+                    format!(" do {}", row.commands.join("; ")),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if row.ignore_count > 0 {
+                line.push_span(Span::styled(
+                    format!(" (ignore {})", row.ignore_count),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if is_focus && idx == selected {
+                line = line.bg(Color::DarkGray).bold();
+            }
+            line
+        })
+        .collect();
+
+    let block = build_bounding_rect(&DebuggerPane::Breakpoints, None, state);
+    Paragraph::new(lines).block(block)
+}
+
+/// The thread list: tid, name, state, stop reason and location, with the
+/// current thread highlighted.
+fn build_threads_pane(state: &DebuggerState, area: Rect) -> impl Widget {
+    let threads = state.threads_state();
+    let rows = threads.rows();
+    let height = area.height.saturating_sub(2) as usize;
+    let selected = threads.selected();
+    let top = threads
+        .scroll
+        .first_visible_line(selected, rows.len(), height);
+    let tid_width = rows
+        .iter()
+        .map(|r| r.tid.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let name_width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    let reason_width = rows.iter().map(|r| r.reason.len()).max().unwrap_or(0);
+
+    let lines: Vec<Line> = rows
+        .iter()
+        .skip(top)
+        .take(height)
+        .map(|row| {
+            let mut line = Line::from(vec![
+                Span::styled(
+                    format!("{:<tid_width$} ", row.tid.to_string()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(
+                    format!("{:<name_width$} ", row.name),
+                    Style::default().fg(Color::White),
+                ),
+                Span::styled(
+                    format!("{:<7} ", row.state.to_string()),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(
+                    format!("{:<reason_width$} ", row.reason),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled(row.location.as_str(), Style::default().fg(Color::Green)),
+            ]);
+            if row.current {
+                line = line.bg(Color::DarkGray).bold();
+            }
+            line
+        })
+        .collect();
+
+    let block = build_bounding_rect(&DebuggerPane::Threads, None, state);
+    Paragraph::new(lines).block(block)
+}
+
+/// Gutter marker for a line with a breakpoint: filled if any of the line's
+/// breakpoints are enabled.
+fn breakpoint_marker(enabled: Option<bool>) -> Span<'static> {
+    match enabled {
+        Some(true) => Span::styled("●", Style::default().fg(Color::Red)),
+        Some(false) => Span::styled("○", Style::default().fg(Color::DarkGray)),
+        None => Span::raw(" "),
+    }
+}
+
+fn current_line_marker(is_current: bool) -> Span<'static> {
+    if is_current {
+        Span::styled("▶ ", Style::default().fg(Color::Yellow).bold())
+    } else {
+        Span::raw("  ")
+    }
+}
+
+fn source_lines<'a>(
+    source: &'a SourcePaneState,
+    process: &Process,
+    file: &Path,
+    line: u64,
+    height: usize,
+) -> Vec<Line<'a>> {
+    // lines with breakpoints, and whether any of them are enabled
+    let mut breakpoints: HashMap<u64, bool> = HashMap::new();
+    for site in process.breakpoint_sites() {
+        if let Some(location) = process.source_location(site.address())
+            && location.file == file
+        {
+            *breakpoints.entry(location.line).or_default() |= site.is_enabled();
+        }
+    }
+
+    let lines = source.file_lines(file);
+    let current = (line as usize).saturating_sub(1);
+    let top = source
+        .scroll
+        .first_visible_line(current, lines.len(), height);
+    let number_width = lines.len().to_string().len();
+
+    lines
+        .iter()
+        .enumerate()
+        .skip(top)
+        .take(height)
+        .map(|(idx, text)| {
+            let number = idx as u64 + 1;
+            let is_current = idx == current;
+            let mut text_style = Style::default().fg(Color::Green);
+            if is_current {
+                text_style = text_style.bg(Color::DarkGray).bold();
+            }
+            Line::from(vec![
+                breakpoint_marker(breakpoints.get(&number).copied()),
+                Span::styled(
+                    format!("{number:>number_width$} "),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                current_line_marker(is_current),
+                Span::styled(text.as_str(), text_style),
+            ])
+        })
+        .collect()
+}
+
+fn disassembly_lines<'a>(
+    scroll: &ScrollState,
+    process: &Process,
+    pc: VirtualAddress,
+    instructions: &'a [Instruction],
+    height: usize,
+) -> Vec<Line<'a>> {
+    let sites: HashMap<VirtualAddress, bool> = process
+        .breakpoint_sites()
+        .map(|site| (site.address(), site.is_enabled()))
+        .collect();
+
+    let current = instructions
+        .iter()
+        .position(|inst| inst.address == pc)
+        .unwrap_or_default();
+    let top = scroll.first_visible_line(current, instructions.len(), height);
+
+    instructions
+        .iter()
+        .skip(top)
+        .take(height)
+        .map(|inst| {
+            let is_current = inst.address == pc;
+            let mut text_style = Style::default().fg(Color::Green);
+            if is_current {
+                text_style = text_style.bg(Color::DarkGray).bold();
+            }
+            let symbol = process
+                .symbolize(inst.address)
+                .map(|s| format!(" <{s}>"))
+                .unwrap_or_default();
+            let text = inst.annotated_text(|target| process.symbolize(target));
+            Line::from(vec![
+                breakpoint_marker(sites.get(&inst.address).copied()),
+                Span::styled(
+                    format!("{}{symbol} ", inst.address),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                current_line_marker(is_current),
+                Span::styled(text, text_style),
+            ])
+        })
+        .collect()
+}
+
+fn build_source_pane(state: &DebuggerState, process: &Process, area: Rect) -> impl Widget {
+    let source = state.source_state();
+    // less the borders
+    let height = area.height.saturating_sub(2) as usize;
+
+    let (title, lines) = match source.view() {
+        SourceView::Empty => (None, Vec::new()),
+        SourceView::Source { file, line } => {
+            let name = file.file_name().unwrap_or(file.as_os_str());
+            let title = format!("source - {}", name.to_string_lossy());
+            (
+                Some(title),
+                source_lines(source, process, file, *line, height),
+            )
+        }
+        SourceView::Disassembly { pc, instructions } => {
+            let title = "source - no source, showing disassembly".to_string();
+            let lines = disassembly_lines(&source.scroll, process, *pc, instructions, height);
+            (Some(title), lines)
+        }
+    };
+
+    let block = build_bounding_rect(&DebuggerPane::Source, title, state);
+    Paragraph::new(lines).block(block)
+}
+
+fn build_assembly_pane(state: &DebuggerState, process: &Process, area: Rect) -> impl Widget {
+    let assembly = state.assembly_state();
+    let height = area.height.saturating_sub(2) as usize;
+    let lines = match assembly.pc() {
+        Some(pc) => disassembly_lines(
+            &assembly.scroll,
+            process,
+            pc,
+            assembly.instructions(),
+            height,
+        ),
+        None => Vec::new(),
+    };
+
+    let block = build_bounding_rect(&DebuggerPane::Assembly, None, state);
+    Paragraph::new(lines).block(block)
 }
 
 fn build_bounding_rect<'a>(
@@ -95,10 +416,10 @@ fn render_debugger_screen(
     frame: &mut Frame,
     rect: Rect,
 ) {
-    let minibuffer_len = if state.debugger_state.last_command_response().is_some() {
-        5
-    } else {
-        3
+    // grow the echo area to fit multi-line responses (e.g. memory dumps), within reason
+    let minibuffer_len = match state.debugger_state.last_command_response() {
+        Some(response) => 3 + response.lines().count().clamp(2, MAX_ECHO_LINES) as u16,
+        None => 3,
     };
 
     let [src, logs, minibuffer] = Layout::default()
@@ -117,22 +438,36 @@ fn render_debugger_screen(
         .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
         .split(src);
 
-    // source pane
-    let source_pane = build_source_pane(&state.debugger_state);
-    frame.render_widget(source_pane, top_pane_chunks[0]);
-    // pane with locals / other ...
-    let others_pane = build_watchers_pane(state);
-    frame.render_widget(others_pane, top_pane_chunks[1]);
+    // source pane, with the assembly below it
+    let [source_area, assembly_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .areas(top_pane_chunks[0]);
+    let source_pane = build_source_pane(&state.debugger_state, process, source_area);
+    frame.render_widget(source_pane, source_area);
+    let assembly_pane = build_assembly_pane(&state.debugger_state, process, assembly_area);
+    frame.render_widget(assembly_pane, assembly_area);
+    // pane with locals / registers, with the threads below it
+    let [locals_area, threads_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .areas(top_pane_chunks[1]);
+    render_locals_pane(&state.debugger_state, frame, locals_area);
+    let threads_pane = build_threads_pane(&state.debugger_state, threads_area);
+    frame.render_widget(threads_pane, threads_area);
 
     /////////////////////////////
     // build logs chunk (stdout)
     let bottom_pane_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(100)])
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
         .split(logs);
     // logs/stdout pane
     let output_pane = build_output_pane(&state.debugger_state, process);
     frame.render_widget(output_pane, bottom_pane_chunks[0]);
+    // breakpoints and watchpoints
+    let breakpoints_pane = build_breakpoints_pane(&state.debugger_state, bottom_pane_chunks[1]);
+    frame.render_widget(breakpoints_pane, bottom_pane_chunks[1]);
 
     /////////////////////////////
     // build minbuffer (command and echo area)
@@ -0,0 +1,53 @@
+//! Scrolling for panes that follow a current line (e.g. the PC), but that the
+//! user can scroll away from.
+
+use std::cell::Cell;
+
+#[derive(Debug, Default)]
+pub struct ScrollState {
+    /// Index of the first line shown, if the user has scrolled. Otherwise the
+    /// view is centred on the current line.
+    scroll: Option<usize>,
+    /// The first line and number of lines shown in the last render, so
+    /// scrolling carries on from what's on screen.
+    top: Cell<usize>,
+    height: Cell<usize>,
+}
+
+impl ScrollState {
+    /// The first line to show in a pane `height` lines tall, out of `len`,
+    /// with the current line at index `current`. Records what was shown for
+    /// scrolling.
+    pub fn first_visible_line(&self, current: usize, len: usize, height: usize) -> usize {
+        let max_top = len.saturating_sub(height);
+        let top = self
+            .scroll
+            .unwrap_or_else(|| current.saturating_sub(height / 2))
+            .min(max_top);
+        self.top.set(top);
+        self.height.set(height);
+        top
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = Some(self.top.get().saturating_sub(lines));
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        // clamped to the end of the content when rendered
+        self.scroll = Some(self.top.get() + lines);
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.height.get().max(1));
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_down(self.height.get().max(1));
+    }
+
+    /// Go back to following the current line.
+    pub fn recenter(&mut self) {
+        self.scroll = None;
+    }
+}
@@ -0,0 +1,96 @@
+//! State for the source pane: what's shown for the current PC, and where the
+//! user has scrolled to.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::trace;
+
+use crate::disassemble::Instruction;
+use crate::process::Process;
+use crate::process::stoppoint::VirtualAddress;
+use crate::tui::assembly::disassemble_around;
+use crate::tui::scroll::ScrollState;
+
+/// What the source pane shows.
+#[derive(Debug, Default)]
+pub enum SourceView {
+    /// Nothing to show, e.g. no process is running.
+    #[default]
+    Empty,
+    /// The source file containing the PC.
+    Source { file: PathBuf, line: u64 },
+    /// Disassembly around the PC, when there's no source available.
+    Disassembly {
+        pc: VirtualAddress,
+        instructions: Vec<Instruction>,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct SourcePaneState {
+    /// Contents of the source files we've loaded, or `None` if a file couldn't
+    /// be read.
+    files: HashMap<PathBuf, Option<Vec<String>>>,
+    view: SourceView,
+    /// The PC the view was built for.
+    last_pc: Option<VirtualAddress>,
+    pub scroll: ScrollState,
+}
+
+impl SourcePaneState {
+    /// Bring the view up to date with the process' current PC.
+    pub fn sync(&mut self, process: &Process) {
+        let pc = process.pc();
+        if pc == self.last_pc {
+            return;
+        }
+        self.last_pc = pc;
+        self.scroll.recenter();
+        self.view = match pc {
+            Some(pc) => self.view_for(process, pc),
+            None => SourceView::Empty,
+        };
+    }
+
+    fn view_for(&mut self, process: &Process, pc: VirtualAddress) -> SourceView {
+        if let Some(location) = process.source_location(pc)
+            && self.load_file(&location.file).is_some()
+        {
+            return SourceView::Source {
+                file: location.file,
+                line: location.line,
+            };
+        }
+
+        match disassemble_around(process, pc) {
+            Some(instructions) => SourceView::Disassembly { pc, instructions },
+            None => SourceView::Empty,
+        }
+    }
+
+    fn load_file(&mut self, path: &Path) -> Option<&[String]> {
+        self.files
+            .entry(path.to_path_buf())
+            .or_insert_with(|| match fs::read_to_string(path) {
+                Ok(source) => Some(source.lines().map(str::to_string).collect()),
+                Err(e) => {
+                    trace!(?path, "Cannot read source file: {e}");
+                    None
+                }
+            })
+            .as_deref()
+    }
+
+    pub fn view(&self) -> &SourceView {
+        &self.view
+    }
+
+    /// The lines of a source file previously loaded by [`Self::sync`].
+    pub fn file_lines(&self, path: &Path) -> &[String] {
+        self.files
+            .get(path)
+            .and_then(|lines| lines.as_deref())
+            .unwrap_or_default()
+    }
+}
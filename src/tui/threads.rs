@@ -0,0 +1,47 @@
+//! State for the threads pane: the thread list, with the current thread
+//! highlighted. Moving the highlight switches the current thread, so the other
+//! panes follow it.
+
+use crate::debugger::thread::{ThreadCommand, ThreadRow, thread_rows};
+use crate::process::Process;
+use crate::tui::scroll::ScrollState;
+
+#[derive(Debug, Default)]
+pub struct ThreadsPaneState {
+    rows: Vec<ThreadRow>,
+    /// The process' generation the rows were built for.
+    generation: Option<u64>,
+    pub scroll: ScrollState,
+}
+
+impl ThreadsPaneState {
+    /// Bring the list up to date with the process' threads.
+    pub fn sync(&mut self, process: &Process) {
+        if self.generation == Some(process.generation()) {
+            return;
+        }
+        self.generation = Some(process.generation());
+        self.rows = thread_rows(process);
+    }
+
+    pub fn rows(&self) -> &[ThreadRow] {
+        &self.rows
+    }
+
+    /// Index of the current thread's row.
+    pub fn selected(&self) -> usize {
+        self.rows.iter().position(|row| row.current).unwrap_or(0)
+    }
+
+    /// Switch to the thread below (or above) the current one.
+    pub fn select_next(&mut self, forward: bool) -> Option<ThreadCommand> {
+        let selected = self.selected();
+        let next = match forward {
+            true => selected + 1,
+            false => selected.checked_sub(1)?,
+        };
+        let row = self.rows.get(next)?;
+        self.scroll.recenter();
+        Some(ThreadCommand::Select(row.tid))
+    }
+}
@@ -6,41 +6,44 @@ use std::time::Duration;
 
 use anyhow::Result;
 use crossbeam_channel::unbounded;
+use jdb::debugger::disassemble::DisassembleCommand;
+use jdb::debugger::handle::HandleCommand;
+use jdb::debugger::info::{InfoCommand, breakpoint_rows};
+use jdb::debugger::memory::MemoryCommand;
+use jdb::debugger::register::RegisterCommand;
+use jdb::debugger::set::SetCommand;
+use jdb::debugger::thread::{ThreadCommand, thread_rows};
+use jdb::debugger::{BreakpointCommand, Debugger, DispatchResult};
+use jdb::elf::loaded::read_memory_maps;
+use jdb::elf::{Elf, SymbolType};
+use jdb::expression::Expression;
 use jdb::options::Options;
-use jdb::process::Process;
-use jdb::process::register_info::Register;
+use jdb::process::register_info::{Register, RegisterValue};
+use jdb::process::stoppoint::VirtualAddress;
+use jdb::process::stoppoint::breakpoint::{BreakpointId, BreakpointLocation};
+use jdb::process::stoppoint::hardware::{HardwareMode, HardwareStoppoint};
+use jdb::process::syscalls::parse_syscall;
+use jdb::process::thread::ThreadState;
+use jdb::process::{Process, ProcessState, StopReason, TrapType};
+use nix::sys::ptrace;
 
-/// Wrapper around the `Process` instance. The key insight is implementing the
-/// `Drop` trait which will guarantee the proper shutdown of the `Process`.
-struct ProcessGuard {
-    process: Option<Process>,
-    shutdown_tx: Option<crossbeam_channel::Sender<()>>,
-}
+use fixtures::ProcessGuard;
 
-impl ProcessGuard {
-    fn new(process: Process, shutdown_tx: crossbeam_channel::Sender<()>) -> Self {
-        Self {
-            process: Some(process),
-            shutdown_tx: Some(shutdown_tx),
-        }
-    }
-
-    fn get_mut(&mut self) -> &mut Process {
-        self.process
-            .as_mut()
-            .expect("process should still be available")
-    }
-}
-
-impl Drop for ProcessGuard {
-    fn drop(&mut self) {
-        if let Some(mut process) = self.process.take() {
-            let _ = process.destroy();
-        }
-        if let Some(tx) = self.shutdown_tx.take() {
-            let _ = tx.send(());
+/// Poll `/proc/<pid>/stat` until the process is in a stopped state.
+fn wait_until_stopped(pid: nix::unistd::Pid) -> Result<()> {
+    for _ in 0..500 {
+        let stat = std::fs::read_to_string(format!("/proc/{pid}/stat"))?;
+        // the state follows the parenthesized command name
+        let state = stat
+            .rsplit(')')
+            .next()
+            .and_then(|s| s.trim().chars().next());
+        if state == Some('T') {
+            return Ok(());
         }
+        std::thread::sleep(Duration::from_millis(10));
     }
+    anyhow::bail!("process {pid} never stopped")
 }
 
 /// End-to-end smoke test: spawn the fixture, stop on SIGSTOP, read registers,
@@ -53,6 +56,7 @@ fn attach_read_and_resume_inferior() -> Result<()> {
     let (shutdown_tx, shutdown_rx) = unbounded();
     let options = Options {
         executable: fixture.clone(),
+        pid: None,
     };
     let mut process_guard =
         ProcessGuard::new(Process::new(options, inferior_tx, shutdown_rx), shutdown_tx);
@@ -62,6 +66,7 @@ fn attach_read_and_resume_inferior() -> Result<()> {
     process.attach(Vec::new()).expect("attach should succeed");
     let pid = process.pid().expect("pid should be available after attach");
     process.resume()?;
+    process.wait_on_signal()?;
 
     // Registers should be readable while stopped.
     assert!(process.read_register(Register::RIP).is_some());
@@ -87,3 +92,1221 @@ fn attach_read_and_resume_inferior() -> Result<()> {
     // Clean teardown.
     Ok(())
 }
+
+/// Attach to an already-running process by PID, then quit: the process must be
+/// detached from (not killed) and able to run to completion on its own.
+#[test]
+fn attach_to_running_process_and_detach() -> Result<()> {
+    let fixture = fixtures::hello_fixture_path();
+
+    // The fixture raises SIGSTOP right after printing, so it will sit there
+    // until we attach.
+    let mut child = std::process::Command::new(&fixture)
+        .stdout(std::process::Stdio::null())
+        .spawn()?;
+    let pid = nix::unistd::Pid::from_raw(child.id() as i32);
+    wait_until_stopped(pid)?;
+
+    let (inferior_tx, _inferior_rx) = unbounded();
+    let (shutdown_tx, shutdown_rx) = unbounded();
+    let options = Options::from_args(["--pid".to_string(), pid.to_string()].into_iter())?;
+    assert_eq!(options.executable.canonicalize()?, fixture.canonicalize()?);
+
+    let mut process_guard =
+        ProcessGuard::new(Process::new(options, inferior_tx, shutdown_rx), shutdown_tx);
+    let process = process_guard.get_mut();
+
+    process.attach_to_pid(pid)?;
+    assert_eq!(process.pid(), Some(pid));
+    assert!(process.read_register(Register::RIP).is_some());
+
+    // "quit" detaches rather than kills
+    process.destroy()?;
+    assert!(matches!(process.state(), ProcessState::Detached));
+
+    nix::sys::signal::kill(pid, nix::sys::signal::Signal::SIGCONT)?;
+    let status = child.wait()?;
+    assert!(
+        status.success(),
+        "detached process should exit cleanly: {status:?}"
+    );
+
+    Ok(())
+}
+
+/// Single-step from the SIGSTOP, then set a breakpoint on the current PC and
+/// continue: the breakpoint must be stepped over rather than re-trapping.
+#[test]
+fn step_instruction_and_resume_over_breakpoint() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    // run up to the fixture's SIGSTOP
+    process.resume()?;
+    process.wait_on_signal()?;
+    let start_pc = current_pc(process);
+
+    process.step_instruction()?;
+    let stepped_pc = current_pc(process);
+    assert_ne!(start_pc, stepped_pc);
+    assert!(
+        matches!(
+            process.last_stop(),
+            Some(StopReason::Stopped {
+                trap: Some(TrapType::SingleStep),
+                ..
+            })
+        ),
+        "expected a single-step stop, got {:?}",
+        process.last_stop()
+    );
+
+    process.breakpoint_command(BreakpointCommand::Create(
+        VirtualAddress::from(stepped_pc).into(),
+    ))?;
+    process.resume()?;
+    let wait_status = process.wait_on_signal()?;
+    assert!(
+        matches!(wait_status, nix::sys::wait::WaitStatus::Exited(_, 0)),
+        "expected the inferior to step over the breakpoint and exit, got {wait_status:?}"
+    );
+
+    Ok(())
+}
+
+fn current_pc(process: &Process) -> u64 {
+    match process.read_register(Register::RIP) {
+        Some(RegisterValue::Uint64(pc)) => pc,
+        other => panic!("unexpected rip value: {other:?}"),
+    }
+}
+
+/// Register writes reach the inferior, with subregisters only changing their
+/// part of the full register.
+#[test]
+fn write_registers() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    let pid = process.pid().expect("running inferior");
+
+    process.write_register(Register::R13, RegisterValue::Uint64(0x1122_3344_5566_7788))?;
+    process.write_register(Register::R13D, RegisterValue::Uint32(0xdead_beef))?;
+    process.write_register(Register::RAX, RegisterValue::Uint64(0))?;
+    process.write_register(Register::AH, RegisterValue::Uint8(0x42))?;
+    assert!(matches!(
+        process.read_register(Register::R13),
+        Some(RegisterValue::Uint64(0x1122_3344_dead_beef))
+    ));
+    let regs = ptrace::getregs(pid)?;
+    assert_eq!(regs.r13, 0x1122_3344_dead_beef);
+    assert_eq!(regs.rax, 0x4200);
+
+    let xmm = u128::from_le_bytes(*b"0123456789abcdef");
+    process.write_register(Register::XMM1, RegisterValue::Byte128(xmm.to_le_bytes()))?;
+    let fp = ptrace::getregset::<ptrace::regset::NT_PRFPREG>(pid)?;
+    assert_eq!(
+        fp.xmm_space[4..8],
+        [0x33323130, 0x37363534, 0x62613938, 0x66656463]
+    );
+
+    // the snapshot and the inferior agree on the moved PC, then put it back
+    let pc = current_pc(process);
+    process.write_register(Register::RIP, RegisterValue::Uint64(pc + 1))?;
+    assert_eq!(current_pc(process), pc + 1);
+    assert_eq!(ptrace::getregs(pid)?.rip, pc + 1);
+    process.write_register(Register::RIP, RegisterValue::Uint64(pc))?;
+    assert_eq!(ptrace::getregs(pid)?.rip, pc);
+
+    Ok(())
+}
+
+/// `register read` and `register write` format values according to the
+/// register, and writes show up in the snapshot.
+#[test]
+fn register_commands() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    let mut run = |command: &str| -> Result<String> {
+        let args: Vec<String> = command.split_whitespace().map(String::from).collect();
+        RegisterCommand::parse(&args)?.execute(process)
+    };
+
+    let gp = run("read")?;
+    assert!(gp.contains("rip = 0x"), "{gp}");
+    assert!(!gp.contains("eax"), "{gp}");
+    assert!(run("read all")?.contains("xmm15 = {"));
+
+    assert_eq!(run("write rax 0x1000 + 0x234")?, "rax = 0x0000000000001234");
+    assert_eq!(run("write $ah 0xff")?, "ah = 0xff");
+    assert_eq!(run("read eax")?, "eax = 0x0000ff34");
+    assert_eq!(run("write st0 -1.25")?, "st0 = -1.25");
+    assert_eq!(
+        run("write xmm2 0x1")?,
+        "xmm2 = {0x00000001 0x00000000 0x00000000 0x00000000}"
+    );
+    assert_eq!(run("read sp")?, run("read rsp")?);
+
+    assert!(run("read nope").is_err());
+    assert!(run("write xmm2 1.5").is_err());
+
+    Ok(())
+}
+
+/// Memory reads hide our `int3`s, and writes round-trip.
+#[test]
+fn read_and_write_inferior_memory() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    // breakpoints are invisible to memory reads
+    let pc = VirtualAddress::from(current_pc(process));
+    let before = process.read_memory(pc, 16)?;
+    process.breakpoint_command(BreakpointCommand::Create(pc.into()))?;
+    assert_eq!(before, process.read_memory(pc, 16)?);
+
+    // write to the stack, read it back, and put it back as it was
+    let sp = match process.read_register(Register::RSP) {
+        Some(RegisterValue::Uint64(sp)) => VirtualAddress::from(sp),
+        other => panic!("unexpected rsp value: {other:?}"),
+    };
+    let sp_expr = Expression::parse("$rsp + 0x10 - 16")?;
+    assert_eq!(process.evaluate_address(&sp_expr)?, sp);
+    let original = process.read_memory(sp, 8)?;
+    process.write_memory(sp, &[0xde, 0xad, 0xbe, 0xef])?;
+    assert_eq!(process.read_memory(sp, 4)?, vec![0xde, 0xad, 0xbe, 0xef]);
+    process.write_memory(sp, &original)?;
+    assert_eq!(process.read_memory(sp, 8)?, original);
+
+    // absurd lengths are errors, not allocations
+    assert!(process.read_memory(sp, usize::MAX).is_err());
+    let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+    assert!(MemoryCommand::parse_memory(&args("read $rsp 0xffffffffffff")).is_err());
+    let huge = format!("x/{}gx", usize::MAX / 2);
+    let message = MemoryCommand::parse_examine(&huge, &args("$rsp"))?
+        .execute(process)
+        .unwrap_err()
+        .to_string();
+    assert!(message.starts_with("Cannot examine"), "{message}");
+
+    Ok(())
+}
+
+/// The executable's symbols are parsed, and resolved against the load bias of
+/// the running inferior; shared objects are picked up once they're mapped.
+#[test]
+fn elf_symbols_and_load_bias() -> Result<()> {
+    let fixture = fixtures::hello_fixture_path();
+
+    let elf = Elf::open(&fixture)?;
+    assert!(elf.section_by_name(".text").is_some());
+    let main = elf
+        .symbols_by_name("main")
+        .next()
+        .expect("fixture should have a main symbol")
+        .clone();
+    assert_eq!(main.kind, SymbolType::Function);
+    assert_eq!(
+        elf.symbol_containing(main.value).map(|s| &s.name),
+        Some(&main.name)
+    );
+
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    let pid = process.pid().expect("inferior should be running");
+    let objects = process.objects().expect("executable should be parsed");
+    let executable = objects.executable();
+
+    // the bias computed from AT_ENTRY agrees with the memory mappings
+    let mapping = read_memory_maps(pid)?
+        .into_iter()
+        .find(|m| m.file() == Some(executable.path()) && m.offset == 0)
+        .expect("executable should be mapped");
+    assert_eq!(
+        executable.load_bias_from_mapping(mapping.start, mapping.offset),
+        Some(executable.load_bias())
+    );
+
+    let main_address = executable.to_virtual(main.value);
+    assert_eq!(
+        process.evaluate_address(&Expression::parse("main+1")?)?,
+        main_address.offset(1)
+    );
+    let (object, symbol) = objects
+        .symbol_containing(main_address.offset(1))
+        .expect("main should be found by address");
+    assert_eq!(object.path(), executable.path());
+    assert_eq!(symbol.name, "main");
+
+    // by the time the fixture raises SIGSTOP, libc has been loaded
+    assert!(
+        objects
+            .iter()
+            .any(|o| o.path().to_string_lossy().contains("libc")),
+        "libc should be loaded: {:?}",
+        objects.iter().map(|o| o.path()).collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
+
+/// Breakpoints on function names resolve once the inferior is loaded (including
+/// by demangled Rust names), and are hit in call order.
+#[test]
+fn break_on_function_names() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    // before the inferior starts, the breakpoints can't be resolved
+    let message = process.breakpoint_command(BreakpointCommand::Create(
+        BreakpointLocation::Function("main".to_string()),
+    ))?;
+    assert!(message.contains("pending"), "{message}");
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Function(
+        "jdb_inferior_fixtures::main".to_string(),
+    )))?;
+    assert!(
+        process
+            .breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Function(
+                "no_such_function".to_string()
+            )))
+            .is_ok(),
+        "unknown functions are pending until the inferior starts"
+    );
+
+    process.attach(Vec::new())?;
+    let objects = process.objects().expect("executable should be parsed");
+    let c_main = objects.find_functions("main");
+    let rust_main = objects.find_functions("jdb_inferior_fixtures::main");
+    assert_eq!(c_main.len(), 1);
+    assert_eq!(rust_main.len(), 1);
+    assert!(
+        process
+            .breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Function(
+                "no_such_function".to_string()
+            )))
+            .is_err()
+    );
+
+    for expected in [c_main[0], rust_main[0]] {
+        process.resume()?;
+        process.wait_on_signal()?;
+        match process.last_stop() {
+            Some(StopReason::Stopped {
+                trap: Some(TrapType::SoftwareBreak),
+                pc,
+                ..
+            }) => assert_eq!(*pc, expected),
+            other => panic!("expected to stop at a breakpoint, got {other:?}"),
+        }
+        assert!(process.breakpoint_at(expected).is_some());
+    }
+
+    Ok(())
+}
+
+/// Line breakpoints set before `run` resolve through the DWARF line table once
+/// the PIE load address is known; a line without code moves to the next one.
+#[test]
+fn break_on_source_line() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    // line 5 is a comment, so the breakpoint goes on the `println!` below it
+    let message =
+        process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Line {
+            file: "src/main.rs".into(),
+            line: 5,
+        }))?;
+    assert!(message.contains("pending"), "{message}");
+
+    process.attach(Vec::new())?;
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    let pc = VirtualAddress::from(current_pc(process));
+    assert!(matches!(
+        process.last_stop(),
+        Some(StopReason::Stopped {
+            trap: Some(TrapType::SoftwareBreak),
+            ..
+        })
+    ));
+    let location = process
+        .source_location(pc)
+        .expect("fixture should have line info");
+    assert!(location.file.ends_with("src/main.rs"), "{location:?}");
+    assert_eq!(location.line, 6);
+    assert_eq!(location.to_string(), "main.rs:6");
+
+    // with debug info, function breakpoints skip the prologue to the first line
+    let objects = process.objects().expect("executable should be parsed");
+    let rust_main = objects.find_functions("jdb_inferior_fixtures::main");
+    assert_eq!(rust_main, vec![pc]);
+
+    Ok(())
+}
+
+/// `disassemble` shows the function around the PC, with our `int3` masked out
+/// and call targets labelled with their symbols.
+#[test]
+fn disassemble_current_function() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Function(
+        "jdb_inferior_fixtures::main".to_string(),
+    )))?;
+    process.attach(Vec::new())?;
+    process.resume()?;
+    process.wait_on_signal()?;
+    let pc = VirtualAddress::from(current_pc(process));
+
+    let output = DisassembleCommand::parse(&[])?.execute(process)?;
+    let current = output
+        .lines()
+        .find(|line| line.starts_with("=>"))
+        .unwrap_or_else(|| panic!("no current instruction in:\n{output}"));
+    assert!(
+        current.starts_with(&format!("=> {pc} <jdb_inferior_fixtures::main+")),
+        "{current}"
+    );
+    assert!(!current.ends_with("int3"), "{current}");
+    assert!(
+        output
+            .lines()
+            .next()
+            .is_some_and(|line| line.contains("<jdb_inferior_fixtures::main>:")),
+        "{output}"
+    );
+    assert!(
+        output
+            .lines()
+            .any(|line| line.contains(":  call 0x") && line.ends_with('>')),
+        "{output}"
+    );
+
+    // an explicit range starting at the PC
+    let output =
+        DisassembleCommand::parse(&["$pc,".to_string(), "+1".to_string()])?.execute(process)?;
+    assert_eq!(output.lines().count(), 1, "{output}");
+
+    Ok(())
+}
+
+/// Write watchpoints use the debug registers, and report the old and new
+/// values of the watched memory.
+#[test]
+fn watchpoint_reports_changed_values() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    // run to the SIGSTOP the fixture raises, before the counter is touched
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    let counter = process.evaluate_address(&Expression::parse("WATCHED_COUNTER")?)?;
+    let misaligned = HardwareStoppoint::watch(counter.offset(1), 8, HardwareMode::Write);
+    assert!(misaligned.is_err());
+    let watch = HardwareStoppoint::watch(counter, 8, HardwareMode::Write)?;
+    let message = process.breakpoint_command(BreakpointCommand::Watch(watch))?;
+    assert!(message.starts_with("Hardware watchpoint"), "{message}");
+
+    for expected in 1..=2 {
+        process.resume()?;
+        process.wait_on_signal()?;
+        assert!(matches!(
+            process.last_stop(),
+            Some(StopReason::Stopped {
+                trap: Some(TrapType::Watchpoint),
+                ..
+            })
+        ));
+        let watchpoint = process
+            .watchpoint_hit()
+            .expect("should stop on the watchpoint");
+        assert_eq!(watchpoint.previous_value(), Some(expected - 1));
+        assert_eq!(watchpoint.value(), Some(expected));
+        let rows = thread_rows(process);
+        let current = rows
+            .iter()
+            .find(|row| row.current)
+            .expect("a current thread");
+        assert_eq!(current.reason, "watchpoint");
+    }
+
+    // only four debug registers
+    for i in 1..=3 {
+        let watch = HardwareStoppoint::watch(counter.offset(8 * i), 8, HardwareMode::ReadWrite)?;
+        process.breakpoint_command(BreakpointCommand::Watch(watch))?;
+    }
+    let watch = HardwareStoppoint::watch(counter.offset(32), 8, HardwareMode::Write)?;
+    let err = process
+        .breakpoint_command(BreakpointCommand::Watch(watch))
+        .expect_err("all the debug registers are in use");
+    assert!(err.to_string().contains("in use"), "{err}");
+
+    Ok(())
+}
+
+/// `hbreak` stops at a function through the debug registers, without
+/// patching an `int3` into the code.
+#[test]
+fn hardware_breakpoint_leaves_code_untouched() -> Result<()> {
+    use std::os::unix::fs::FileExt;
+
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    let message = process.breakpoint_command(BreakpointCommand::CreateHardware(
+        BreakpointLocation::Function("jdb_inferior_fixtures::main".to_string()),
+    ))?;
+    assert!(message.starts_with("Hardware breakpoint"), "{message}");
+
+    process.attach(Vec::new())?;
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    let pc = VirtualAddress::from(current_pc(process));
+    assert!(matches!(
+        process.last_stop(),
+        Some(StopReason::Stopped {
+            trap: Some(TrapType::HardwareBreak),
+            ..
+        })
+    ));
+    let breakpoint = process
+        .breakpoint_at(pc)
+        .expect("should stop at the breakpoint");
+    assert!(breakpoint.is_hardware());
+
+    // the raw memory, not `read_memory`, which would hide an int3
+    let mem = std::fs::File::open(format!("/proc/{}/mem", process.expect_pid()))?;
+    let mut byte = [0u8; 1];
+    mem.read_exact_at(&mut byte, pc.addr())?;
+    assert_ne!(byte[0], 0xcc);
+
+    // continuing doesn't trip over the breakpoint again
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(matches!(
+        process.last_stop(),
+        Some(StopReason::Stopped {
+            signal: nix::sys::signal::Signal::SIGSTOP,
+            ..
+        })
+    ));
+
+    Ok(())
+}
+
+/// `info breakpoints` lists breakpoints and watchpoints by number, with how
+/// often each has been hit.
+#[test]
+fn info_breakpoints_counts_hits() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    let info = |process: &Process| -> Result<String> {
+        InfoCommand::parse(&["breakpoints".to_string()])?.execute(process)
+    };
+    assert_eq!(info(process)?, "No breakpoints or watchpoints");
+
+    // the `thread::sleep` in the fixture's loop
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Line {
+        file: "src/main.rs".into(),
+        line: 15,
+    }))?;
+    let listing = info(process)?;
+    assert!(listing.contains("<PENDING>"), "{listing}");
+
+    // run to the SIGSTOP, then into the loop
+    process.attach(Vec::new())?;
+    process.resume()?;
+    process.wait_on_signal()?;
+    let counter = process.evaluate_address(&Expression::parse("WATCHED_COUNTER")?)?;
+    let watch = HardwareStoppoint::watch(counter, 8, HardwareMode::Write)?;
+    process.breakpoint_command(BreakpointCommand::Watch(watch))?;
+
+    // sleep, the counter changes, sleep again
+    for _ in 0..3 {
+        let generation = process.generation();
+        process.resume()?;
+        process.wait_on_signal()?;
+        assert!(process.generation() > generation);
+    }
+
+    let rows = breakpoint_rows(process);
+    assert_eq!(rows.len(), 2, "{rows:?}");
+    assert_eq!(rows[0].kind, "breakpoint");
+    assert_eq!(rows[0].hits, 2);
+    assert!(rows[0].what.ends_with("main.rs:15"), "{rows:?}");
+    assert_eq!(rows[1].kind, "hw watchpoint");
+    assert_eq!(rows[1].hits, 1);
+
+    let listing = info(process)?;
+    let lines: Vec<&str> = listing.lines().collect();
+    assert!(lines[0].starts_with("Num"), "{listing}");
+    assert!(
+        lines[1].starts_with(&format!("{} ", rows[0].number)),
+        "{listing}"
+    );
+    assert!(lines[2].contains(" y "), "{listing}");
+
+    // listing changes nothing, so the panes have nothing to rebuild
+    let generation = process.generation();
+    assert_eq!(info(process)?, listing);
+    assert_eq!(process.generation(), generation);
+    process.breakpoint_command(BreakpointCommand::Disable(rows[1].id))?;
+    assert_ne!(process.generation(), generation);
+    assert!(!breakpoint_rows(process)[1].enabled);
+
+    Ok(())
+}
+
+/// `ignore` continues through a breakpoint's next hits, still counting them.
+#[test]
+fn ignore_count_skips_hits() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    // the `thread::sleep` at the top of the fixture's loop
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Line {
+        file: "src/main.rs".into(),
+        line: 15,
+    }))?;
+    let id = process.breakpoints().next().expect("just created").id();
+    let message = process.breakpoint_command(BreakpointCommand::Ignore { id, count: 2 })?;
+    assert_eq!(
+        message,
+        format!("Will ignore next 2 crossings of breakpoint {id}")
+    );
+    let listing = InfoCommand::parse(&["breakpoints".to_string()])?.execute(process)?;
+    assert!(
+        listing.contains("Will ignore next 2 crossings"),
+        "{listing}"
+    );
+
+    // stops on the third time round the loop
+    process.resume()?;
+    process.wait_on_signal()?;
+    let pc = VirtualAddress::from(current_pc(process));
+    assert_eq!(process.breakpoint_at(pc).map(|b| b.id()), Some(id));
+    let counter = process.evaluate_address(&Expression::parse("WATCHED_COUNTER")?)?;
+    let value = process.read_memory(counter, 8)?;
+    assert_eq!(u64::from_le_bytes(value.try_into().unwrap()), 2);
+
+    let rows = breakpoint_rows(process);
+    assert_eq!(rows[0].hits, 3);
+    assert_eq!(rows[0].ignore_count, 0);
+
+    assert!(
+        process
+            .breakpoint_command(BreakpointCommand::Ignore {
+                id: BreakpointId::try_from(vec!["99".to_string()])?,
+                count: 1
+            })
+            .is_err()
+    );
+
+    Ok(())
+}
+
+/// A breakpoint with a condition only stops (and counts a hit) when the
+/// condition, evaluated against the stopped inferior, is true.
+#[test]
+fn conditional_breakpoint() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&[])?;
+    let process = process_guard.get_mut();
+
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    // the `thread::sleep` at the top of the fixture's loop
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Line {
+        file: "src/main.rs".into(),
+        line: 15,
+    }))?;
+    let id = process.breakpoints().next().expect("just created").id();
+    let condition = Expression::parse("*(u32*)WATCHED_COUNTER == 2 && $rip != 0")?;
+    let message = process.breakpoint_command(BreakpointCommand::Condition {
+        id,
+        condition: Some(condition),
+    })?;
+    assert_eq!(
+        message,
+        format!("Breakpoint {id} now stops only if *(u32*)WATCHED_COUNTER == 2 && $rip != 0")
+    );
+    let listing = InfoCommand::parse(&["breakpoints".to_string()])?.execute(process)?;
+    assert!(
+        listing.contains("stop only if *(u32*)WATCHED_COUNTER"),
+        "{listing}"
+    );
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    let counter = process.evaluate_address(&Expression::parse("WATCHED_COUNTER")?)?;
+    let value = process.read_memory(counter, 8)?;
+    assert_eq!(u64::from_le_bytes(value.try_into().unwrap()), 2);
+    let rows = breakpoint_rows(process);
+    assert_eq!(rows[0].hits, 1, "only hits where the condition holds count");
+
+    let message = process.breakpoint_command(BreakpointCommand::Condition {
+        id,
+        condition: None,
+    })?;
+    assert_eq!(message, format!("Breakpoint {id} is now unconditional"));
+    assert_eq!(breakpoint_rows(process)[0].condition, None);
+
+    Ok(())
+}
+
+/// A breakpoint's command list runs on every hit, through the debugger, with
+/// `continue` carrying on to the next stop.
+#[test]
+fn breakpoint_commands_trace_a_loop() -> Result<()> {
+    // keep the command history out of the real cache directory
+    let cache = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("breakpoint_commands");
+    std::fs::create_dir_all(cache.join("jdb"))?;
+    // SAFETY: no other test reads or writes the environment
+    unsafe { std::env::set_var("XDG_CACHE_HOME", &cache) };
+
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+    let mut debugger = Debugger::new()?;
+
+    let mut run = |command: &str, process: &mut Process| -> Result<String> {
+        match debugger.next(command.to_string(), process)? {
+            DispatchResult::Message(message) => Ok(message),
+            _ => Ok(String::new()),
+        }
+    };
+
+    assert!(run("continue", process).is_err(), "not started yet");
+    // to the fixture's SIGSTOP
+    run("run", process)?;
+    run("continue", process)?;
+    run("break src/main.rs:15", process)?;
+    let id = process.breakpoints().next().expect("just created").id();
+    assert!(run(&format!("commands {id} bogus; continue"), process).is_err());
+    let message = run(
+        &format!("commands {id} x/1xg WATCHED_COUNTER; continue"),
+        process,
+    )?;
+    assert_eq!(
+        message,
+        format!("Will run 2 commands when breakpoint {id} is hit")
+    );
+    let listing = run("info breakpoints", process)?;
+    assert!(listing.contains("   x/1xg WATCHED_COUNTER"), "{listing}");
+
+    // an interrupt stops the list at the next stop, without running its
+    // commands
+    jdb::debugger::request_interrupt();
+    let output = run("continue", process)?;
+    let hits = output.matches(&format!("hit breakpoint {id}")).count();
+    assert_eq!(hits, 2, "{output}");
+    let examined = output.lines().filter(|line| line.starts_with("0x")).count();
+    assert_eq!(examined, 1, "{output}");
+    assert!(
+        output.ends_with("not running any more breakpoint commands"),
+        "{output}"
+    );
+
+    // then every time round the rest of the loop, until the inferior exits
+    let output = run("continue", process)?;
+    let hits = output.matches(&format!("hit breakpoint {id}")).count();
+    assert_eq!(hits, 1, "{output}");
+    assert!(output.ends_with("exited with status 0"), "{output}");
+
+    Ok(())
+}
+
+/// The state character of a thread, from `/proc/<pid>/task/<tid>/stat`.
+fn thread_proc_state(pid: nix::unistd::Pid, tid: nix::unistd::Pid) -> Result<char> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/task/{tid}/stat"))?;
+    stat.rsplit(')')
+        .next()
+        .and_then(|s| s.trim().chars().next())
+        .ok_or_else(|| anyhow::anyhow!("malformed stat for thread {tid}"))
+}
+
+/// Breakpoints hit in threads other than the main one are reported against
+/// that thread, every other thread is stopped too, and the current thread can
+/// be switched. No hit is lost when two threads reach the breakpoint at once.
+#[test]
+fn breakpoints_in_threads() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&["threads"])?;
+    let process = process_guard.get_mut();
+
+    let pid = process.expect_pid();
+    // to the fixture's SIGSTOP
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    // in `worker`, which each of the two threads runs twice
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Line {
+        file: "src/main.rs".into(),
+        line: 55,
+    }))?;
+    let id = process.breakpoints().next().expect("just created").id();
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    let Some(StopReason::Stopped {
+        tid,
+        trap: Some(TrapType::SoftwareBreak),
+        pc,
+        ..
+    }) = process.last_stop().cloned()
+    else {
+        panic!("expected a breakpoint, got {:?}", process.last_stop());
+    };
+    assert_ne!(tid, pid, "the breakpoint is only reached by the workers");
+    assert_eq!(process.current_thread().map(|t| t.tid()), Some(tid));
+    assert_eq!(current_pc(process), pc.addr());
+
+    // all-stop: every thread is stopped, not just the one at the breakpoint
+    assert!(process.threads().count() >= 2);
+    for thread in process.threads() {
+        assert_eq!(thread.state(), ThreadState::Stopped);
+        assert_eq!(thread_proc_state(pid, thread.tid())?, 't');
+    }
+    let listing = ThreadCommand::List.execute(process)?;
+    assert!(listing.contains(&format!("* {tid}")), "{listing}");
+    assert!(listing.contains("breakpoint"), "{listing}");
+    for row in thread_rows(process) {
+        let comm = std::fs::read_to_string(format!("/proc/{pid}/task/{}/comm", row.tid))?;
+        assert_eq!(row.name, comm.trim_end());
+        assert!(!row.name.is_empty());
+    }
+
+    // the main thread is waiting for the workers, elsewhere
+    ThreadCommand::Select(pid).execute(process)?;
+    assert_eq!(process.current_thread().map(|t| t.tid()), Some(pid));
+    assert_ne!(current_pc(process), pc.addr());
+    assert!(
+        ThreadCommand::Select(nix::unistd::Pid::from_raw(1))
+            .execute(process)
+            .is_err()
+    );
+
+    let mut hit_threads = vec![tid];
+    loop {
+        process.resume()?;
+        process.wait_on_signal()?;
+        match process.last_stop() {
+            Some(StopReason::Stopped { tid, .. }) => hit_threads.push(*tid),
+            Some(StopReason::Exited(code)) => {
+                assert_eq!(*code, 0);
+                break;
+            }
+            other => panic!("unexpected stop {other:?}"),
+        }
+    }
+    assert_eq!(hit_threads.len(), 4, "{hit_threads:?}");
+    hit_threads.sort();
+    hit_threads.dedup();
+    assert_eq!(hit_threads.len(), 2, "each worker hits the breakpoint");
+    let rows = breakpoint_rows(process);
+    let row = rows.iter().find(|row| row.id == id).expect("listed");
+    assert_eq!(row.hits, 4);
+
+    Ok(())
+}
+
+/// Launch the fixture with the given arguments, and continue it past its
+/// `SIGSTOP`, with a breakpoint on a line of `src/main.rs`.
+fn run_to_fixture_line(process: &mut Process, args: &[&str], line: u64) -> Result<()> {
+    process.attach(args.iter().map(|arg| arg.to_string()).collect())?;
+    process.resume()?;
+    process.wait_on_signal()?;
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Line {
+        file: "src/main.rs".into(),
+        line,
+    }))?;
+    process.resume()?;
+    process.wait_on_signal()?;
+    Ok(())
+}
+
+/// Line of `forked`, which both sides of the fixture's fork run.
+const FORKED_LINE: u64 = 88;
+
+/// By default, the parent is followed after a fork, and the child is detached
+/// without our breakpoints, so it runs (through the breakpoint) to completion.
+#[test]
+fn fork_follows_the_parent() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    run_to_fixture_line(process, &["fork"], FORKED_LINE)?;
+    let pid = process.expect_pid();
+    let Some(StopReason::Stopped { tid, .. }) = process.last_stop() else {
+        panic!("expected a breakpoint, got {:?}", process.last_stop());
+    };
+    assert_eq!(*tid, pid);
+    assert!(process.breakpoint_hit().is_some());
+
+    // the fixture exits with 1 if the child didn't exit cleanly
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(
+        matches!(process.last_stop(), Some(StopReason::Exited(0))),
+        "{:?}",
+        process.last_stop()
+    );
+
+    Ok(())
+}
+
+/// `posix_spawn` `vfork`s, and the child shares the parent's memory until it
+/// execs, so the breakpoints go back in once it has.
+#[test]
+fn vfork_keeps_the_parents_breakpoints() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    run_to_fixture_line(process, &["spawn"], FORKED_LINE)?;
+    let pid = process.expect_pid();
+    assert!(
+        matches!(process.last_stop(), Some(StopReason::Stopped { tid, .. }) if *tid == pid),
+        "{:?}",
+        process.last_stop()
+    );
+    assert!(process.breakpoint_hit().is_some());
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(matches!(process.last_stop(), Some(StopReason::Exited(0))));
+
+    Ok(())
+}
+
+/// With `follow-fork-mode child`, the child is debugged after a fork, and
+/// the parent is detached without our breakpoints.
+#[test]
+fn follow_fork_mode_child() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    let message = SetCommand::parse(&["follow-fork-mode".to_string(), "child".to_string()])?
+        .execute(process)?;
+    assert_eq!(message, "follow-fork-mode is now child");
+    assert!(SetCommand::parse(&["follow-fork-mode".to_string(), "both".to_string()]).is_err());
+
+    process.attach(vec!["fork".to_string()])?;
+    let parent = process.expect_pid();
+    process.resume()?;
+    process.wait_on_signal()?;
+    process.breakpoint_command(BreakpointCommand::Create(BreakpointLocation::Line {
+        file: "src/main.rs".into(),
+        line: FORKED_LINE,
+    }))?;
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    let child = process.expect_pid();
+    assert_ne!(child, parent);
+    let status = std::fs::read_to_string(format!("/proc/{child}/status"))?;
+    assert!(status.contains(&format!("PPid:\t{parent}\n")), "{status}");
+    let Some(StopReason::Stopped { tid, .. }) = process.last_stop() else {
+        panic!("expected a breakpoint, got {:?}", process.last_stop());
+    };
+    assert_eq!(*tid, child);
+    assert!(process.breakpoint_hit().is_some());
+    assert_eq!(
+        process.threads().map(|t| t.tid()).collect::<Vec<_>>(),
+        vec![child]
+    );
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(matches!(process.last_stop(), Some(StopReason::Exited(0))));
+    // the parent ran through the breakpoint too, and saw the child exit cleanly
+    assert_eq!(
+        nix::sys::wait::waitpid(parent, None)?,
+        nix::sys::wait::WaitStatus::Exited(parent, 0)
+    );
+
+    Ok(())
+}
+
+/// With `detach-on-fork off`, the child is held stopped, still traced, until
+/// the debugger is done.
+#[test]
+fn detach_on_fork_off_holds_the_child() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    SetCommand::parse(&["detach-on-fork".to_string(), "off".to_string()])?.execute(process)?;
+    run_to_fixture_line(process, &["fork"], FORKED_LINE)?;
+    let pid = process.expect_pid();
+    assert!(process.breakpoint_hit().is_some());
+
+    let children = std::fs::read_to_string(format!("/proc/{pid}/task/{pid}/children"))?;
+    let child: nix::unistd::Pid = jdb::options::parse_pid(children.trim())?;
+    assert_eq!(thread_proc_state(child, child)?, 't');
+    let status = std::fs::read_to_string(format!("/proc/{child}/status"))?;
+    // ptrace is per thread, so the tracer is this test thread
+    let tracer = format!("TracerPid:\t{}\n", nix::unistd::gettid());
+    assert!(status.contains(&tracer), "{status}");
+
+    // quitting kills the held child along with the parent
+    process.destroy()?;
+    let state = thread_proc_state(child, child).unwrap_or('X');
+    assert!(matches!(state, 'X' | 'Z'), "{state}");
+
+    Ok(())
+}
+
+/// After an exec, the new program's symbols are loaded, and breakpoints are
+/// resolved against it.
+#[test]
+fn exec_reloads_symbols() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    // in `execed`, which is only called after the exec
+    run_to_fixture_line(process, &["exec"], 105)?;
+    let pid = process.expect_pid();
+    // the new program stops itself, like the old one
+    assert!(
+        matches!(
+            process.last_stop(),
+            Some(StopReason::Stopped {
+                signal: nix::sys::signal::Signal::SIGSTOP,
+                ..
+            })
+        ),
+        "{:?}",
+        process.last_stop()
+    );
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert_eq!(process.expect_pid(), pid);
+    let Some(StopReason::Stopped {
+        trap: Some(TrapType::SoftwareBreak),
+        pc,
+        ..
+    }) = process.last_stop().cloned()
+    else {
+        panic!("expected a breakpoint, got {:?}", process.last_stop());
+    };
+    assert!(process.breakpoint_hit().is_some());
+    assert_eq!(process.breakpoint_sites().count(), 1);
+    let location = process.source_location(pc).expect("line info");
+    assert_eq!(location.line, 105);
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(matches!(process.last_stop(), Some(StopReason::Exited(0))));
+
+    Ok(())
+}
+
+/// By default, `SIGALRM` is passed to the inferior without stopping, while
+/// `SIGUSR1` stops, and is passed on when the inferior is continued.
+#[test]
+fn signals_stop_or_pass_silently_by_default() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&["signals"])?;
+    let process = process_guard.get_mut();
+
+    // to the fixture's SIGSTOP
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(
+        matches!(
+            process.last_stop(),
+            Some(StopReason::Stopped {
+                signal: nix::sys::signal::Signal::SIGUSR1,
+                ..
+            })
+        ),
+        "{:?}",
+        process.last_stop()
+    );
+
+    // the fixture exits with the number of signals its handlers saw
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(
+        matches!(process.last_stop(), Some(StopReason::Exited(2))),
+        "{:?}",
+        process.last_stop()
+    );
+
+    Ok(())
+}
+
+/// `handle` changes whether a signal stops the inferior, and whether it's
+/// passed on to it.
+#[test]
+fn handle_changes_signal_dispositions() -> Result<()> {
+    let mut process_guard = fixtures::fixture_process();
+    let process = process_guard.get_mut();
+
+    let handle = |args: &str| {
+        HandleCommand::parse(
+            &args
+                .split_whitespace()
+                .map(String::from)
+                .collect::<Vec<_>>(),
+        )
+    };
+    let message = handle("SIGALRM stop")?.execute(process)?;
+    assert_eq!(
+        message,
+        "Signal    Stop Print Pass\nSIGALRM   Yes  Yes   Yes"
+    );
+    let message = handle("usr1 nopass")?.execute(process)?;
+    assert_eq!(
+        message,
+        "Signal    Stop Print Pass\nSIGUSR1   Yes  Yes   No"
+    );
+    assert!(handle("SIGUSR1 sometimes").is_err());
+    assert!(handle("SIGBOGUS stop").is_err());
+    assert!(handle("SIGTRAP nostop")?.execute(process).is_err());
+
+    let message = InfoCommand::parse(&["signals".to_string()])?.execute(process)?;
+    assert!(
+        message.contains("\nSIGCHLD   No   No    Yes\n"),
+        "{message}"
+    );
+
+    process.attach(vec!["signals".to_string()])?;
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    for expected in [
+        nix::sys::signal::Signal::SIGALRM,
+        nix::sys::signal::Signal::SIGUSR1,
+    ] {
+        process.resume()?;
+        process.wait_on_signal()?;
+        assert!(
+            matches!(
+                process.last_stop(),
+                Some(StopReason::Stopped { signal, .. }) if *signal == expected
+            ),
+            "{:?}",
+            process.last_stop()
+        );
+    }
+
+    // only the SIGALRM made it to the fixture's handlers
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(
+        matches!(process.last_stop(), Some(StopReason::Exited(1))),
+        "{:?}",
+        process.last_stop()
+    );
+
+    Ok(())
+}
+
+/// A syscall catchpoint stops on the way into the syscall, with its arguments
+/// decoded, and again on the way out, with its return value.
+#[test]
+fn catch_syscall_reports_entry_and_exit() -> Result<()> {
+    let mut process_guard = fixtures::launch_fixture(&["syscalls"])?;
+    let process = process_guard.get_mut();
+
+    // to the fixture's SIGSTOP
+    process.resume()?;
+    process.wait_on_signal()?;
+
+    let openat = parse_syscall("openat")?;
+    assert_eq!(openat, 257);
+    assert!(parse_syscall("bogus").is_err());
+    let message = process.breakpoint_command(BreakpointCommand::Catch(vec![openat]))?;
+    let id = process.catchpoints().next().expect("just created").id();
+    assert_eq!(message, format!("Catchpoint {id} (syscall 'openat' [257])"));
+    let rows = breakpoint_rows(process);
+    assert_eq!(rows[0].kind, "catchpoint");
+    assert_eq!(rows[0].what, "syscall \"openat\"");
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(
+        matches!(
+            process.last_stop(),
+            Some(StopReason::Stopped {
+                trap: Some(TrapType::SyscallEntry),
+                ..
+            })
+        ),
+        "{:?}",
+        process.last_stop()
+    );
+    assert_eq!(process.catchpoint_hit().map(|c| c.id()), Some(id));
+    let syscall = process.last_syscall().expect("stopped at a syscall");
+    assert!(
+        syscall
+            .to_string()
+            .starts_with("call to openat(AT_FDCWD, \"/nonexistent/jdb-fixture.conf\", "),
+        "{syscall}"
+    );
+
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(
+        matches!(
+            process.last_stop(),
+            Some(StopReason::Stopped {
+                trap: Some(TrapType::SyscallExit),
+                ..
+            })
+        ),
+        "{:?}",
+        process.last_stop()
+    );
+    // the arguments are the ones the syscall was made with
+    let syscall = process.last_syscall().expect("stopped at a syscall");
+    let text = syscall.to_string();
+    assert!(
+        text.starts_with("returned from openat(AT_FDCWD, \"/nonexistent/jdb-fixture.conf\", "),
+        "{syscall}"
+    );
+    assert!(
+        text.ends_with(") = -1 ENOENT (No such file or directory)"),
+        "{syscall}"
+    );
+    assert_eq!(
+        process.catchpoints().next().unwrap().policy().hit_count(),
+        2
+    );
+
+    // without the catchpoint, the fixture runs to the end
+    process.breakpoint_command(BreakpointCommand::Delete(id))?;
+    process.resume()?;
+    process.wait_on_signal()?;
+    assert!(
+        matches!(process.last_stop(), Some(StopReason::Exited(0))),
+        "{:?}",
+        process.last_stop()
+    );
+
+    Ok(())
+}
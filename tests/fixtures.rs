@@ -2,6 +2,11 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::sync::OnceLock;
 
+use anyhow::Result;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use jdb::options::Options;
+use jdb::process::Process;
+
 static HELLO_BINARY: OnceLock<PathBuf> = OnceLock::new();
 
 pub fn hello_fixture_path() -> PathBuf {
@@ -34,3 +39,63 @@ pub fn hello_fixture_path() -> PathBuf {
         })
         .clone()
 }
+
+/// Wrapper around the `Process` instance. The key insight is implementing the
+/// `Drop` trait which will guarantee the proper shutdown of the `Process`.
+pub struct ProcessGuard {
+    process: Option<Process>,
+    shutdown_tx: Option<Sender<()>>,
+    /// Kept open for the inferior's output, which the test doesn't read.
+    _inferior_rx: Option<Receiver<String>>,
+}
+
+impl ProcessGuard {
+    pub fn new(process: Process, shutdown_tx: Sender<()>) -> Self {
+        Self {
+            process: Some(process),
+            shutdown_tx: Some(shutdown_tx),
+            _inferior_rx: None,
+        }
+    }
+
+    pub fn get_mut(&mut self) -> &mut Process {
+        self.process
+            .as_mut()
+            .expect("process should still be available")
+    }
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        if let Some(mut process) = self.process.take() {
+            let _ = process.destroy();
+        }
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+/// A `Process` for the fixture, not yet started, so the test can set things up
+/// before it runs.
+pub fn fixture_process() -> ProcessGuard {
+    let (inferior_tx, inferior_rx) = unbounded();
+    let (shutdown_tx, shutdown_rx) = unbounded();
+    let options = Options {
+        executable: hello_fixture_path(),
+        pid: None,
+    };
+    let mut guard = ProcessGuard::new(Process::new(options, inferior_tx, shutdown_rx), shutdown_tx);
+    guard._inferior_rx = Some(inferior_rx);
+    guard
+}
+
+/// Launch the fixture with the given arguments. It's stopped at the exec, before
+/// it raises its own `SIGSTOP`.
+pub fn launch_fixture(args: &[&str]) -> Result<ProcessGuard> {
+    let mut guard = fixture_process();
+    guard
+        .get_mut()
+        .attach(args.iter().map(|arg| arg.to_string()).collect())?;
+    Ok(guard)
+}
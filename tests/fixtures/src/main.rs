@@ -13,5 +13,118 @@ fn main() {
     // After continuing, run briefly and exit.
     for _ in 0..3 {
         thread::sleep(Duration::from_millis(10));
+        // give watchpoints something to see
+        unsafe {
+            let counter = std::ptr::addr_of_mut!(WATCHED_COUNTER);
+            counter.write_volatile(counter.read_volatile() + 1);
+        }
     }
+
+    if std::env::args().any(|arg| arg == "threads") {
+        run_threads();
+    }
+    if std::env::args().any(|arg| arg == "fork") {
+        run_fork();
+    }
+    if std::env::args().any(|arg| arg == "spawn") {
+        run_spawn();
+    }
+    if std::env::args().any(|arg| arg == "exec") {
+        run_exec();
+    }
+    if std::env::args().any(|arg| arg == "signals") {
+        run_signals();
+    }
+    if std::env::args().any(|arg| arg == "syscalls") {
+        run_syscalls();
+    }
+}
+
+static WORKER_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Run a couple of threads, for the multi-threading tests.
+fn run_threads() {
+    let workers: Vec<_> = (0..2).map(|_| thread::spawn(worker)).collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
+
+fn worker() {
+    for _ in 0..2 {
+        WORKER_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[no_mangle]
+static mut WATCHED_COUNTER: u64 = 0;
+
+/// Fork a child that calls `forked` too, for the follow-fork tests. The
+/// child's exit status is passed on, so a breakpoint left in it is noticed.
+fn run_fork() {
+    let child = unsafe { libc::fork() };
+    forked();
+    if child == 0 {
+        unsafe { libc::_exit(0) };
+    }
+    let mut status = 0;
+    unsafe { libc::waitpid(child, &mut status, 0) };
+    if status != 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Start another program (with `posix_spawn`, which `vfork`s), then call
+/// `forked`.
+fn run_spawn() {
+    let status = std::process::Command::new("true").status().unwrap();
+    assert!(status.success());
+    forked();
+}
+
+#[inline(never)]
+fn forked() {
+    WORKER_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Exec this program again, which calls `execed` once it has.
+fn run_exec() {
+    if std::env::args().any(|arg| arg == "execed") {
+        execed();
+        return;
+    }
+    let mut command = std::process::Command::new(std::env::current_exe().unwrap());
+    command.args(["exec", "execed"]);
+    let error = std::os::unix::process::CommandExt::exec(&mut command);
+    panic!("exec failed: {error}");
+}
+
+#[inline(never)]
+fn execed() {
+    WORKER_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+}
+
+static SIGNALS_HANDLED: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
+
+extern "C" fn count_signal(_: libc::c_int) {
+    SIGNALS_HANDLED.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Raise `SIGALRM` and then `SIGUSR1`, and exit with the number of them that
+/// were handled, so the signal tests can tell which were passed on.
+fn run_signals() {
+    unsafe {
+        libc::signal(libc::SIGALRM, count_signal as libc::sighandler_t);
+        libc::signal(libc::SIGUSR1, count_signal as libc::sighandler_t);
+        libc::raise(libc::SIGALRM);
+        libc::raise(libc::SIGUSR1);
+    }
+    std::process::exit(SIGNALS_HANDLED.load(std::sync::atomic::Ordering::SeqCst));
+}
+
+/// Try to open a config file that doesn't exist, for the catchpoint tests.
+fn run_syscalls() {
+    let error = std::fs::File::open("/nonexistent/jdb-fixture.conf").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
 }